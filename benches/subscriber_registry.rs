@@ -0,0 +1,237 @@
+//! Latency of the feed's L1 publishing path (sanity checks, statistics, last value cache,
+//! registry lookup and conflation into every subscriber's mailbox) against a baseline that
+//! fans out under a lock on the whole subscriber map, with several feeds publishing at
+//! once, first on their own and then while another task keeps subscribing and
+//! unsubscribing. Subscription changes must not show up in the publishing tail.
+//!
+//! cargo bench --features bench --bench subscriber_registry
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sniper_algorithm::common_types::instrument_id::{self, InstrumentId};
+use sniper_algorithm::common_types::tracked_sender::TrackedSender;
+use sniper_algorithm::config::SanityConfig;
+use sniper_algorithm::feed::feed_actor::L1Publisher;
+use sniper_algorithm::feed::messages::l1_data::L1Data;
+use sniper_algorithm::feed::messages::messages::FeedUpdate;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+
+type AlgoIds = Arc<[String]>;
+type LockedMap = HashMap<String, HashMap<String, (TrackedSender<FeedUpdate>, AlgoIds)>>;
+
+const INSTRUMENTS: usize = 50;
+const PUBLISHERS: usize = 4;
+const PUBLISHES: usize = 2_000;
+const CHURN_INTERVAL: Duration = Duration::from_millis(1);
+const SUBSCRIBER_COUNTS: [usize; 3] = [10, 100, 1_000];
+const CHANNEL_SIZE: usize = 1000;
+
+/// Interned like the feeds do, so publishing does not copy the symbol.
+fn instrument(index: usize) -> InstrumentId {
+    instrument_id::intern(&format!("instrument{}", index % INSTRUMENTS))
+}
+
+#[derive(Clone)]
+enum Registry {
+    /// Every publish and every subscription change locks the whole map.
+    Locked(Arc<Mutex<LockedMap>>),
+    Feed(Arc<L1Publisher>),
+}
+
+impl Registry {
+    fn name(&self, with_churn: bool) -> &'static str {
+        match (self, with_churn) {
+            (Registry::Locked(_), false) => "locked",
+            (Registry::Locked(_), true) => "locked+churn",
+            (Registry::Feed(_), false) => "feed",
+            (Registry::Feed(_), true) => "feed+churn",
+        }
+    }
+
+    async fn subscribe(
+        &self,
+        instrument: &str,
+        subscriber: &TrackedSender<FeedUpdate>,
+        algo_id: &str,
+    ) {
+        match self {
+            Registry::Locked(map) => {
+                let mut map = map.lock().await;
+                let senders = map.entry(instrument.to_owned()).or_default();
+                let algo_ids = senders
+                    .get(&subscriber.receiver_id)
+                    .map(|(_, algo_ids)| algo_ids.to_vec())
+                    .unwrap_or_default();
+                let algo_ids = algo_ids.into_iter().chain([algo_id.to_owned()]).collect();
+                senders.insert(
+                    subscriber.receiver_id.clone(),
+                    (subscriber.clone(), algo_ids),
+                );
+            }
+            Registry::Feed(publisher) => publisher.subscribe(instrument, subscriber, algo_id).await,
+        }
+    }
+
+    async fn unsubscribe(
+        &self,
+        instrument: &str,
+        subscriber: &TrackedSender<FeedUpdate>,
+        algo_id: &str,
+    ) {
+        match self {
+            Registry::Locked(map) => {
+                let mut map = map.lock().await;
+                if let Some(senders) = map.get_mut(instrument) {
+                    if let Some((_, algo_ids)) = senders.get(&subscriber.receiver_id) {
+                        let algo_ids: AlgoIds = algo_ids
+                            .iter()
+                            .filter(|id| *id != algo_id)
+                            .cloned()
+                            .collect();
+                        if algo_ids.is_empty() {
+                            senders.remove(&subscriber.receiver_id);
+                        } else {
+                            senders.insert(
+                                subscriber.receiver_id.clone(),
+                                (subscriber.clone(), algo_ids),
+                            );
+                        }
+                    }
+                }
+            }
+            Registry::Feed(publisher) => {
+                publisher.unsubscribe(instrument, subscriber, algo_id).await
+            }
+        }
+    }
+
+    async fn publish(&self, l1_data: L1Data) {
+        match self {
+            Registry::Locked(map) => {
+                let map = map.lock().await;
+                if let Some(senders) = map.get(&*l1_data.symbol) {
+                    let l1_data = Arc::new(l1_data);
+                    for (tracked_sender, algo_ids) in senders.values() {
+                        let _ = tracked_sender
+                            .sender
+                            .try_send(FeedUpdate::L1Update(algo_ids.clone(), l1_data.clone()));
+                    }
+                }
+            }
+            Registry::Feed(publisher) => publisher.publish(l1_data, Utc::now()),
+        }
+    }
+}
+
+/// Subscribes every context to every instrument. Contexts drain their channels like algo
+/// contexts do, so mailboxes do not fill up.
+async fn subscribe_all(registry: &Registry, subscribers: usize) {
+    for context in 0..subscribers {
+        let (sender, mut receiver) = mpsc::channel(CHANNEL_SIZE);
+        let subscriber = TrackedSender::new(sender, format!("context-{context}"));
+        tokio::spawn(async move { while receiver.recv().await.is_some() {} });
+
+        for index in 0..INSTRUMENTS {
+            registry
+                .subscribe(&instrument(index), &subscriber, &format!("algo-{context}"))
+                .await;
+        }
+    }
+}
+
+/// Unsubscribes and resubscribes one algo, the way the feed actor edits the registry.
+async fn churn(registry: Registry, running: Arc<AtomicBool>) {
+    let (sender, mut receiver) = mpsc::channel(CHANNEL_SIZE);
+    let subscriber = TrackedSender::new(sender, "churning-context".to_owned());
+    tokio::spawn(async move { while receiver.recv().await.is_some() {} });
+
+    let mut index = 0;
+    while running.load(Ordering::Relaxed) {
+        registry
+            .subscribe(&instrument(index), &subscriber, "churning-algo")
+            .await;
+        registry
+            .unsubscribe(&instrument(index), &subscriber, "churning-algo")
+            .await;
+        index += 1;
+        tokio::time::sleep(CHURN_INTERVAL).await;
+    }
+}
+
+/// Runs the publishers, each publishing L1 for its own instruments, and collects the
+/// latency of every publish.
+async fn bench(registry: Registry, with_churn: bool) -> Vec<Duration> {
+    let running = Arc::new(AtomicBool::new(true));
+    let churn = with_churn.then(|| tokio::spawn(churn(registry.clone(), running.clone())));
+
+    let publishers: Vec<_> = (0..PUBLISHERS)
+        .map(|publisher_index| {
+            let registry = registry.clone();
+            tokio::spawn(async move {
+                let mut latencies = Vec::with_capacity(PUBLISHES);
+                for publish_index in 0..PUBLISHES {
+                    let l1_data = L1Data::new(
+                        instrument(publish_index * PUBLISHERS + publisher_index),
+                        Decimal::ONE,
+                        Decimal::from(100 + publish_index % 2),
+                        Decimal::ONE,
+                        Decimal::from(102),
+                    );
+
+                    let started = Instant::now();
+                    registry.publish(l1_data).await;
+                    latencies.push(started.elapsed());
+
+                    if publish_index % 100 == 0 {
+                        tokio::task::yield_now().await;
+                    }
+                }
+                latencies
+            })
+        })
+        .collect();
+
+    let mut latencies = Vec::with_capacity(PUBLISHERS * PUBLISHES);
+    for publisher in publishers {
+        latencies.extend(publisher.await.unwrap());
+    }
+
+    running.store(false, Ordering::Relaxed);
+    if let Some(churn) = churn {
+        churn.await.unwrap();
+    }
+    latencies
+}
+
+fn report(name: &str, subscribers: usize, mut latencies: Vec<Duration>) {
+    latencies.sort_unstable();
+    let percentile = |p: f64| latencies[((latencies.len() - 1) as f64 * p) as usize];
+
+    println!(
+        "{name:<12} subscribers={subscribers:<5} p50={:?} p99={:?} p99.9={:?} max={:?}",
+        percentile(0.5),
+        percentile(0.99),
+        percentile(0.999),
+        latencies[latencies.len() - 1],
+    );
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+async fn main() {
+    for subscribers in SUBSCRIBER_COUNTS {
+        for registry in [
+            Registry::Locked(Arc::default()),
+            Registry::Feed(Arc::new(L1Publisher::new(SanityConfig::default()))),
+        ] {
+            subscribe_all(&registry, subscribers).await;
+            for with_churn in [false, true] {
+                let name = registry.name(with_churn);
+                report(name, subscribers, bench(registry.clone(), with_churn).await);
+            }
+        }
+    }
+}
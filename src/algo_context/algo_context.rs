@@ -10,7 +10,7 @@ use crate::market::market_handle::MarketHandle;
 use crate::market::market_service::MarketService;
 use crate::market::messages::market_responses::MarketResponses;
 use probe::probe_lazy;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
@@ -92,23 +92,104 @@ impl AlgoContext {
             FeedUpdate::L1Update(algo_ids, l1_data) => {
                 probe!(start_processing_l1_update);
                 algo_ids
-                    .into_par_iter()
-                    .filter_map(|algo_id| self.algorithams.get(&algo_id).cloned())
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
                     .for_each(move |algo| {
                         let mut algo = algo.lock().unwrap();
                         algo.handle_l1(&l1_data);
                     });
                 probe!(finish_processing_l1_update);
             }
+            FeedUpdate::ConsolidatedL1(algo_ids, consolidated_l1) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.handle_consolidated_l1(&consolidated_l1);
+                    });
+            }
             FeedUpdate::L2Update(algo_ids, l2_data) => {
                 algo_ids
-                    .into_par_iter()
-                    .filter_map(|algo_id| self.algorithams.get(&algo_id).cloned())
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
                     .for_each(move |algo| {
                         let mut algo = algo.lock().unwrap();
                         algo.handle_l2(&l2_data);
                     });
             }
+            FeedUpdate::L2Delta(algo_ids, l2_delta) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.handle_l2_delta(&l2_delta);
+                    });
+            }
+            FeedUpdate::Trade(algo_ids, trade_data) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.handle_trade(&trade_data);
+                    });
+            }
+            FeedUpdate::Candle(algo_ids, candle) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.handle_candle(&candle);
+                    });
+            }
+            FeedUpdate::Status(algo_ids, feed_status) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.handle_feed_status(&feed_status);
+                    });
+            }
+            FeedUpdate::Snapshot(algo_ids, snapshot) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.handle_snapshot(&snapshot);
+                    });
+            }
+            FeedUpdate::Analytics(algo_ids, analytics) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.handle_analytics(&analytics);
+                    });
+            }
+            FeedUpdate::Ticker(algo_ids, ticker) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.handle_ticker(&ticker);
+                    });
+            }
+            FeedUpdate::History(algo_ids, history) => {
+                algo_ids
+                    .par_iter()
+                    .filter_map(|algo_id| self.algorithams.get(algo_id).cloned())
+                    .for_each(move |algo| {
+                        let mut algo = algo.lock().unwrap();
+                        algo.warm_up(&history);
+                    });
+            }
         }
     }
 
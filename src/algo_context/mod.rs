@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod algo_context;
 pub mod algo_service;
 pub mod messages;
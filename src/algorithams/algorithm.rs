@@ -1,10 +1,25 @@
 use crate::{
-    feed::messages::{l1_data::L1Data, l2_data::L2Data},
+    feed::messages::{
+        analytics::Analytics, candle::Candle, consolidated_l1::ConsolidatedL1,
+        feed_snapshot::FeedSnapshot, feed_status::FeedStatus, history::History, l1_data::L1Data,
+        l2_data::L2Data, l2_delta::L2Delta, ticker::Ticker, trade_data::TradeData,
+    },
     market::messages::market_responses::MarketResponses,
 };
 
 pub trait Algorithm {
     fn handle_l1(&mut self, l1_data: &L1Data);
+    fn handle_consolidated_l1(&mut self, consolidated_l1: &ConsolidatedL1);
     fn handle_l2(&mut self, l2_data: &L2Data);
+    fn handle_l2_delta(&mut self, l2_delta: &L2Delta);
+    fn handle_trade(&mut self, trade_data: &TradeData);
+    fn handle_candle(&mut self, candle: &Candle);
+    fn handle_feed_status(&mut self, feed_status: &FeedStatus);
+    fn handle_snapshot(&mut self, snapshot: &FeedSnapshot);
+    fn handle_analytics(&mut self, analytics: &Analytics);
+    fn handle_ticker(&mut self, ticker: &Ticker);
+    /// Receives the history requested through `FeedService::request_history`, before any
+    /// live data the algorithm subscribes to afterwards.
+    fn warm_up(&mut self, history: &History);
     fn handle_market_reponse(&mut self, market_response: MarketResponses);
 }
@@ -1,12 +1,22 @@
 use crate::algorithams::algorithm::Algorithm;
 use crate::common_types::order_types::OrderType;
 use crate::common_types::side::Side;
+use crate::common_types::symbol_status::SymbolStatus;
 use crate::common_types::time_in_force::TIF;
 use crate::config::AlgoParameters;
 use crate::feed::feed_service::FeedService;
+use crate::feed::messages::analytics::Analytics;
+use crate::feed::messages::candle::Candle;
+use crate::feed::messages::consolidated_l1::ConsolidatedL1;
+use crate::feed::messages::feed_snapshot::FeedSnapshot;
+use crate::feed::messages::feed_status::{FeedState, FeedStatus, FeedStream};
+use crate::feed::messages::history::History;
 use crate::feed::messages::l1_data::L1Data;
 use crate::feed::messages::l2_data::L2Data;
+use crate::feed::messages::l2_delta::L2Delta;
 use crate::feed::messages::symbol_information::SymbolInformation;
+use crate::feed::messages::ticker::Ticker;
+use crate::feed::messages::trade_data::TradeData;
 use crate::logging::algo_report::AlgoPdfLogger;
 use crate::market::market_service::MarketService;
 use crate::market::messages::execution_type::ExecutionType;
@@ -15,11 +25,16 @@ use crate::{log_debug, log_error, log_info, logging, report};
 use core::fmt;
 use logging::algo_logger::AlgoLogger;
 use rust_decimal::{prelude::Zero, Decimal};
+use std::collections::HashSet;
+
+/// The feeds the sniper trades on. It places no orders while any of them is not live.
+const REQUIRED_FEEDS: [FeedStream; 1] = [FeedStream::L1];
 
 #[derive(Debug)]
 enum State {
     New,
     WaitingForMarketConditions,
+    TradingPaused,
     PendingCreate,
     Working,
     Done,
@@ -30,6 +45,7 @@ impl fmt::Display for State {
         let state_name = match self {
             State::New => "New",
             State::WaitingForMarketConditions => "WaitingForMarketConditions",
+            State::TradingPaused => "TradingPaused",
             State::PendingCreate => "PendingCreate",
             State::Working => "Working",
             State::Done => "Done",
@@ -49,11 +65,19 @@ enum Event {
         max_price: Option<Decimal>,
         tick_size: Option<Decimal>,
         min_amount: Option<Decimal>,
+        status: Option<SymbolStatus>,
+    },
+    SymbolStatus {
+        status: SymbolStatus,
     },
     FeedUpdate {
         quantity: Decimal,
         price: Decimal,
     },
+    FeedStatus {
+        stream: FeedStream,
+        state: FeedState,
+    },
     CreateOrderAck {
         order_id: String,
         symbol: String,
@@ -107,6 +131,17 @@ enum Event {
         cumulative_quantity: Decimal,
         leaves_quantity: Decimal,
     },
+    OrderPartiallyRejected {
+        order_id: String,
+        symbol: String,
+        execution_status: ExecutionType,
+        quantity: Decimal,
+        side: Side,
+        rejected_quantity: Decimal,
+        cumulative_quantity: Decimal,
+        leaves_quantity: Decimal,
+        rejection_reason: String,
+    },
     OrderCanceled {
         order_id: String,
         symbol: String,
@@ -128,6 +163,7 @@ pub struct SniperAlgo {
     remaining_quantity: Decimal,
     executed_quantity: Decimal,
     exposed_quantity: Decimal,
+    stale_feeds: HashSet<FeedStream>,
     logger: AlgoLogger,
     pdf_report: AlgoPdfLogger,
 }
@@ -135,6 +171,18 @@ pub struct SniperAlgo {
 impl Algorithm for SniperAlgo {
     fn handle_l1(&mut self, l1_data: &L1Data) {
         log_debug!(self.logger, "handle_l1", "Handling L1 update {}", l1_data);
+
+        if let Some(violation) = &l1_data.violation {
+            log_info!(
+                self.logger,
+                "handle_l1",
+                "Disregarding the L1 update {} as it failed the feed sanity checks ({}).",
+                l1_data,
+                violation
+            );
+            return;
+        }
+
         match self.algo_parameters.side {
             Side::Buy => {
                 self.on_event(Event::FeedUpdate {
@@ -155,6 +203,74 @@ impl Algorithm for SniperAlgo {
         log_debug!(self.logger, "handle_l2", "Handling L2 update {}", l2_data);
     }
 
+    fn handle_consolidated_l1(&mut self, consolidated_l1: &ConsolidatedL1) {
+        log_debug!(
+            self.logger,
+            "handle_consolidated_l1",
+            "Handling consolidated L1 {}",
+            consolidated_l1
+        );
+    }
+
+    fn handle_l2_delta(&mut self, l2_delta: &L2Delta) {
+        log_debug!(
+            self.logger,
+            "handle_l2_delta",
+            "Handling L2 delta {}",
+            l2_delta
+        );
+    }
+
+    fn handle_trade(&mut self, trade_data: &TradeData) {
+        log_debug!(self.logger, "handle_trade", "Handling trade {}", trade_data);
+    }
+
+    fn handle_candle(&mut self, candle: &Candle) {
+        log_debug!(self.logger, "handle_candle", "Handling candle {}", candle);
+    }
+
+    fn handle_feed_status(&mut self, feed_status: &FeedStatus) {
+        log_debug!(
+            self.logger,
+            "handle_feed_status",
+            "Handling feed status {}",
+            feed_status
+        );
+        self.on_event(Event::FeedStatus {
+            stream: feed_status.stream,
+            state: feed_status.state,
+        });
+    }
+
+    fn handle_snapshot(&mut self, snapshot: &FeedSnapshot) {
+        log_debug!(
+            self.logger,
+            "handle_snapshot",
+            "Handling snapshot {}",
+            snapshot
+        );
+        if let Some(l1_data) = &snapshot.l1 {
+            self.handle_l1(l1_data);
+        }
+    }
+
+    fn handle_analytics(&mut self, analytics: &Analytics) {
+        log_debug!(
+            self.logger,
+            "handle_analytics",
+            "Handling analytics {}",
+            analytics
+        );
+    }
+
+    fn handle_ticker(&mut self, ticker: &Ticker) {
+        log_debug!(self.logger, "handle_ticker", "Handling ticker {}", ticker);
+    }
+
+    fn warm_up(&mut self, history: &History) {
+        log_debug!(self.logger, "warm_up", "Handling history {}", history);
+    }
+
     fn handle_market_reponse(&mut self, market_response: MarketResponses) {
         log_debug!(
             self.logger,
@@ -172,6 +288,7 @@ impl Algorithm for SniperAlgo {
                 max_price,
                 tick_size,
                 min_amount,
+                status,
             } => {
                 self.on_event(Event::SymbolInformation {
                     min_quantity,
@@ -181,9 +298,16 @@ impl Algorithm for SniperAlgo {
                     max_price,
                     tick_size,
                     min_amount,
+                    status,
                 });
             }
 
+            MarketResponses::SymbolStatusChanged {
+                algo_id: _,
+                symbol: _,
+                status,
+            } => self.on_event(Event::SymbolStatus { status }),
+
             MarketResponses::CreateOrderAck {
                 order_id,
                 algo_id: _,
@@ -290,6 +414,28 @@ impl Algorithm for SniperAlgo {
                 rejection_reason,
                 time_in_force,
             }),
+            MarketResponses::OrderPartiallyRejected {
+                order_id,
+                algo_id: _,
+                symbol,
+                execution_status,
+                quantity,
+                side,
+                rejected_quantity,
+                cumulative_quantity,
+                leaves_quantity,
+                rejection_reason,
+            } => self.on_event(Event::OrderPartiallyRejected {
+                order_id,
+                symbol,
+                execution_status,
+                quantity,
+                side,
+                rejected_quantity,
+                cumulative_quantity,
+                leaves_quantity,
+                rejection_reason,
+            }),
             MarketResponses::OrderCanceled {
                 order_id,
                 algo_id: _,
@@ -310,6 +456,7 @@ impl Algorithm for SniperAlgo {
                 cumulative_quantity,
                 leaves_quantity,
             }),
+            MarketResponses::OcoListStatus { .. } | MarketResponses::OcoOrderRejected { .. } => {}
         }
     }
 }
@@ -318,12 +465,15 @@ impl fmt::Display for Event {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let event_name = match self {
             Event::SymbolInformation { .. } => "SymbolInformation",
+            Event::SymbolStatus { .. } => "SymbolStatus",
             Event::FeedUpdate { .. } => "FeedUpdate",
+            Event::FeedStatus { .. } => "FeedStatus",
             Event::CreateOrderAck { .. } => "CreateOrderAck",
             Event::CreateOrderRej { .. } => "CreateOrderRej",
             Event::OrderPartiallyFilled { .. } => "OrderPartiallyFilled",
             Event::OrderFullyFilled { .. } => "OrderFullyFilled",
             Event::OrderExpired { .. } => "OrderExpired",
+            Event::OrderPartiallyRejected { .. } => "OrderPartiallyRejected",
             Event::OrderCanceled { .. } => "OrderCanceled",
         };
         write!(f, "{}", event_name)
@@ -340,7 +490,7 @@ impl SniperAlgo {
         market_sevice.get_symbol_info(algo_parameters.make_symbol());
         let mut pdf_report = AlgoPdfLogger::new(
             &algo_parameters.algo_id,
-            &algo_parameters.algo_type.to_string(),
+            algo_parameters.algo_type.to_string(),
         );
 
         report!(
@@ -356,10 +506,10 @@ impl SniperAlgo {
 
         Self {
             logger: AlgoLogger::new(
-                &algo_parameters.algo_type.to_string(),
+                algo_parameters.algo_type.to_string(),
                 &algo_parameters.algo_id,
             ),
-            algo_parameters: algo_parameters,
+            algo_parameters,
             market_sevice,
             feed_service,
             state: State::New,
@@ -367,6 +517,7 @@ impl SniperAlgo {
             remaining_quantity,
             executed_quantity: Decimal::zero(),
             exposed_quantity: Decimal::zero(),
+            stale_feeds: HashSet::new(),
             pdf_report,
         }
     }
@@ -392,8 +543,95 @@ impl SniperAlgo {
         }
     }
 
+    fn pause_trading(&mut self) {
+        let status = self
+            .symbol_information
+            .status
+            .clone()
+            .unwrap_or(SymbolStatus::Unknown);
+
+        log_info!(
+            self.logger,
+            "transitionToTradingPaused",
+            "Symbol {} is in trading status {}, so no orders will be \
+            placed until trading resumes.",
+            self.algo_parameters.make_symbol(),
+            status
+        );
+
+        report!(
+            self.pdf_report,
+            "The exchange reported trading status {} for Symbol {}. \
+            The algorithm is paused with a remaining quantity of {} until trading resumes.",
+            status,
+            self.algo_parameters.make_symbol(),
+            self.remaining_quantity
+        );
+
+        self.state = State::TradingPaused;
+    }
+
+    fn feed_stale(&self) -> bool {
+        REQUIRED_FEEDS
+            .iter()
+            .any(|stream| self.stale_feeds.contains(stream))
+    }
+
+    fn on_feed_status(&mut self, stream: FeedStream, state: FeedState) {
+        if state == FeedState::Gap {
+            // The feed itself is healthy and the next tick is current again.
+            log_error!(
+                self.logger,
+                "FeedStatusEvent",
+                "Missed market data updates for Symbol {}.",
+                self.algo_parameters.make_symbol()
+            );
+            return;
+        }
+
+        let was_stale = self.feed_stale();
+        if state == FeedState::Recovered {
+            self.stale_feeds.remove(&stream);
+        } else {
+            self.stale_feeds.insert(stream);
+        }
+
+        log_info!(
+            self.logger,
+            "FeedStatusEvent",
+            "{} market data for Symbol {} is {}.",
+            stream,
+            self.algo_parameters.make_symbol(),
+            state
+        );
+
+        match (was_stale, self.feed_stale()) {
+            (false, true) => {
+                report!(
+                    self.pdf_report,
+                    "{} market data for Symbol {} became {}. No orders will be placed until it recovers.",
+                    stream,
+                    self.algo_parameters.make_symbol(),
+                    state
+                );
+            }
+            (true, false) => {
+                report!(
+                    self.pdf_report,
+                    "Market data for Symbol {} recovered.",
+                    self.algo_parameters.make_symbol()
+                );
+            }
+            _ => {}
+        }
+    }
+
     fn on_event(&mut self, event: Event) {
         match (&self.state, event) {
+            (State::Done, Event::FeedStatus { .. }) => {}
+
+            (_, Event::FeedStatus { stream, state }) => self.on_feed_status(stream, state),
+
             (
                 State::New,
                 Event::SymbolInformation {
@@ -404,6 +642,7 @@ impl SniperAlgo {
                     max_price,
                     tick_size,
                     min_amount,
+                    status,
                 },
             ) => {
                 self.symbol_information.set_values(
@@ -415,6 +654,7 @@ impl SniperAlgo {
                     tick_size,
                     min_amount,
                 );
+                self.symbol_information.set_status(status);
 
                 if let Some(min_quantity) = self.symbol_information.min_quantity {
                     if self.algo_parameters.quantity < min_quantity {
@@ -440,12 +680,19 @@ impl SniperAlgo {
 
                         self.pdf_report.write_to_pdf().unwrap();
                         self.state = State::Done;
+                        self.market_sevice
+                            .stop_watching_symbol(self.algo_parameters.make_symbol());
                         return;
                     }
                 }
                 self.feed_service
                     .subscribe_to_l1(&self.algo_parameters.base, &self.algo_parameters.quote);
-                self.state = State::WaitingForMarketConditions;
+
+                if self.symbol_information.is_trading() {
+                    self.state = State::WaitingForMarketConditions;
+                } else {
+                    self.pause_trading();
+                }
             }
 
             (_state @ State::New, _event) => {
@@ -466,6 +713,21 @@ impl SniperAlgo {
                     mut price,
                 },
             ) => {
+                if !self.symbol_information.is_trading() {
+                    self.pause_trading();
+                    return;
+                }
+
+                if self.feed_stale() {
+                    log_info!(
+                        self.logger,
+                        "onFeedUpdate",
+                        "Disregarding the update at Price {} as the market data is stale.",
+                        price
+                    );
+                    return;
+                }
+
                 if Self::should_react(
                     &self.algo_parameters.side,
                     &price,
@@ -531,6 +793,14 @@ impl SniperAlgo {
                 }
             }
 
+            (State::WaitingForMarketConditions, Event::SymbolStatus { status }) => {
+                self.symbol_information.set_status(Some(status));
+
+                if !self.symbol_information.is_trading() {
+                    self.pause_trading();
+                }
+            }
+
             (_state @ State::WaitingForMarketConditions, _event) => {
                 #[cfg(debug_assertions)]
                 log_error!(
@@ -541,6 +811,40 @@ impl SniperAlgo {
                     _state
                 );
             }
+
+            (State::TradingPaused, Event::SymbolStatus { status }) => {
+                self.symbol_information.set_status(Some(status));
+
+                if self.symbol_information.is_trading() {
+                    log_info!(
+                        self.logger,
+                        "SymbolStatusEvent",
+                        "Trading resumed for Symbol {}, so we are awaiting further feed updates.",
+                        self.algo_parameters.make_symbol()
+                    );
+
+                    report!(
+                        self.pdf_report,
+                        "Trading has resumed on the exchange for Symbol {}. \
+                        The algorithm continues with a remaining quantity of {}.",
+                        self.algo_parameters.make_symbol(),
+                        self.remaining_quantity
+                    );
+
+                    self.state = State::WaitingForMarketConditions;
+                }
+            }
+
+            (_state @ State::TradingPaused, _event) => {
+                #[cfg(debug_assertions)]
+                log_error!(
+                    self.logger,
+                    "unsupportedEvent",
+                    "Ignoring event {} as it is not supported in the current state {}.",
+                    _event,
+                    _state
+                );
+            }
             (
                 State::PendingCreate,
                 Event::CreateOrderAck {
@@ -630,6 +934,10 @@ impl SniperAlgo {
                 self.state = State::WaitingForMarketConditions;
             }
 
+            (State::PendingCreate | State::Working, Event::SymbolStatus { status }) => {
+                self.symbol_information.set_status(Some(status));
+            }
+
             (_state @ State::PendingCreate, _event) => {
                 #[cfg(debug_assertions)]
                 log_error!(
@@ -789,6 +1097,8 @@ impl SniperAlgo {
                         &self.algo_parameters.base,
                         &self.algo_parameters.quote,
                     );
+                    self.market_sevice
+                        .stop_watching_symbol(self.algo_parameters.make_symbol());
                     self.pdf_report.write_to_pdf().unwrap();
                 } else {
                     log_info!(
@@ -872,6 +1182,49 @@ impl SniperAlgo {
                 self.state = State::WaitingForMarketConditions;
             }
 
+            (
+                State::Working,
+                Event::OrderPartiallyRejected {
+                    order_id,
+                    symbol,
+                    execution_status,
+                    quantity,
+                    side,
+                    rejected_quantity,
+                    cumulative_quantity,
+                    leaves_quantity,
+                    rejection_reason,
+                },
+            ) => {
+                log_info!(
+                    self.logger,
+                    "OrderPartiallyRejectedEvent",
+                    "Quantity {} of Order {} for Symbol {}, Side {}, has been rejected                     with Rejection Reason {}. The total order quantity is {}, cumulative                     executed quantity is {}, working quantity is {}, and the execution                     status of the order is {}.",
+                    rejected_quantity,
+                    order_id,
+                    symbol,
+                    side,
+                    rejection_reason,
+                    quantity,
+                    cumulative_quantity,
+                    leaves_quantity,
+                    execution_status
+                );
+
+                self.exposed_quantity -= &rejected_quantity;
+                self.remaining_quantity += &rejected_quantity;
+
+                report!(
+                    self.pdf_report,
+                    "A quantity of {} of an order for Symbol {} was rejected due to {},                     while {} stays exposed on the exchange. The algorithm has a remaining                     quantity of {} to expose once this order is done.",
+                    rejected_quantity,
+                    symbol,
+                    rejection_reason,
+                    leaves_quantity,
+                    self.remaining_quantity
+                );
+            }
+
             (_state @ State::Working, _event) => {
                 #[cfg(debug_assertions)]
                 log_error!(
@@ -896,3 +1249,197 @@ impl SniperAlgo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::algo_type::AlgoType;
+    use crate::feed::feed_handle::FeedHandle;
+    use crate::feed::FeedMessages;
+    use crate::market::market_handle::MarketHandle;
+    use crate::market::messages::market_messages::MarketMessages;
+    use tokio::sync::mpsc;
+
+    struct Sniper {
+        algo: SniperAlgo,
+        market: mpsc::Receiver<MarketMessages>,
+        _feed: mpsc::Receiver<FeedMessages>,
+    }
+
+    impl Sniper {
+        /// A sniper buying 1 below 100 on a trading symbol.
+        fn trading(algo_id: &str) -> Self {
+            Self::with_status(algo_id, SymbolStatus::Trading)
+        }
+
+        /// A sniper buying 1 below 100 on a symbol in the given trading status.
+        fn with_status(algo_id: &str, status: SymbolStatus) -> Self {
+            std::fs::create_dir_all("logs").unwrap();
+
+            let (market_sender, market) = mpsc::channel(100);
+            let (feed_sender, feed) = mpsc::channel(100);
+            let (responses, _) = mpsc::channel(100);
+            let (updates, _) = mpsc::channel(100);
+
+            let algo_parameters = AlgoParameters {
+                base: "btc".into(),
+                quote: "usdt".into(),
+                algo_type: AlgoType::Sniper,
+                algo_id: algo_id.into(),
+                side: Side::Buy,
+                quantity: Decimal::ONE,
+                price: Decimal::ONE_HUNDRED,
+            };
+            let mut algo = SniperAlgo::new(
+                algo_parameters,
+                MarketService::new(
+                    &MarketHandle::with_sender(market_sender),
+                    &responses,
+                    algo_id,
+                ),
+                FeedService::new(
+                    &FeedHandle::with_sender(feed_sender),
+                    "context",
+                    algo_id,
+                    &updates,
+                ),
+            );
+
+            algo.handle_market_reponse(MarketResponses::SymbolInformation {
+                algo_id: algo_id.into(),
+                min_quantity: None,
+                max_quantity: None,
+                lot_size: None,
+                min_price: None,
+                max_price: None,
+                tick_size: None,
+                min_amount: None,
+                status: Some(status),
+            });
+
+            Self {
+                algo,
+                market,
+                _feed: feed,
+            }
+        }
+
+        fn offer(&mut self, price: i64) {
+            self.algo.handle_l1(&offer(price));
+        }
+
+        fn symbol_status(&mut self, status: SymbolStatus) {
+            self.algo
+                .handle_market_reponse(MarketResponses::SymbolStatusChanged {
+                    algo_id: "sniper".into(),
+                    symbol: "BTCUSDT".into(),
+                    status,
+                });
+        }
+
+        fn paused(&self) -> bool {
+            matches!(self.algo.state, State::TradingPaused)
+        }
+
+        fn status(&mut self, stream: FeedStream, state: FeedState) {
+            self.algo
+                .handle_feed_status(&FeedStatus::new("btcusdt", stream, state));
+        }
+
+        fn orders(&mut self) -> usize {
+            let mut orders = 0;
+            while let Ok(message) = self.market.try_recv() {
+                if matches!(message, MarketMessages::CreateOrder { .. }) {
+                    orders += 1;
+                }
+            }
+            orders
+        }
+    }
+
+    fn offer(price: i64) -> L1Data {
+        L1Data::new(
+            "btcusdt",
+            Decimal::ONE,
+            Decimal::from(price - 1),
+            Decimal::ONE,
+            Decimal::from(price),
+        )
+    }
+
+    #[test]
+    fn subscribing_while_the_feed_is_stale_places_no_order() {
+        let mut sniper = Sniper::trading("stale-subscription");
+
+        // A stale feed sends its status ahead of the cached snapshot.
+        sniper.status(FeedStream::L1, FeedState::Stale);
+        sniper.algo.handle_snapshot(&FeedSnapshot {
+            symbol: "btcusdt".into(),
+            l1: Some(offer(90)),
+            l2: None,
+        });
+        assert_eq!(sniper.orders(), 0);
+
+        sniper.status(FeedStream::L1, FeedState::Recovered);
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 1);
+    }
+
+    #[test]
+    fn stale_market_data_stops_trading_until_it_recovers() {
+        let mut sniper = Sniper::trading("stale-feed");
+
+        sniper.status(FeedStream::L1, FeedState::Stale);
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 0);
+
+        // Other feeds recovering leave the L1 feed stale.
+        sniper.status(FeedStream::L2, FeedState::Recovered);
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 0);
+
+        sniper.status(FeedStream::L1, FeedState::Recovered);
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 1);
+    }
+
+    #[test]
+    fn feeds_the_sniper_does_not_trade_on_do_not_stop_it() {
+        let mut sniper = Sniper::trading("stale-book");
+
+        sniper.status(FeedStream::L2, FeedState::Stale);
+        sniper.status(FeedStream::Mailbox, FeedState::Gap);
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 1);
+    }
+
+    #[test]
+    fn a_symbol_that_stops_trading_pauses_the_sniper_until_it_resumes() {
+        let mut sniper = Sniper::trading("symbol-halt");
+
+        sniper.symbol_status(SymbolStatus::Halt);
+        assert!(sniper.paused());
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 0);
+
+        sniper.symbol_status(SymbolStatus::Break);
+        assert!(sniper.paused());
+
+        sniper.symbol_status(SymbolStatus::Trading);
+        assert!(!sniper.paused());
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 1);
+    }
+
+    #[test]
+    fn a_symbol_that_is_not_trading_at_the_start_pauses_the_sniper() {
+        let mut sniper = Sniper::with_status("symbol-pre-trading", SymbolStatus::PreTrading);
+        assert!(sniper.paused());
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 0);
+
+        sniper.symbol_status(SymbolStatus::Trading);
+        sniper.offer(90);
+        assert_eq!(sniper.orders(), 1);
+    }
+}
@@ -0,0 +1,65 @@
+//! Local stand-in for the Binance spot market data API, driven by a scripted scenario.
+//! Serves the bookTicker, trade and diff depth websocket streams and the REST depth snapshot.
+//!
+//! cargo run --bin mock_exchange -- config/mock_scenarios/l2_gap_and_reconnect.toml [port]
+
+mod scenario;
+mod server;
+
+use scenario::Scenario;
+use server::{handle_connection, run_scenario, Exchange};
+use std::process;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+const DEFAULT_PORT: u16 = 9443;
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+async fn main() {
+    let mut args = std::env::args().skip(1);
+
+    let Some(scenario_path) = args.next() else {
+        eprintln!("Usage: mock_exchange <scenario.toml> [port]");
+        process::exit(1);
+    };
+    let port = args
+        .next()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+
+    let scenario = match Scenario::load_from_file(&scenario_path) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("Failed to load scenario {}: {}", scenario_path, e);
+            process::exit(1);
+        }
+    };
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to listen on port {}: {}", port, e);
+            process::exit(1);
+        }
+    };
+
+    println!(
+        "Mock exchange listening on port {}. Point the feed at it with:",
+        port
+    );
+    println!("[feed.endpoints]");
+    println!("rest_api = \"http://127.0.0.1:{}\"", port);
+    println!("websocket = \"ws://127.0.0.1:{}/ws\"", port);
+
+    let exchange = Arc::new(Exchange::new());
+    tokio::spawn(run_scenario(exchange.clone(), scenario));
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_connection(exchange.clone(), stream));
+            }
+            Err(e) => eprintln!("Failed to accept connection: {}", e),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Deserialize;
+use std::fs;
+
+/// Price and quantity, as strings like the exchange sends them.
+pub type Level = (String, String);
+
+/// A scripted sequence of exchange events, played once the first websocket client connects.
+#[derive(Deserialize, Debug)]
+pub struct Scenario {
+    /// Starts over after the last step. Depth update ids are replayed as written, so
+    /// repeating suits L1-only scenarios.
+    #[serde(default)]
+    pub repeat: bool,
+    pub steps: Vec<Step>,
+}
+
+impl Scenario {
+    pub fn load_from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let scenario: Scenario = toml::from_str(&content)?;
+        Ok(scenario)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Step {
+    /// Wait before the event, counted from the previous step.
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// Sent on `<symbol>@bookTicker`.
+    BookTicker {
+        symbol: String,
+        update_id: u64,
+        bid_price: String,
+        bid_quantity: String,
+        ask_price: String,
+        ask_quantity: String,
+    },
+    /// Sent on `<symbol>@trade`.
+    Trade {
+        symbol: String,
+        trade_id: u64,
+        price: String,
+        quantity: String,
+        #[serde(default)]
+        buyer_is_maker: bool,
+    },
+    /// Sent on `<symbol>@depth` and `<symbol>@depth@100ms`.
+    DepthUpdate {
+        symbol: String,
+        first_update_id: u64,
+        last_update_id: u64,
+        #[serde(default)]
+        bids: Vec<Level>,
+        #[serde(default)]
+        asks: Vec<Level>,
+    },
+    /// Sets the book returned by `GET /api/v3/depth` from now on.
+    DepthSnapshot {
+        symbol: String,
+        last_update_id: u64,
+        #[serde(default)]
+        bids: Vec<Level>,
+        #[serde(default)]
+        asks: Vec<Level>,
+    },
+    /// Drops every websocket connection, so clients have to reconnect.
+    Disconnect,
+}
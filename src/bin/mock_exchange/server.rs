@@ -0,0 +1,334 @@
+use crate::scenario::{Event, Level, Scenario};
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, Notify};
+use tokio_tungstenite::tungstenite::Message;
+
+const MAX_REQUEST_HEAD: usize = 8192;
+
+#[derive(Clone, Debug)]
+enum Broadcast {
+    /// A stream message for every client whose stream starts with `stream_prefix`.
+    Message {
+        stream_prefix: String,
+        text: String,
+    },
+    Disconnect,
+}
+
+struct Snapshot {
+    last_update_id: u64,
+    bids: Vec<Level>,
+    asks: Vec<Level>,
+}
+
+/// State shared by the scenario and the client connections.
+pub struct Exchange {
+    snapshots: Mutex<HashMap<String, Snapshot>>,
+    broadcasts: broadcast::Sender<Broadcast>,
+    client_connected: Notify,
+}
+
+impl Exchange {
+    pub fn new() -> Self {
+        let (broadcasts, _) = broadcast::channel(1000);
+
+        Self {
+            snapshots: Mutex::new(HashMap::new()),
+            broadcasts,
+            client_connected: Notify::new(),
+        }
+    }
+
+    fn publish(&self, stream_prefix: String, payload: Value) {
+        // Nobody listening is fine: the exchange does not wait for subscribers either.
+        let _ = self.broadcasts.send(Broadcast::Message {
+            stream_prefix,
+            text: payload.to_string(),
+        });
+    }
+
+    fn apply(&self, event: Event) {
+        match event {
+            Event::BookTicker {
+                symbol,
+                update_id,
+                bid_price,
+                bid_quantity,
+                ask_price,
+                ask_quantity,
+            } => self.publish(
+                format!("{}@bookTicker", symbol.to_lowercase()),
+                json!({
+                    "u": update_id,
+                    "s": symbol.to_uppercase(),
+                    "b": bid_price,
+                    "B": bid_quantity,
+                    "a": ask_price,
+                    "A": ask_quantity,
+                }),
+            ),
+            Event::Trade {
+                symbol,
+                trade_id,
+                price,
+                quantity,
+                buyer_is_maker,
+            } => {
+                let now = Utc::now().timestamp_millis();
+                self.publish(
+                    format!("{}@trade", symbol.to_lowercase()),
+                    json!({
+                        "e": "trade",
+                        "E": now,
+                        "s": symbol.to_uppercase(),
+                        "t": trade_id,
+                        "p": price,
+                        "q": quantity,
+                        "T": now,
+                        "m": buyer_is_maker,
+                    }),
+                )
+            }
+            Event::DepthUpdate {
+                symbol,
+                first_update_id,
+                last_update_id,
+                bids,
+                asks,
+            } => self.publish(
+                format!("{}@depth", symbol.to_lowercase()),
+                json!({
+                    "e": "depthUpdate",
+                    "E": Utc::now().timestamp_millis(),
+                    "s": symbol.to_uppercase(),
+                    "U": first_update_id,
+                    "u": last_update_id,
+                    "b": bids,
+                    "a": asks,
+                }),
+            ),
+            Event::DepthSnapshot {
+                symbol,
+                last_update_id,
+                bids,
+                asks,
+            } => {
+                self.snapshots.lock().unwrap().insert(
+                    symbol.to_lowercase(),
+                    Snapshot {
+                        last_update_id,
+                        bids,
+                        asks,
+                    },
+                );
+            }
+            Event::Disconnect => {
+                println!("Dropping all websocket connections.");
+                let _ = self.broadcasts.send(Broadcast::Disconnect);
+            }
+        }
+    }
+}
+
+pub async fn run_scenario(exchange: Arc<Exchange>, scenario: Scenario) {
+    exchange.client_connected.notified().await;
+    println!("Client connected, starting the scenario.");
+
+    loop {
+        for step in &scenario.steps {
+            tokio::time::sleep(Duration::from_millis(step.delay_ms)).await;
+            exchange.apply(step.event.clone());
+        }
+
+        if !scenario.repeat {
+            println!("Scenario finished.");
+            return;
+        }
+    }
+}
+
+pub async fn handle_connection(exchange: Arc<Exchange>, stream: TcpStream) {
+    let Some(head) = peek_request_head(&stream).await else {
+        return;
+    };
+
+    let path = head
+        .lines()
+        .next()
+        .and_then(|request_line| request_line.split_whitespace().nth(1))
+        .unwrap_or("/")
+        .to_owned();
+    let is_websocket = head
+        .lines()
+        .any(|line| line.to_lowercase().replace(' ', "") == "upgrade:websocket");
+
+    if is_websocket {
+        serve_websocket(exchange, stream, path).await;
+    } else {
+        serve_rest(exchange, stream, &path).await;
+    }
+}
+
+/// Reads the request head without consuming it, so websocket requests can still be handed
+/// to the handshake.
+async fn peek_request_head(stream: &TcpStream) -> Option<String> {
+    let mut buffer = vec![0; MAX_REQUEST_HEAD];
+
+    loop {
+        let read = stream.peek(&mut buffer).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+
+        let head = String::from_utf8_lossy(&buffer[..read]);
+        if let Some(end) = head.find("\r\n\r\n") {
+            return Some(head[..end].to_owned());
+        }
+        if read == buffer.len() {
+            return None;
+        }
+
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+/// Answers a `SUBSCRIBE` request, returning the requested streams.
+fn subscribe_request(text: &str) -> Option<(Vec<String>, Value)> {
+    let request: Value = serde_json::from_str(text).ok()?;
+    if request["method"] != "SUBSCRIBE" {
+        return None;
+    }
+
+    let streams = request["params"]
+        .as_array()?
+        .iter()
+        .filter_map(|stream| stream.as_str().map(str::to_owned))
+        .collect();
+    Some((streams, json!({ "result": null, "id": request["id"] })))
+}
+
+/// Serves `/ws/<stream>` as binance-rs connects, or `/ws` followed by `SUBSCRIBE` requests as
+/// barter does.
+async fn serve_websocket(exchange: Arc<Exchange>, stream: TcpStream, path: String) {
+    let mut subscribed_streams: Vec<String> = path
+        .strip_prefix("/ws/")
+        .filter(|stream| !stream.is_empty())
+        .map(|stream| vec![stream.to_owned()])
+        .unwrap_or_default();
+    let mut broadcasts = exchange.broadcasts.subscribe();
+
+    let mut web_socket = match tokio_tungstenite::accept_async(stream).await {
+        Ok(web_socket) => web_socket,
+        Err(error) => {
+            eprintln!("Websocket handshake failed: {}", error);
+            return;
+        }
+    };
+
+    if !subscribed_streams.is_empty() {
+        println!("Client subscribed to {}.", subscribed_streams.join(", "));
+        exchange.client_connected.notify_one();
+    }
+
+    loop {
+        tokio::select! {
+            broadcast = broadcasts.recv() => match broadcast {
+                Ok(Broadcast::Message { stream_prefix, text }) => {
+                    if !subscribed_streams
+                        .iter()
+                        .any(|stream| stream.starts_with(&stream_prefix))
+                    {
+                        continue;
+                    }
+                    if web_socket.send(Message::text(text)).await.is_err() {
+                        return;
+                    }
+                }
+                Ok(Broadcast::Disconnect) | Err(broadcast::error::RecvError::Closed) => return,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!(
+                        "Client on {} lagged, {} messages skipped.",
+                        subscribed_streams.join(", "),
+                        skipped
+                    );
+                }
+            },
+            message = web_socket.next() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(Message::Text(text))) => {
+                    let Some((streams, response)) = subscribe_request(&text) else {
+                        continue;
+                    };
+                    if web_socket.send(Message::text(response.to_string())).await.is_err() {
+                        return;
+                    }
+
+                    println!("Client subscribed to {}.", streams.join(", "));
+                    subscribed_streams.extend(streams);
+                    exchange.client_connected.notify_one();
+                }
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn serve_rest(exchange: Arc<Exchange>, mut stream: TcpStream, path: &str) {
+    let mut request = vec![0; MAX_REQUEST_HEAD];
+    if stream.read(&mut request).await.is_err() {
+        return;
+    }
+
+    let (route, query) = path.split_once('?').unwrap_or((path, ""));
+    let (status, body) = match route {
+        "/api/v3/ping" => ("200 OK", json!({})),
+        "/api/v3/depth" => depth_snapshot(&exchange, query),
+        _ => (
+            "404 Not Found",
+            json!({ "code": -1, "msg": format!("{} is not supported by the mock exchange.", route) }),
+        ),
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+
+    if let Err(error) = stream.write_all(response.as_bytes()).await {
+        eprintln!("Failed to answer {}: {}", path, error);
+    }
+}
+
+fn depth_snapshot(exchange: &Exchange, query: &str) -> (&'static str, Value) {
+    let symbol = query
+        .split('&')
+        .filter_map(|parameter| parameter.split_once('='))
+        .find(|(name, _)| *name == "symbol")
+        .map(|(_, value)| value.to_lowercase())
+        .unwrap_or_default();
+
+    match exchange.snapshots.lock().unwrap().get(&symbol) {
+        Some(snapshot) => (
+            "200 OK",
+            json!({
+                "lastUpdateId": snapshot.last_update_id,
+                "bids": snapshot.bids,
+                "asks": snapshot.asks,
+            }),
+        ),
+        None => (
+            "400 Bad Request",
+            json!({ "code": -1121, "msg": "Invalid symbol." }),
+        ),
+    }
+}
@@ -0,0 +1,21 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// A symbol such as "btcusdt". Feed messages carry it by reference count, so publishing
+/// to many subscribers never copies the symbol.
+pub type InstrumentId = Arc<str>;
+
+/// Returns the shared id of a symbol. Feeds intern their instrument once when they start
+/// and clone the id for every update.
+pub fn intern(symbol: &str) -> InstrumentId {
+    static INSTRUMENTS: OnceLock<Mutex<HashSet<InstrumentId>>> = OnceLock::new();
+
+    let mut instruments = INSTRUMENTS.get_or_init(Default::default).lock().unwrap();
+    if let Some(instrument) = instruments.get(symbol) {
+        return instrument.clone();
+    }
+
+    let instrument = InstrumentId::from(symbol);
+    instruments.insert(instrument.clone());
+    instrument
+}
@@ -1,5 +1,8 @@
 pub mod algo_type;
+pub mod instrument_id;
 pub mod order_types;
 pub mod side;
+pub mod symbol_status;
 pub mod time_in_force;
 pub mod tracked_sender;
+pub mod venue;
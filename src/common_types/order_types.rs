@@ -1,7 +1,7 @@
 use core::fmt;
 use std::{fmt::Display, str::FromStr};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum OrderType {
     Limit,
     Market,
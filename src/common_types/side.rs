@@ -9,6 +9,7 @@ pub enum Side {
 }
 
 impl Side {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: &str) -> Option<Self> {
         match value.to_uppercase().as_str() {
             "BUY" => Some(Side::Buy),
@@ -0,0 +1,80 @@
+use core::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolStatus {
+    PreTrading,
+    Trading,
+    PostTrading,
+    EndOfDay,
+    Halt,
+    AuctionMatch,
+    Break,
+    Unknown,
+}
+
+impl SymbolStatus {
+    pub fn is_trading(&self) -> bool {
+        *self == SymbolStatus::Trading
+    }
+}
+
+impl fmt::Display for SymbolStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let variant_str = match self {
+            SymbolStatus::PreTrading => "PRE_TRADING",
+            SymbolStatus::Trading => "TRADING",
+            SymbolStatus::PostTrading => "POST_TRADING",
+            SymbolStatus::EndOfDay => "END_OF_DAY",
+            SymbolStatus::Halt => "HALT",
+            SymbolStatus::AuctionMatch => "AUCTION_MATCH",
+            SymbolStatus::Break => "BREAK",
+            SymbolStatus::Unknown => "UNKNOWN",
+        };
+        write!(f, "{}", variant_str)
+    }
+}
+
+impl FromStr for SymbolStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PRE_TRADING" => Ok(SymbolStatus::PreTrading),
+            "TRADING" => Ok(SymbolStatus::Trading),
+            "POST_TRADING" => Ok(SymbolStatus::PostTrading),
+            "END_OF_DAY" => Ok(SymbolStatus::EndOfDay),
+            "HALT" => Ok(SymbolStatus::Halt),
+            "AUCTION_MATCH" => Ok(SymbolStatus::AuctionMatch),
+            "BREAK" => Ok(SymbolStatus::Break),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exchange_statuses_parse_back_from_their_names() {
+        for status in [
+            SymbolStatus::PreTrading,
+            SymbolStatus::Trading,
+            SymbolStatus::PostTrading,
+            SymbolStatus::EndOfDay,
+            SymbolStatus::Halt,
+            SymbolStatus::AuctionMatch,
+            SymbolStatus::Break,
+        ] {
+            assert_eq!(SymbolStatus::from_str(&status.to_string()), Ok(status));
+        }
+    }
+
+    #[test]
+    fn other_statuses_do_not_parse() {
+        assert_eq!(SymbolStatus::from_str("UNKNOWN"), Err(()));
+        assert_eq!(SymbolStatus::from_str("trading"), Err(()));
+        assert_eq!(SymbolStatus::from_str(""), Err(()));
+    }
+}
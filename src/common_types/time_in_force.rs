@@ -1,7 +1,7 @@
 use core::fmt;
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TIF {
     GTC, // Good-Till-Cancel
     IOC, // Immediate-Or-Cancel
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Venue {
+    BinanceSpot,
+    Kraken,
+}
+
+impl Display for Venue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BinanceSpot => write!(f, "BINANCE_SPOT"),
+            Self::Kraken => write!(f, "KRAKEN"),
+        }
+    }
+}
+
+impl FromStr for Venue {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "BINANCE_SPOT" => Ok(Self::BinanceSpot),
+            "KRAKEN" => Ok(Self::Kraken),
+            _ => Err(()),
+        }
+    }
+}
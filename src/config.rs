@@ -1,12 +1,15 @@
 use dotenv::dotenv;
 use rust_decimal::Decimal;
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::env;
+use std::fmt;
 use std::fs;
+use std::time::Duration;
 
 use crate::common_types::algo_type::AlgoType;
 use crate::common_types::side::Side;
+use crate::common_types::venue::Venue;
 
 #[derive(Deserialize, Debug, Clone)]
 
@@ -30,8 +33,352 @@ impl AlgoParameters {
     }
 }
 
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    #[default]
+    Testnet,
+    Mainnet,
+    Simulator,
+}
+
+impl Environment {
+    pub fn is_production(&self) -> bool {
+        *self == Environment::Mainnet
+    }
+
+    /// Binance addresses of the environment. The simulator never calls the testnet ones it
+    /// gets: its feeds, orders and history are local.
+    pub fn binance_config(&self) -> binance::config::Config {
+        match self {
+            Environment::Mainnet => binance::config::Config::default(),
+            Environment::Testnet => binance::config::Config::testnet(),
+            Environment::Simulator => binance::config::Config::testnet(),
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Environment::Testnet => write!(f, "Binance Testnet"),
+            Environment::Mainnet => write!(f, "Binance Mainnet"),
+            Environment::Simulator => write!(f, "Simulator"),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SimulatorConfig {
+    pub tick_interval_ms: u64,
+    pub reference_prices: HashMap<String, Decimal>,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            tick_interval_ms: 500,
+            reference_prices: HashMap::new(),
+        }
+    }
+}
+
+impl SimulatorConfig {
+    pub fn reference_price(&self, base: &str, quote: &str) -> Option<Decimal> {
+        self.reference_prices
+            .get(&format!("{}{}", base, quote).to_lowercase())
+            .cloned()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RecorderConfig {
+    pub enabled: bool,
+    pub directory: String,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: "recordings".to_owned(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayMode {
+    #[default]
+    RealTime,
+    Accelerated,
+    AsFastAsPossible,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReplayConfig {
+    pub directory: String,
+    pub date: String,
+    #[serde(default)]
+    pub mode: ReplayMode,
+    #[serde(default = "ReplayConfig::default_speed_multiplier")]
+    pub speed_multiplier: f64,
+}
+
+impl ReplayConfig {
+    fn default_speed_multiplier() -> f64 {
+        10.0
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct StalenessConfig {
+    pub threshold_ms: u64,
+    pub instrument_thresholds_ms: HashMap<String, u64>,
+}
+
+impl Default for StalenessConfig {
+    fn default() -> Self {
+        Self {
+            threshold_ms: 5000,
+            instrument_thresholds_ms: HashMap::new(),
+        }
+    }
+}
+
+impl StalenessConfig {
+    pub fn threshold(&self, instrument: &str) -> Duration {
+        Duration::from_millis(
+            self.instrument_thresholds_ms
+                .get(&instrument.to_lowercase())
+                .cloned()
+                .unwrap_or(self.threshold_ms),
+        )
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct StatsConfig {
+    /// How often a summary is logged; 0 disables the summary.
+    pub log_interval_secs: u64,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            log_interval_secs: 60,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct VenuesConfig {
+    /// Instruments (e.g. "btcusdt") quoted on each venue for the consolidated L1 feed.
+    pub instruments: HashMap<Venue, Vec<String>>,
+}
+
+impl VenuesConfig {
+    /// Venues configured for the instrument, falling back to Binance spot.
+    pub fn venues(&self, instrument: &str) -> Vec<Venue> {
+        let instrument = instrument.to_lowercase();
+        let mut venues: Vec<Venue> = self
+            .instruments
+            .iter()
+            .filter(|(_, instruments)| {
+                instruments
+                    .iter()
+                    .any(|configured| configured.to_lowercase() == instrument)
+            })
+            .map(|(venue, _)| *venue)
+            .collect();
+
+        if venues.is_empty() {
+            venues.push(Venue::BinanceSpot);
+        }
+        venues.sort();
+        venues
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistorySource {
+    /// Klines and aggregate trades from the exchange REST API.
+    #[default]
+    Exchange,
+    /// Trades recorded under the recorder directory, with bars built locally.
+    Recordings,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub source: HistorySource,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AnalyticsConfig {
+    pub vwap_window_secs: u64,
+    /// Number of mid price changes the realized volatility is computed over.
+    pub volatility_window: usize,
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            vwap_window_secs: 300,
+            volatility_window: 100,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SanityAction {
+    /// Suspicious updates are reported and never reach the algorithms.
+    #[default]
+    Drop,
+    /// Suspicious updates are reported and published anyway.
+    Flag,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SanityConfig {
+    pub enabled: bool,
+    pub action: SanityAction,
+    pub reject_zero_prices: bool,
+    pub reject_crossed_books: bool,
+    /// Largest move from the last accepted price, in percent; 0 disables the check.
+    pub max_price_change_pct: Decimal,
+    pub max_consecutive_outliers: u32,
+}
+
+impl Default for SanityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: SanityAction::Drop,
+            reject_zero_prices: true,
+            reject_crossed_books: true,
+            max_price_change_pct: Decimal::from(50),
+            max_consecutive_outliers: 5,
+        }
+    }
+}
+
+/// A cross rate derived from two instruments sharing a currency, e.g. SOL/BTC from
+/// SOL/USDT and BTC/USDT with `via = "usdt"`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct SyntheticConfig {
+    pub base: String,
+    pub quote: String,
+    pub via: String,
+}
+
+impl SyntheticConfig {
+    pub fn symbol(&self) -> String {
+        format!("{}{}", self.base, self.quote)
+    }
+
+    pub fn base_leg(&self) -> String {
+        format!("{}{}", self.base, self.via)
+    }
+
+    pub fn quote_leg(&self) -> String {
+        format!("{}{}", self.quote, self.via)
+    }
+
+    pub fn legs(&self) -> [(String, String); 2] {
+        [
+            (self.base.clone(), self.via.clone()),
+            (self.quote.clone(), self.via.clone()),
+        ]
+    }
+}
+
+/// Replaces the Binance endpoints, for example with the local mock exchange.
+#[derive(Deserialize, Debug, Clone)]
+pub struct EndpointsConfig {
+    pub rest_api: String,
+    pub websocket: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct FeedConfig {
+    pub recorder: RecorderConfig,
+    pub staleness: StalenessConfig,
+    pub stats: StatsConfig,
+    pub sanity: SanityConfig,
+    pub analytics: AnalyticsConfig,
+    pub history: HistoryConfig,
+    pub venues: VenuesConfig,
+    pub synthetics: Vec<SyntheticConfig>,
+    /// When set, feeds are read from recorded files instead of the exchange.
+    pub replay: Option<ReplayConfig>,
+    /// When set, Binance L1, trades and L2 come from these endpoints' websocket and REST APIs,
+    /// and exchange candles and tickers are built from their trades.
+    pub endpoints: Option<EndpointsConfig>,
+}
+
+impl FeedConfig {
+    pub fn binance_config(&self, environment: &Environment) -> binance::config::Config {
+        let config = environment.binance_config();
+
+        match &self.endpoints {
+            Some(endpoints) => config
+                .set_rest_api_endpoint(endpoints.rest_api.clone())
+                .set_ws_endpoint(endpoints.websocket.clone()),
+            None => config,
+        }
+    }
+
+    /// Where exchange warm-up history is fetched from, or `None` when there is no exchange to
+    /// ask: the simulator never calls Binance, and the `[feed.endpoints]` mock exchange only
+    /// answers depth snapshots.
+    pub fn history_config(&self, environment: &Environment) -> Option<binance::config::Config> {
+        match (environment, &self.endpoints) {
+            (Environment::Mainnet | Environment::Testnet, None) => {
+                Some(environment.binance_config())
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ValidationConfig {
+    /// Warn at startup when a sniper limit is further than this from the last traded price;
+    /// 0 disables the check.
+    pub max_limit_distance_pct: Decimal,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_limit_distance_pct: Decimal::TEN,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Default)]
 pub struct AlgorithmConfig {
+    #[serde(default)]
+    pub environment: Environment,
+    #[serde(default)]
+    pub simulator: SimulatorConfig,
+    #[serde(default)]
+    pub feed: FeedConfig,
+    #[serde(default)]
+    pub validation: ValidationConfig,
     pub algorithms: Vec<AlgoParameters>,
 }
 
@@ -47,29 +394,27 @@ impl AlgorithmConfig {
 pub struct MarketConfig {
     pub api_key: String,
     pub api_secret: String,
+    pub environment: Environment,
 }
 
 impl MarketConfig {
-    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn from_env(environment: Environment) -> Result<Self, Box<dyn std::error::Error>> {
         dotenv().ok();
 
-        let api_key = env::var("API_KEY")?;
-        let api_secret = env::var("API_SECRET")?;
+        let (api_key, api_secret) = match environment {
+            Environment::Simulator => (
+                env::var("API_KEY").unwrap_or_default(),
+                env::var("API_SECRET").unwrap_or_default(),
+            ),
+            Environment::Testnet | Environment::Mainnet => {
+                (env::var("API_KEY")?, env::var("API_SECRET")?)
+            }
+        };
 
         Ok(Self {
             api_key,
             api_secret,
+            environment,
         })
     }
 }
-
-pub fn extract_trading_pairs(params: &[AlgoParameters]) -> HashSet<(String, String)> {
-    let mut trading_pairs: HashSet<(String, String)> = HashSet::new();
-
-    for param in params {
-        let (base, quote) = param.get_trading_pair();
-        trading_pairs.insert((base.to_string(), quote.to_string()));
-    }
-
-    trading_pairs
-}
@@ -0,0 +1,274 @@
+use super::messages::{analytics::Analytics, l1_data::L1Data, trade_data::TradeData};
+use super::shared_map::SharedMap;
+use crate::common_types::instrument_id::InstrumentId;
+use crate::config::AnalyticsConfig;
+use arc_swap::ArcSwapOption;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::time::Instant;
+
+const ANALYTICS_DECIMAL_PLACES: u32 = 8;
+
+/// Keeps the rolling state behind `Analytics` for one instrument.
+pub(super) struct AnalyticsEngine {
+    symbol: InstrumentId,
+    vwap_window: Duration,
+    volatility_window: usize,
+    l1_data: Option<L1Data>,
+    trades: VecDeque<(DateTime<Utc>, Decimal, Decimal)>,
+    /// The newest trade time and when it arrived. The VWAP window is aged in the trades'
+    /// own clock, which is in the past when replaying.
+    last_trade: Option<(DateTime<Utc>, Instant)>,
+    mids: VecDeque<Decimal>,
+}
+
+impl AnalyticsEngine {
+    pub fn new<Symbol>(symbol: Symbol, config: &AnalyticsConfig) -> Self
+    where
+        Symbol: Into<InstrumentId>,
+    {
+        Self {
+            symbol: symbol.into(),
+            vwap_window: Duration::seconds(config.vwap_window_secs as i64),
+            volatility_window: config.volatility_window.max(2),
+            l1_data: None,
+            trades: VecDeque::new(),
+            last_trade: None,
+            mids: VecDeque::new(),
+        }
+    }
+
+    pub fn on_l1(&mut self, l1_data: &L1Data) -> Option<Analytics> {
+        let mid = (l1_data.best_bid_level.price + l1_data.best_ask_level.price) / Decimal::TWO;
+        if !mid.is_zero() && self.mids.back() != Some(&mid) {
+            self.mids.push_back(mid);
+            if self.mids.len() > self.volatility_window {
+                self.mids.pop_front();
+            }
+        }
+
+        if let Some((trade_time, received)) = self.last_trade {
+            let since_trade = Duration::from_std(received.elapsed()).unwrap_or_default();
+            self.trim_trades(trade_time + since_trade);
+        }
+
+        self.l1_data = Some(l1_data.clone());
+        self.analytics()
+    }
+
+    pub fn on_trade(&mut self, trade_data: &TradeData) -> Option<Analytics> {
+        self.trades
+            .push_back((trade_data.trade_time, trade_data.price, trade_data.quantity));
+        self.last_trade = Some((trade_data.trade_time, Instant::now()));
+        self.trim_trades(trade_data.trade_time);
+
+        self.analytics()
+    }
+
+    fn trim_trades(&mut self, now: DateTime<Utc>) {
+        let window_start = now - self.vwap_window;
+        while self
+            .trades
+            .front()
+            .is_some_and(|(trade_time, ..)| *trade_time < window_start)
+        {
+            self.trades.pop_front();
+        }
+    }
+
+    fn analytics(&self) -> Option<Analytics> {
+        let l1_data = self.l1_data.as_ref()?;
+        let bid = &l1_data.best_bid_level;
+        let ask = &l1_data.best_ask_level;
+
+        let mid = (bid.price + ask.price) / Decimal::TWO;
+        let depth = bid.quantity + ask.quantity;
+        if mid.is_zero() || depth.is_zero() {
+            return None;
+        }
+
+        Some(Analytics {
+            symbol: self.symbol.clone(),
+            mid: round(mid),
+            microprice: round((bid.price * ask.quantity + ask.price * bid.quantity) / depth),
+            spread_bps: round((ask.price - bid.price) / mid * Decimal::from(10_000)),
+            book_imbalance: round((bid.quantity - ask.quantity) / depth),
+            vwap: self.vwap(),
+            realized_volatility: self.realized_volatility(),
+        })
+    }
+
+    fn vwap(&self) -> Option<Decimal> {
+        let (notional, volume) = self.trades.iter().fold(
+            (Decimal::ZERO, Decimal::ZERO),
+            |(notional, volume), (_, price, quantity)| {
+                (notional + price * quantity, volume + quantity)
+            },
+        );
+
+        (!volume.is_zero()).then(|| round(notional / volume))
+    }
+
+    fn realized_volatility(&self) -> Option<f64> {
+        let returns: Vec<f64> = self
+            .mids
+            .iter()
+            .zip(self.mids.iter().skip(1))
+            .filter_map(|(previous, current)| (current / previous).to_f64())
+            .map(f64::ln)
+            .collect();
+
+        if returns.len() < 2 {
+            return None;
+        }
+
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / (returns.len() - 1) as f64;
+
+        Some(variance.sqrt())
+    }
+}
+
+fn round(value: Decimal) -> Decimal {
+    value.round_dp(ANALYTICS_DECIMAL_PLACES)
+}
+
+/// Latest analytics per instrument, readable without a round trip through the feed actor.
+#[derive(Clone)]
+pub(super) struct AnalyticsCache(Arc<SharedMap<InstrumentId, ArcSwapOption<Analytics>>>);
+
+impl AnalyticsCache {
+    pub fn new(values: Arc<SharedMap<InstrumentId, ArcSwapOption<Analytics>>>) -> Self {
+        Self(values)
+    }
+
+    pub fn get(&self, instrument: &str) -> Option<Analytics> {
+        self.0
+            .get(instrument)
+            .and_then(|analytics| analytics.load_full())
+            .map(|analytics| analytics.as_ref().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::side::Side;
+
+    fn engine() -> AnalyticsEngine {
+        AnalyticsEngine::new(
+            "btcusdt",
+            &AnalyticsConfig {
+                vwap_window_secs: 60,
+                volatility_window: 3,
+            },
+        )
+    }
+
+    fn l1(bid_quantity: i64, bid: i64, ask_quantity: i64, ask: i64) -> L1Data {
+        L1Data::new(
+            "btcusdt",
+            Decimal::from(bid_quantity),
+            Decimal::from(bid),
+            Decimal::from(ask_quantity),
+            Decimal::from(ask),
+        )
+    }
+
+    fn trade(seconds: i64, price: i64, quantity: i64) -> TradeData {
+        TradeData::new(
+            "btcusdt",
+            Decimal::from(price),
+            Decimal::from(quantity),
+            Side::Buy,
+            DateTime::from_timestamp(seconds, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn book_analytics_come_from_the_best_levels() {
+        let mut engine = engine();
+        assert!(engine.on_trade(&trade(0, 100, 1)).is_none());
+
+        let analytics = engine.on_l1(&l1(3, 99, 1, 101)).unwrap();
+        assert_eq!(analytics.mid, Decimal::from(100));
+        // Leans towards the ask, which has less quantity.
+        assert_eq!(analytics.microprice, Decimal::new(1005, 1));
+        assert_eq!(analytics.spread_bps, Decimal::from(200));
+        assert_eq!(analytics.book_imbalance, Decimal::new(5, 1));
+
+        assert!(engine.on_l1(&l1(0, 99, 0, 101)).is_none());
+    }
+
+    #[test]
+    fn vwap_weighs_the_trades_in_the_window_by_quantity() {
+        let mut engine = engine();
+        engine.on_l1(&l1(1, 99, 1, 101));
+
+        engine.on_trade(&trade(0, 100, 1));
+        let analytics = engine.on_trade(&trade(30, 110, 3)).unwrap();
+        assert_eq!(analytics.vwap, Some(Decimal::new(1075, 1)));
+
+        // The first trade has left the window.
+        let analytics = engine.on_trade(&trade(61, 120, 1)).unwrap();
+        assert_eq!(analytics.vwap, Some(Decimal::new(1125, 1)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn vwap_ages_out_between_trades() {
+        let mut engine = engine();
+        engine.on_trade(&trade(0, 100, 1));
+        assert_eq!(
+            engine.on_l1(&l1(1, 99, 1, 101)).unwrap().vwap,
+            Some(Decimal::from(100))
+        );
+
+        tokio::time::advance(std::time::Duration::from_secs(61)).await;
+        assert_eq!(engine.on_l1(&l1(1, 99, 1, 101)).unwrap().vwap, None);
+    }
+
+    #[test]
+    fn volatility_follows_the_last_mid_changes() {
+        let mut engine = engine();
+        assert_eq!(
+            engine
+                .on_l1(&l1(1, 99, 1, 101))
+                .unwrap()
+                .realized_volatility,
+            None
+        );
+        // An unchanged mid is not a new return.
+        engine.on_l1(&l1(1, 99, 1, 101));
+        assert_eq!(
+            engine
+                .on_l1(&l1(1, 109, 1, 111))
+                .unwrap()
+                .realized_volatility,
+            None
+        );
+
+        let volatility = engine
+            .on_l1(&l1(1, 98, 1, 100))
+            .unwrap()
+            .realized_volatility
+            .unwrap();
+        let expected = (1.1f64.ln() - 0.9f64.ln()).abs() / 2f64.sqrt();
+        assert!((volatility - expected).abs() < 1e-12);
+
+        // Only the last three mids count: 110, 99 and 110 again.
+        let volatility = engine
+            .on_l1(&l1(1, 109, 1, 111))
+            .unwrap()
+            .realized_volatility
+            .unwrap();
+        let expected = (0.9f64.ln() - (110f64 / 99f64).ln()).abs() / 2f64.sqrt();
+        assert!((volatility - expected).abs() < 1e-12);
+    }
+}
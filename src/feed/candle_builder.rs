@@ -0,0 +1,161 @@
+use super::messages::{
+    candle::{Candle, CandleInterval},
+    trade_data::TradeData,
+};
+use crate::common_types::instrument_id::InstrumentId;
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::prelude::Zero;
+use rust_decimal::Decimal;
+
+/// Aggregates public trades into bars of a fixed interval. Intervals without trades
+/// produce no bar.
+pub(super) struct CandleBuilder {
+    symbol: InstrumentId,
+    interval: CandleInterval,
+    current: Option<Candle>,
+}
+
+impl CandleBuilder {
+    pub fn new<Symbol>(symbol: Symbol, interval: CandleInterval) -> Self
+    where
+        Symbol: Into<InstrumentId>,
+    {
+        Self {
+            symbol: symbol.into(),
+            interval,
+            current: None,
+        }
+    }
+
+    /// Returns the bar closed by this trade, if any, followed by the updated in-progress bar.
+    pub fn on_trade(&mut self, trade: &TradeData) -> Vec<Candle> {
+        let mut candles = Vec::new();
+
+        if let Some(closed) = self.close_until(trade.trade_time) {
+            candles.push(closed);
+        }
+
+        let candle = self
+            .current
+            .get_or_insert_with(|| new_candle(self.symbol.clone(), self.interval, trade));
+
+        candle.high = candle.high.max(trade.price);
+        candle.low = candle.low.min(trade.price);
+        candle.close = trade.price;
+        candle.volume += trade.quantity;
+        candle.trade_count += 1;
+
+        candles.push(candle.clone());
+        candles
+    }
+
+    /// Closes the in-progress bar once `now` has passed its close time.
+    pub fn close_until(&mut self, now: DateTime<Utc>) -> Option<Candle> {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|candle| now > candle.close_time)
+        {
+            let mut closed = self.current.take()?;
+            closed.is_closed = true;
+            return Some(closed);
+        }
+
+        None
+    }
+}
+
+fn new_candle(symbol: InstrumentId, interval: CandleInterval, trade: &TradeData) -> Candle {
+    let interval_millis = interval.duration().as_millis() as i64;
+    let open_millis = trade.trade_time.timestamp_millis() / interval_millis * interval_millis;
+
+    let open_time = Utc
+        .timestamp_millis_opt(open_millis)
+        .single()
+        .unwrap_or(trade.trade_time);
+    let close_time = Utc
+        .timestamp_millis_opt(open_millis + interval_millis - 1)
+        .single()
+        .unwrap_or(trade.trade_time);
+
+    Candle {
+        symbol,
+        interval,
+        open_time,
+        close_time,
+        open: trade.price,
+        high: trade.price,
+        low: trade.price,
+        close: trade.price,
+        volume: Decimal::zero(),
+        trade_count: 0,
+        is_closed: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::side::Side;
+
+    fn trade(seconds: i64, price: i64) -> TradeData {
+        TradeData::new(
+            "BTCUSDT",
+            Decimal::from(price),
+            Decimal::ONE,
+            Side::Buy,
+            Utc.timestamp_opt(1_700_000_040 + seconds, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn a_trade_in_the_next_interval_closes_the_bar() {
+        let mut builder = CandleBuilder::new("BTCUSDT", CandleInterval::OneMinute);
+
+        builder.on_trade(&trade(0, 100));
+        builder.on_trade(&trade(5, 103));
+        let candles = builder.on_trade(&trade(10, 99));
+        assert_eq!(candles.len(), 1);
+        assert!(!candles[0].is_closed);
+
+        // 1_700_000_040 is the start of a minute.
+        let candles = builder.on_trade(&trade(60, 101));
+        assert_eq!(candles.len(), 2);
+
+        let closed = &candles[0];
+        assert!(closed.is_closed);
+        assert_eq!(
+            closed.open_time,
+            Utc.timestamp_opt(1_700_000_040, 0).unwrap()
+        );
+        assert_eq!(closed.open, Decimal::from(100));
+        assert_eq!(closed.high, Decimal::from(103));
+        assert_eq!(closed.low, Decimal::from(99));
+        assert_eq!(closed.close, Decimal::from(99));
+        assert_eq!(closed.trade_count, 3);
+
+        let current = &candles[1];
+        assert!(!current.is_closed);
+        assert_eq!(
+            current.open_time,
+            Utc.timestamp_opt(1_700_000_100, 0).unwrap()
+        );
+        assert_eq!(current.open, Decimal::from(101));
+        assert_eq!(current.volume, Decimal::ONE);
+    }
+
+    #[test]
+    fn the_bar_closes_once_its_interval_has_passed() {
+        let mut builder = CandleBuilder::new("BTCUSDT", CandleInterval::OneMinute);
+        builder.on_trade(&trade(0, 100));
+
+        let end = Utc.timestamp_opt(1_700_000_099, 0).unwrap();
+        assert!(builder.close_until(end).is_none());
+        assert!(builder
+            .close_until(Utc.timestamp_opt(1_700_000_100, 0).unwrap())
+            .is_some_and(|candle| candle.is_closed));
+        assert!(builder
+            .close_until(Utc.timestamp_opt(1_700_000_200, 0).unwrap())
+            .is_none());
+    }
+}
@@ -0,0 +1,532 @@
+use super::feed_stats::FeedStats;
+use super::messages::candle::CandleInterval;
+use super::messages::feed_statistics::MailboxStatistics;
+use super::messages::feed_status::{FeedState, FeedStatus, FeedStream};
+use super::messages::messages::{AlgoIds, FeedUpdate};
+use super::shared_map::SharedMap;
+use crate::common_types::instrument_id::InstrumentId;
+use crate::common_types::tracked_sender::TrackedSender;
+use arc_swap::ArcSwapOption;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Entries a mailbox holds before it drops updates for its subscriber. Conflated updates
+/// take one entry per key, however often they are replaced.
+const MAILBOX_CAPACITY: usize = 10_000;
+
+/// Keys hold shared ids only, so building one for every subscriber does not allocate.
+type ConflationKey = (Conflated, InstrumentId, AlgoIds);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Conflated {
+    L1,
+    L2,
+    Analytics,
+    Ticker,
+    Candle(CandleInterval),
+}
+
+/// The newest update of a conflation key. The key sits in the queue at most once, so
+/// replacing its update does not touch the queue.
+#[derive(Default)]
+struct Slot {
+    update: ArcSwapOption<Published>,
+    queued: AtomicBool,
+    /// How many full updates the mailbox had queued when the slot was queued. Once another
+    /// one is queued behind the slot, replacing its update would deliver the newer update
+    /// ahead of it, so the key gets a new slot instead.
+    sealed_after: AtomicU64,
+}
+
+enum Entry {
+    Latest(Arc<Slot>),
+    Update(Published),
+}
+
+/// An update with the time it was published, to measure how long it took to reach the
+/// algo context.
+#[derive(Clone)]
+struct Published {
+    update: FeedUpdate,
+    received_time: DateTime<Utc>,
+}
+
+/// The statistics stream an update is counted under, if any.
+fn stream(update: &FeedUpdate) -> Option<&'static str> {
+    match update {
+        FeedUpdate::L1Update(..) => Some("L1"),
+        FeedUpdate::ConsolidatedL1(..) => Some("ConsolidatedL1"),
+        FeedUpdate::L2Update(..) | FeedUpdate::L2Delta(..) => Some("L2"),
+        FeedUpdate::Trade(..) => Some("Trades"),
+        FeedUpdate::Ticker(..) => Some("Ticker"),
+        _ => None,
+    }
+}
+
+/// Book snapshots, analytics, tickers and in-progress candles are superseded by newer ones;
+/// everything else (trades, deltas, closed candles, status changes) is delivered in full.
+fn conflation_key(update: &FeedUpdate) -> Option<ConflationKey> {
+    let (conflated, symbol, algo_ids) = match update {
+        FeedUpdate::L1Update(algo_ids, l1_data) => (Conflated::L1, &l1_data.symbol, algo_ids),
+        FeedUpdate::L2Update(algo_ids, l2_data) => (Conflated::L2, &l2_data.symbol, algo_ids),
+        FeedUpdate::Analytics(algo_ids, analytics) => {
+            (Conflated::Analytics, &analytics.symbol, algo_ids)
+        }
+        FeedUpdate::Ticker(algo_ids, ticker) => (Conflated::Ticker, &ticker.symbol, algo_ids),
+        FeedUpdate::Candle(algo_ids, candle) if !candle.is_closed => {
+            (Conflated::Candle(candle.interval), &candle.symbol, algo_ids)
+        }
+        _ => return None,
+    };
+
+    Some((conflated, symbol.clone(), algo_ids.clone()))
+}
+
+fn audience(update: &FeedUpdate) -> (InstrumentId, &AlgoIds) {
+    match update {
+        FeedUpdate::L1Update(algo_ids, l1_data) => (l1_data.symbol.clone(), algo_ids),
+        FeedUpdate::ConsolidatedL1(algo_ids, consolidated_l1) => {
+            (consolidated_l1.symbol.clone(), algo_ids)
+        }
+        FeedUpdate::L2Update(algo_ids, l2_data) => (l2_data.symbol.clone(), algo_ids),
+        FeedUpdate::L2Delta(algo_ids, l2_delta) => (l2_delta.symbol.clone(), algo_ids),
+        FeedUpdate::Trade(algo_ids, trade) => (trade.symbol.clone(), algo_ids),
+        FeedUpdate::Candle(algo_ids, candle) => (candle.symbol.clone(), algo_ids),
+        FeedUpdate::Status(algo_ids, feed_status) => (feed_status.symbol.clone(), algo_ids),
+        FeedUpdate::Snapshot(algo_ids, snapshot) => (snapshot.symbol.clone(), algo_ids),
+        FeedUpdate::Analytics(algo_ids, analytics) => (analytics.symbol.clone(), algo_ids),
+        FeedUpdate::Ticker(algo_ids, ticker) => (ticker.symbol.clone(), algo_ids),
+        FeedUpdate::History(algo_ids, history) => (history.symbol.clone(), algo_ids),
+    }
+}
+
+/// Dropping the mailbox closes its queue, which ends the forwarder once the queue is drained.
+struct Mailbox {
+    subscriber: String,
+    queue: mpsc::Sender<Entry>,
+    slots: SharedMap<ConflationKey, Slot>,
+    full_updates: AtomicU64,
+    /// The algos that missed updates of each instrument while the queue was full. They are
+    /// sent a gap status ahead of anything queued after the drop.
+    gaps: Mutex<HashMap<InstrumentId, Vec<String>>>,
+    has_gaps: AtomicBool,
+}
+
+impl Mailbox {
+    fn push(&self, update: FeedUpdate) -> bool {
+        if self.has_gaps.load(Ordering::Acquire) && !self.queue_gaps() {
+            self.drop_update(&update);
+            return false;
+        }
+
+        let key = conflation_key(&update);
+        let published = Published {
+            update,
+            received_time: Utc::now(),
+        };
+        let entry = match key {
+            Some(key) => {
+                let full_updates = self.full_updates.load(Ordering::Acquire);
+                let mut slot = self.slots.get_or_insert_with(&key, Slot::default);
+                if slot.queued.load(Ordering::Acquire)
+                    && slot.sealed_after.load(Ordering::Relaxed) != full_updates
+                {
+                    slot = Arc::new(Slot::default());
+                    self.slots.insert(key, slot.clone());
+                }
+
+                slot.update.store(Some(Arc::new(published)));
+                if slot.queued.swap(true, Ordering::AcqRel) {
+                    return true;
+                }
+                slot.sealed_after.store(full_updates, Ordering::Relaxed);
+                Entry::Latest(slot)
+            }
+            None => {
+                self.full_updates.fetch_add(1, Ordering::AcqRel);
+                Entry::Update(published)
+            }
+        };
+
+        match self.queue.try_send(entry) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(Entry::Latest(slot))) => {
+                slot.queued.store(false, Ordering::Release);
+                if let Some(published) = slot.update.swap(None) {
+                    self.drop_update(&published.update);
+                }
+                false
+            }
+            Err(mpsc::error::TrySendError::Full(Entry::Update(published))) => {
+                self.drop_update(&published.update);
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        }
+    }
+
+    fn drop_update(&self, update: &FeedUpdate) {
+        let (instrument, algo_ids) = audience(update);
+
+        let mut gaps = self.gaps.lock().unwrap();
+        if gaps.is_empty() {
+            eprintln!(
+                "Subscriber {} fell {} entries behind, dropping updates.",
+                self.subscriber, MAILBOX_CAPACITY
+            );
+        }
+
+        let missed = gaps.entry(instrument).or_default();
+        for algo_id in algo_ids.iter() {
+            if !missed.contains(algo_id) {
+                missed.push(algo_id.clone());
+            }
+        }
+        self.has_gaps.store(true, Ordering::Release);
+    }
+
+    /// Queues a gap status for every instrument with dropped updates. False while the
+    /// queue is still full.
+    fn queue_gaps(&self) -> bool {
+        let mut gaps = self.gaps.lock().unwrap();
+
+        while let Some(instrument) = gaps.keys().next().cloned() {
+            let algo_ids = gaps[&instrument].clone();
+            let gap = FeedUpdate::Status(
+                algo_ids.into(),
+                Arc::new(FeedStatus::new(
+                    instrument.clone(),
+                    FeedStream::Mailbox,
+                    FeedState::Gap,
+                )),
+            );
+
+            self.full_updates.fetch_add(1, Ordering::AcqRel);
+            let published = Published {
+                update: gap,
+                received_time: Utc::now(),
+            };
+            if self.queue.try_send(Entry::Update(published)).is_err() {
+                return false;
+            }
+            gaps.remove(&instrument);
+        }
+
+        self.has_gaps.store(false, Ordering::Release);
+        true
+    }
+
+    /// Forgets the keys with nothing queued, such as those of algo lists that changed.
+    fn prune(&self) {
+        self.slots
+            .retain(|_, slot| slot.queued.load(Ordering::Acquire));
+    }
+}
+
+/// Per-subscriber mailboxes so publishing never waits on a slow algo context. Each mailbox
+/// is drained into the subscriber's channel by its own task.
+#[derive(Clone, Default)]
+pub(super) struct Conflator {
+    mailboxes: Arc<SharedMap<String, Mailbox>>,
+    stats: FeedStats,
+}
+
+impl Conflator {
+    pub fn new(stats: &FeedStats) -> Self {
+        Self {
+            mailboxes: Arc::default(),
+            stats: stats.clone(),
+        }
+    }
+
+    /// False when the subscriber fell too far behind and the update was dropped. Its algos
+    /// get a `Gap` status once the mailbox has room again.
+    pub fn publish(&self, subscriber: &TrackedSender<FeedUpdate>, update: FeedUpdate) -> bool {
+        self.mailbox(subscriber).push(update)
+    }
+
+    /// Called when the subscriber's subscriptions changed. A subscriber without any left
+    /// loses its mailbox and forwarder; updates already queued are still delivered.
+    pub fn release(&self, subscriber: &TrackedSender<FeedUpdate>, subscribed: bool) {
+        if !subscribed {
+            self.mailboxes.remove(&subscriber.receiver_id);
+        } else if let Some(mailbox) = self.mailboxes.get(&subscriber.receiver_id) {
+            mailbox.prune();
+        }
+    }
+
+    /// How full each subscriber's mailbox is right now.
+    pub fn statistics(&self) -> Vec<MailboxStatistics> {
+        self.mailboxes
+            .snapshot()
+            .values()
+            .map(|mailbox| MailboxStatistics {
+                subscriber: mailbox.subscriber.clone(),
+                queued: MAILBOX_CAPACITY - mailbox.queue.capacity(),
+                capacity: MAILBOX_CAPACITY,
+            })
+            .collect()
+    }
+
+    fn mailbox(&self, subscriber: &TrackedSender<FeedUpdate>) -> Arc<Mailbox> {
+        if let Some(mailbox) = self.mailboxes.get(&subscriber.receiver_id) {
+            return mailbox;
+        }
+
+        let (queue, entries) = mpsc::channel(MAILBOX_CAPACITY);
+        let mailbox = Arc::new(Mailbox {
+            subscriber: subscriber.receiver_id.clone(),
+            queue,
+            slots: SharedMap::default(),
+            full_updates: AtomicU64::new(0),
+            gaps: Mutex::default(),
+            has_gaps: AtomicBool::new(false),
+        });
+        let current = self
+            .mailboxes
+            .insert_if_absent(subscriber.receiver_id.clone(), mailbox.clone());
+        if !Arc::ptr_eq(&current, &mailbox) {
+            return current;
+        }
+
+        tokio::spawn(run_forwarder(self.clone(), subscriber.clone(), entries));
+
+        mailbox
+    }
+}
+
+async fn run_forwarder(
+    conflator: Conflator,
+    subscriber: TrackedSender<FeedUpdate>,
+    mut entries: mpsc::Receiver<Entry>,
+) {
+    while let Some(entry) = entries.recv().await {
+        let Published {
+            update,
+            received_time,
+        } = match entry {
+            Entry::Latest(slot) => {
+                slot.queued.store(false, Ordering::Release);
+                match slot.update.swap(None) {
+                    Some(published) => Arc::unwrap_or_clone(published),
+                    None => continue,
+                }
+            }
+            Entry::Update(published) => published,
+        };
+        let delivered = stream(&update).map(|stream| (stream, audience(&update).0));
+
+        if subscriber.sender.send(update).await.is_err() {
+            eprintln!(
+                "Subscriber {} closed its feed channel.",
+                subscriber.receiver_id
+            );
+            conflator.mailboxes.remove(&subscriber.receiver_id);
+            return;
+        }
+
+        if let Some((stream, symbol)) = delivered {
+            conflator
+                .stats
+                .record_delivery(stream, &symbol, received_time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::side::Side;
+    use crate::feed::messages::l1_data::L1Data;
+    use crate::feed::messages::l2_data::L2Data;
+    use crate::feed::messages::l2_delta::L2Delta;
+    use crate::feed::messages::level::Level;
+    use crate::feed::messages::trade_data::TradeData;
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    fn subscriber(capacity: usize) -> (TrackedSender<FeedUpdate>, mpsc::Receiver<FeedUpdate>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (TrackedSender::new(sender, "context".to_owned()), receiver)
+    }
+
+    fn algo_ids() -> AlgoIds {
+        Arc::from(vec!["algo".to_owned()])
+    }
+
+    fn l1(bid_price: i64) -> FeedUpdate {
+        let l1_data = L1Data::new(
+            "btcusdt",
+            Decimal::ONE,
+            Decimal::from(bid_price),
+            Decimal::ONE,
+            Decimal::from(bid_price + 1),
+        );
+        FeedUpdate::L1Update(algo_ids(), Arc::new(l1_data))
+    }
+
+    fn l2(bid_price: i64) -> FeedUpdate {
+        let l2_data = L2Data::new(
+            "btcusdt",
+            vec![Level::new(1, Decimal::ONE, Decimal::from(bid_price))],
+            Vec::new(),
+        );
+        FeedUpdate::L2Update(algo_ids(), Arc::new(l2_data))
+    }
+
+    fn delta(bid_price: i64) -> FeedUpdate {
+        let l2_delta = L2Delta::new(
+            "btcusdt",
+            vec![Level::new(1, Decimal::ONE, Decimal::from(bid_price))],
+            Vec::new(),
+        );
+        FeedUpdate::L2Delta(algo_ids(), Arc::new(l2_delta))
+    }
+
+    fn trade(price: i64) -> FeedUpdate {
+        let trade = TradeData::new(
+            "btcusdt",
+            Decimal::from(price),
+            Decimal::ONE,
+            Side::Buy,
+            Utc::now(),
+        );
+        FeedUpdate::Trade(algo_ids(), Arc::new(trade))
+    }
+
+    fn status(state: FeedState) -> FeedUpdate {
+        FeedUpdate::Status(
+            algo_ids(),
+            Arc::new(FeedStatus::new("btcusdt", FeedStream::L1, state)),
+        )
+    }
+
+    /// What an update carries, to compare sequences of received updates.
+    fn describe(update: &FeedUpdate) -> String {
+        match update {
+            FeedUpdate::L1Update(_, l1_data) => format!("l1 {}", l1_data.best_bid_level.price),
+            FeedUpdate::L2Update(_, l2_data) => {
+                format!("l2 {}", l2_data.bid_side_levels[0].price)
+            }
+            FeedUpdate::L2Delta(_, l2_delta) => format!("delta {}", l2_delta.bid_changes[0].price),
+            FeedUpdate::Trade(_, trade) => format!("trade {}", trade.price),
+            FeedUpdate::Status(algo_ids, feed_status) => {
+                format!("{} {:?}", feed_status.state, algo_ids)
+            }
+            _ => "other".to_owned(),
+        }
+    }
+
+    async fn receive(receiver: &mut mpsc::Receiver<FeedUpdate>, count: usize) -> Vec<String> {
+        let mut received = Vec::with_capacity(count);
+        for _ in 0..count {
+            received.push(describe(&receiver.recv().await.unwrap()));
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn queued_snapshots_are_replaced_by_newer_ones() {
+        let conflator = Conflator::default();
+        let (subscriber, mut receiver) = subscriber(10);
+
+        for bid_price in 100..105 {
+            assert!(conflator.publish(&subscriber, l1(bid_price)));
+        }
+        conflator.publish(&subscriber, trade(200));
+
+        assert_eq!(receive(&mut receiver, 2).await, ["l1 104", "trade 200"]);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn snapshots_never_overtake_earlier_deltas() {
+        let conflator = Conflator::default();
+        let (subscriber, mut receiver) = subscriber(10);
+
+        conflator.publish(&subscriber, l2(100));
+        conflator.publish(&subscriber, delta(101));
+        conflator.publish(&subscriber, l2(102));
+        conflator.publish(&subscriber, l2(103));
+        conflator.publish(&subscriber, delta(104));
+
+        assert_eq!(
+            receive(&mut receiver, 4).await,
+            ["l2 100", "delta 101", "l2 103", "delta 104"]
+        );
+    }
+
+    #[tokio::test]
+    async fn ticks_after_a_status_are_delivered_after_it() {
+        let conflator = Conflator::default();
+        let (subscriber, mut receiver) = subscriber(10);
+
+        conflator.publish(&subscriber, l1(100));
+        conflator.publish(&subscriber, status(FeedState::Stale));
+        conflator.publish(&subscriber, l1(101));
+        conflator.publish(&subscriber, status(FeedState::Recovered));
+        conflator.publish(&subscriber, l1(102));
+        conflator.publish(&subscriber, l1(103));
+
+        assert_eq!(
+            receive(&mut receiver, 5).await,
+            [
+                "l1 100",
+                "STALE [\"algo\"]",
+                "l1 101",
+                "RECOVERED [\"algo\"]",
+                "l1 103"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn dropped_updates_are_followed_by_a_gap_status() {
+        let conflator = Conflator::default();
+        let (subscriber, mut receiver) = subscriber(1);
+
+        // The forwarder does not run until the test awaits, so the mailbox fills up.
+        for price in 0..MAILBOX_CAPACITY as i64 {
+            assert!(conflator.publish(&subscriber, trade(price)));
+        }
+        assert!(!conflator.publish(&subscriber, delta(1)));
+        assert!(!conflator.publish(&subscriber, l1(1)));
+
+        let received = receive(&mut receiver, MAILBOX_CAPACITY).await;
+        assert_eq!(
+            received.last().unwrap(),
+            &format!("trade {}", MAILBOX_CAPACITY - 1)
+        );
+
+        assert!(conflator.publish(&subscriber, l2(2)));
+        assert_eq!(receive(&mut receiver, 2).await, ["GAP [\"algo\"]", "l2 2"]);
+    }
+
+    #[tokio::test]
+    async fn queued_updates_and_deliveries_are_counted() {
+        let stats = FeedStats::default();
+        let conflator = Conflator::new(&stats);
+        let (subscriber, mut receiver) = subscriber(10);
+
+        conflator.publish(&subscriber, trade(100));
+        conflator.publish(&subscriber, trade(101));
+        let mailboxes = conflator.statistics();
+        assert_eq!(mailboxes.len(), 1);
+        assert_eq!(mailboxes[0].queued, 2);
+
+        receive(&mut receiver, 2).await;
+        assert_eq!(conflator.statistics()[0].queued, 0);
+
+        // The forwarder records a delivery once the send returned, after the test received it.
+        while stats.statistics().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        let trades = stats.statistics();
+        assert_eq!(trades[0].stream, "Trades");
+        assert_eq!(trades[0].symbol, "btcusdt");
+        assert_eq!(trades[0].total_updates, 0);
+    }
+}
@@ -0,0 +1,149 @@
+use super::messages::consolidated_l1::{ConsolidatedL1, VenueL1};
+use super::messages::l1_data::L1Data;
+use crate::common_types::instrument_id::InstrumentId;
+use crate::common_types::venue::Venue;
+use std::collections::BTreeMap;
+
+/// Latest top of book per venue for one instrument.
+pub(super) struct ConsolidatedBook {
+    symbol: InstrumentId,
+    quotes: BTreeMap<Venue, L1Data>,
+}
+
+impl ConsolidatedBook {
+    pub fn new<Symbol>(symbol: Symbol) -> Self
+    where
+        Symbol: Into<InstrumentId>,
+    {
+        Self {
+            symbol: symbol.into(),
+            quotes: BTreeMap::new(),
+        }
+    }
+
+    /// Stores the venue's quote and returns the consolidated view, or `None` while no venue
+    /// has a usable bid and ask.
+    pub fn update(&mut self, venue: Venue, l1_data: L1Data) -> Option<ConsolidatedL1> {
+        self.quotes.insert(venue, l1_data);
+
+        // Equal prices go to the venue showing more quantity.
+        let (best_bid_venue, best_bid) = self
+            .quotes
+            .iter()
+            .filter(|(_, quote)| !quote.best_bid_level.price.is_zero())
+            .max_by(|(_, a), (_, b)| {
+                (a.best_bid_level.price, a.best_bid_level.quantity)
+                    .cmp(&(b.best_bid_level.price, b.best_bid_level.quantity))
+            })?;
+        let (best_ask_venue, best_ask) = self
+            .quotes
+            .iter()
+            .filter(|(_, quote)| !quote.best_ask_level.price.is_zero())
+            .min_by(|(_, a), (_, b)| {
+                (a.best_ask_level.price, b.best_ask_level.quantity)
+                    .cmp(&(b.best_ask_level.price, a.best_ask_level.quantity))
+            })?;
+
+        Some(ConsolidatedL1 {
+            symbol: self.symbol.clone(),
+            consolidated: L1Data::new(
+                self.symbol.clone(),
+                best_bid.best_bid_level.quantity,
+                best_bid.best_bid_level.price,
+                best_ask.best_ask_level.quantity,
+                best_ask.best_ask_level.price,
+            ),
+            best_bid_venue: *best_bid_venue,
+            best_ask_venue: *best_ask_venue,
+            venues: self
+                .quotes
+                .iter()
+                .map(|(venue, l1_data)| VenueL1 {
+                    venue: *venue,
+                    l1_data: l1_data.clone(),
+                })
+                .collect(),
+        })
+    }
+
+    pub fn remove(&mut self, venue: Venue) {
+        self.quotes.remove(&venue);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn quote(bid: (i64, i64), ask: (i64, i64)) -> L1Data {
+        L1Data::new(
+            "BTCUSDT",
+            Decimal::from(bid.0),
+            Decimal::from(bid.1),
+            Decimal::from(ask.0),
+            Decimal::from(ask.1),
+        )
+    }
+
+    #[test]
+    fn best_bid_and_ask_can_come_from_different_venues() {
+        let mut book = ConsolidatedBook::new("BTCUSDT");
+
+        book.update(Venue::BinanceSpot, quote((1, 100), (1, 103)));
+        let consolidated = book
+            .update(Venue::Kraken, quote((1, 99), (1, 102)))
+            .unwrap();
+
+        assert_eq!(consolidated.best_bid_venue, Venue::BinanceSpot);
+        assert_eq!(consolidated.best_ask_venue, Venue::Kraken);
+        assert_eq!(
+            consolidated.consolidated.best_bid_level.price,
+            Decimal::from(100)
+        );
+        assert_eq!(
+            consolidated.consolidated.best_ask_level.price,
+            Decimal::from(102)
+        );
+        assert_eq!(consolidated.venues.len(), 2);
+    }
+
+    #[test]
+    fn equal_prices_go_to_the_venue_showing_more_quantity() {
+        let mut book = ConsolidatedBook::new("BTCUSDT");
+
+        book.update(Venue::BinanceSpot, quote((2, 100), (1, 101)));
+        let consolidated = book
+            .update(Venue::Kraken, quote((1, 100), (3, 101)))
+            .unwrap();
+
+        assert_eq!(consolidated.best_bid_venue, Venue::BinanceSpot);
+        assert_eq!(consolidated.best_ask_venue, Venue::Kraken);
+        assert_eq!(
+            consolidated.consolidated.best_bid_level.quantity,
+            Decimal::from(2)
+        );
+        assert_eq!(
+            consolidated.consolidated.best_ask_level.quantity,
+            Decimal::from(3)
+        );
+    }
+
+    #[test]
+    fn venues_without_a_price_are_skipped() {
+        let mut book = ConsolidatedBook::new("BTCUSDT");
+
+        assert!(book
+            .update(Venue::BinanceSpot, quote((0, 0), (1, 101)))
+            .is_none());
+        let consolidated = book.update(Venue::Kraken, quote((1, 99), (0, 0))).unwrap();
+
+        assert_eq!(consolidated.best_bid_venue, Venue::Kraken);
+        assert_eq!(consolidated.best_ask_venue, Venue::BinanceSpot);
+
+        book.remove(Venue::Kraken);
+        assert!(book
+            .update(Venue::BinanceSpot, quote((0, 0), (1, 101)))
+            .is_none());
+    }
+}
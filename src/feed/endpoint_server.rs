@@ -0,0 +1,32 @@
+use barter_data_sniper::exchange::binance::Binance;
+use barter_data_sniper::exchange::ExchangeServer;
+use barter_instrument_copy::exchange::ExchangeId;
+use std::sync::OnceLock;
+
+static WEBSOCKET_URL: OnceLock<String> = OnceLock::new();
+
+/// Binance spot behind the `[feed.endpoints]` websocket, such as the mock exchange, so
+/// barter parses its L1 and trades exactly as it parses the real exchange's.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub(super) struct EndpointServer;
+
+pub(super) type EndpointBinance = Binance<EndpointServer>;
+
+impl ExchangeServer for EndpointServer {
+    const ID: ExchangeId = ExchangeId::BinanceSpot;
+
+    fn websocket_url() -> &'static str {
+        WEBSOCKET_URL.get().map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// Barter asks the server type for its url, so there is one endpoint per process.
+pub(super) fn set_websocket_url(url: &str) -> Result<(), String> {
+    let current = WEBSOCKET_URL.get_or_init(|| url.to_owned());
+
+    if current == url {
+        Ok(())
+    } else {
+        Err(format!("the websocket endpoint is already {}", current))
+    }
+}
@@ -0,0 +1,1240 @@
+use super::analytics_engine::AnalyticsCache;
+use super::conflation::Conflator;
+use super::feed_stats::FeedStats;
+use super::history_loader::HistoryLoader;
+use super::messages::candle::{CandleInterval, CandleSource};
+use super::messages::feed_snapshot::FeedSnapshot;
+use super::messages::feed_status::{FeedState, FeedStatus, FeedStream};
+use super::messages::messages::{AlgoIds, FeedUpdate};
+use super::messages::ticker::Ticker;
+use super::recorder::Recorder;
+use super::sanity::SanityFilter;
+use super::shared_map::SharedMap;
+use super::FeedMessages;
+use crate::common_types::instrument_id::{self, InstrumentId};
+use crate::common_types::tracked_sender::TrackedSender;
+use crate::config::{
+    AnalyticsConfig, Environment, FeedConfig, ReplayConfig, SimulatorConfig, StalenessConfig,
+    StatsConfig, SyntheticConfig, VenuesConfig,
+};
+use binance::config::Config as BinanceConfig;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self};
+use tokio::task::{self};
+
+mod publish;
+mod registry;
+mod rest;
+mod runners;
+mod streams;
+
+use registry::{
+    AnalyticsSubscribers, L1Subscribers, L2Subscribers, L2Subscription, Registry, Subscribers,
+    TickerSubscribers,
+};
+use rest::fetch_ticker;
+use runners::{
+    run_analytics_feed, run_consolidated_l1_feed, run_exchange_candle_feed, run_l1_feed,
+    run_l2_feed, run_local_candle_feed, run_local_ticker_feed, run_replay_feed, run_simulated_feed,
+    run_synthetic_feed, run_ticker_feed, run_trade_feed,
+};
+use streams::BinanceServer;
+
+type AlgoId = String;
+
+const CACHED_L2_DEPTH: usize = 100;
+const ENGINE_CHANNEL_SIZE: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum FeedKind {
+    L1,
+    ConsolidatedL1,
+    L2,
+    Trades,
+    Candles(CandleInterval, CandleSource),
+    Ticker,
+}
+
+/// The feeds of an instrument a replay publishes to.
+type ReplayKinds = Arc<SharedMap<FeedKind, ()>>;
+
+/// The replay of one instrument's recording, shared by all of its feeds.
+struct Replay {
+    kinds: ReplayKinds,
+    handle: task::JoinHandle<()>,
+}
+
+impl FeedKind {
+    fn feed_id(&self, instrument: &InstrumentId) -> InstrumentId {
+        match self {
+            FeedKind::Candles(interval, source) => {
+                candle_feed_id(instrument, *interval, *source).into()
+            }
+            _ => instrument.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct FeedSubscribers {
+    l1: L1Subscribers,
+    consolidated_l1: Subscribers,
+    l2: L2Subscribers,
+    trades: Subscribers,
+    candles: Subscribers,
+    analytics: AnalyticsSubscribers,
+    tickers: TickerSubscribers,
+}
+
+impl FeedSubscribers {
+    fn has_subscriber(&self, subscriber: &TrackedSender<FeedUpdate>) -> bool {
+        self.l1.has_subscriber(subscriber)
+            || self.consolidated_l1.has_subscriber(subscriber)
+            || self.l2.has_subscriber(subscriber)
+            || self.trades.has_subscriber(subscriber)
+            || self.candles.has_subscriber(subscriber)
+            || self.analytics.has_subscriber(subscriber)
+            || self.tickers.has_subscriber(subscriber)
+    }
+
+    /// Drops the subscriber's conflation mailbox once it has no subscriptions left.
+    fn release(&self, subscriber: &TrackedSender<FeedUpdate>) {
+        self.l1
+            .conflator
+            .release(subscriber, self.has_subscriber(subscriber));
+    }
+}
+
+fn unsubscriber(msg: &FeedMessages) -> Option<TrackedSender<FeedUpdate>> {
+    match msg {
+        FeedMessages::UnsubscribeFromL1 { subscriber, .. }
+        | FeedMessages::UnsubscribeFromConsolidatedL1 { subscriber, .. }
+        | FeedMessages::UnsubscribeFromL2 { subscriber, .. }
+        | FeedMessages::UnsubscribeFromTrades { subscriber, .. }
+        | FeedMessages::UnsubscribeFromCandles { subscriber, .. }
+        | FeedMessages::UnsubscribeFromAnalytics { subscriber, .. }
+        | FeedMessages::UnsubscribeFromTicker { subscriber, .. } => Some(subscriber.clone()),
+        _ => None,
+    }
+}
+
+fn candle_feed_id(instrument: &str, interval: CandleInterval, source: CandleSource) -> String {
+    format!("{}@{}@{}", instrument, interval, source)
+}
+
+pub(super) struct FeedActor {
+    receiver: mpsc::Receiver<FeedMessages>,
+    environment: Environment,
+    binance_config: BinanceConfig,
+    simulator_config: SimulatorConfig,
+    l1_feeds: HashMap<InstrumentId, task::JoinHandle<()>>,
+    consolidated_l1_feeds: HashMap<InstrumentId, task::JoinHandle<()>>,
+    l2_feeds: HashMap<InstrumentId, task::JoinHandle<()>>,
+    trade_feeds: HashMap<InstrumentId, task::JoinHandle<()>>,
+    candle_feeds: HashMap<InstrumentId, task::JoinHandle<()>>,
+    analytics_feeds: HashMap<InstrumentId, task::JoinHandle<()>>,
+    ticker_feeds: HashMap<InstrumentId, task::JoinHandle<()>>,
+    replays: HashMap<InstrumentId, Replay>,
+    subscribers: FeedSubscribers,
+    replay_config: Option<ReplayConfig>,
+    staleness_config: StalenessConfig,
+    stats_config: StatsConfig,
+    stats: FeedStats,
+    conflator: Conflator,
+    venues_config: VenuesConfig,
+    synthetics: Vec<SyntheticConfig>,
+    analytics_config: AnalyticsConfig,
+    engine_subscribers: HashMap<String, TrackedSender<FeedUpdate>>,
+    history_loader: HistoryLoader,
+    binance_server: BinanceServer,
+}
+
+impl FeedActor {
+    pub fn new(
+        receiver: mpsc::Receiver<FeedMessages>,
+        environment: Environment,
+        simulator_config: SimulatorConfig,
+        feed_config: FeedConfig,
+    ) -> Self {
+        let stats = FeedStats::default();
+        let conflator = Conflator::new(&stats);
+        let sanity = SanityFilter::new(feed_config.sanity.clone());
+        let history_loader = HistoryLoader::new(&environment, &feed_config);
+        let binance_config = feed_config.binance_config(&environment);
+        let binance_server = BinanceServer::new(&environment, &feed_config);
+        // Replayed data is already on disk.
+        let recorder = match feed_config.replay {
+            Some(_) => None,
+            None => Recorder::start(&feed_config.recorder),
+        };
+
+        FeedActor {
+            receiver,
+            environment,
+            binance_config,
+            binance_server,
+            simulator_config,
+            l1_feeds: HashMap::new(),
+            consolidated_l1_feeds: HashMap::new(),
+            l2_feeds: HashMap::new(),
+            trade_feeds: HashMap::new(),
+            candle_feeds: HashMap::new(),
+            analytics_feeds: HashMap::new(),
+            ticker_feeds: HashMap::new(),
+            replays: HashMap::new(),
+            subscribers: FeedSubscribers {
+                l1: Registry::new(&conflator, &stats, &sanity).recording(&recorder),
+                consolidated_l1: Registry::new(&conflator, &stats, &sanity).recording(&recorder),
+                l2: Registry::new(&conflator, &stats, &sanity).recording(&recorder),
+                trades: Registry::new(&conflator, &stats, &sanity).recording(&recorder),
+                candles: Registry::new(&conflator, &stats, &sanity),
+                analytics: Registry::new(&conflator, &stats, &sanity),
+                tickers: Registry::new(&conflator, &stats, &sanity),
+            },
+            replay_config: feed_config.replay,
+            staleness_config: feed_config.staleness,
+            stats_config: feed_config.stats,
+            stats,
+            conflator,
+            venues_config: feed_config.venues,
+            synthetics: feed_config.synthetics,
+            analytics_config: feed_config.analytics,
+            engine_subscribers: HashMap::new(),
+            history_loader,
+        }
+    }
+
+    fn start_feed(&mut self, kind: FeedKind, base: &str, quote: &str) {
+        let instrument = instrument_id::intern(&(base.to_owned() + quote));
+        let feed_id = kind.feed_id(&instrument);
+        let builds_exchange_data = self.builds_exchange_data();
+        let feeds = match kind {
+            FeedKind::L1 => &mut self.l1_feeds,
+            FeedKind::ConsolidatedL1 => &mut self.consolidated_l1_feeds,
+            FeedKind::L2 => &mut self.l2_feeds,
+            FeedKind::Trades => &mut self.trade_feeds,
+            FeedKind::Candles(..) => &mut self.candle_feeds,
+            FeedKind::Ticker => &mut self.ticker_feeds,
+        };
+
+        if feeds
+            .get(&feed_id)
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return;
+        }
+
+        eprintln!("Opening {:?} subscription for {}.", kind, instrument);
+
+        if let Some(replay_config) = &self.replay_config {
+            if let Some(replay) = self
+                .replays
+                .get(&instrument)
+                .filter(|replay| !replay.handle.is_finished())
+            {
+                replay.kinds.insert(kind, Arc::new(()));
+                return;
+            }
+
+            let kinds = ReplayKinds::default();
+            kinds.insert(kind, Arc::new(()));
+            let handle = tokio::spawn(run_replay_feed(
+                instrument.clone(),
+                replay_config.clone(),
+                kinds.clone(),
+                self.subscribers.clone(),
+            ));
+            self.replays.insert(instrument, Replay { kinds, handle });
+            return;
+        }
+
+        let handle = match (&self.environment, kind) {
+            (Environment::Simulator, _) => {
+                let Some(reference_price) = self.simulator_config.reference_price(base, quote)
+                else {
+                    eprintln!(
+                        "No simulator reference price configured for {}, skipping simulated feed.",
+                        instrument
+                    );
+                    return;
+                };
+
+                tokio::spawn(run_simulated_feed(
+                    kind,
+                    instrument.clone(),
+                    reference_price,
+                    Duration::from_millis(self.simulator_config.tick_interval_ms),
+                    self.subscribers.clone(),
+                ))
+            }
+            (_, FeedKind::L1) => tokio::spawn(run_l1_feed(
+                self.binance_server,
+                base.to_owned(),
+                quote.to_owned(),
+                self.subscribers.l1.clone(),
+                self.staleness_config.threshold(&instrument),
+            )),
+            (environment, FeedKind::ConsolidatedL1) => tokio::spawn(run_consolidated_l1_feed(
+                environment.clone(),
+                base.to_owned(),
+                quote.to_owned(),
+                self.venues_config.venues(&instrument),
+                self.subscribers.consolidated_l1.clone(),
+            )),
+            (_, FeedKind::L2) => tokio::spawn(run_l2_feed(
+                self.binance_config.clone(),
+                base.to_owned(),
+                quote.to_owned(),
+                self.subscribers.l2.clone(),
+                self.staleness_config.threshold(&instrument),
+            )),
+            (_, FeedKind::Trades) => tokio::spawn(run_trade_feed(
+                self.binance_server,
+                base.to_owned(),
+                quote.to_owned(),
+                self.subscribers.trades.clone(),
+            )),
+            (_, FeedKind::Candles(interval, CandleSource::Exchange)) if !builds_exchange_data => {
+                tokio::spawn(run_exchange_candle_feed(
+                    self.binance_config.clone(),
+                    instrument.clone(),
+                    interval,
+                    self.subscribers.candles.clone(),
+                ))
+            }
+            (_, FeedKind::Candles(interval, source)) => tokio::spawn(run_local_candle_feed(
+                self.binance_server,
+                base.to_owned(),
+                quote.to_owned(),
+                interval,
+                source,
+                self.subscribers.candles.clone(),
+            )),
+            (_, FeedKind::Ticker) if builds_exchange_data => tokio::spawn(run_local_ticker_feed(
+                self.binance_server,
+                base.to_owned(),
+                quote.to_owned(),
+                self.subscribers.tickers.clone(),
+            )),
+            (_, FeedKind::Ticker) => tokio::spawn(run_ticker_feed(
+                self.binance_config.clone(),
+                instrument.clone(),
+                self.subscribers.tickers.clone(),
+            )),
+        };
+
+        feeds.insert(feed_id, handle);
+    }
+
+    /// Stops the feed, or takes it out of the instrument's replay. A replay keeps running
+    /// without feeds, so feeds subscribed later join it where it is.
+    fn stop_feed(&mut self, kind: FeedKind, instrument: &str) {
+        let feed_id = &*kind.feed_id(&instrument_id::intern(instrument));
+        if let Some(replay) = self.replays.get(instrument) {
+            replay.kinds.remove(&kind);
+        }
+
+        let feeds = match kind {
+            FeedKind::L1 => &mut self.l1_feeds,
+            FeedKind::ConsolidatedL1 => &mut self.consolidated_l1_feeds,
+            FeedKind::L2 => &mut self.l2_feeds,
+            FeedKind::Trades => &mut self.trade_feeds,
+            FeedKind::Candles(..) => &mut self.candle_feeds,
+            FeedKind::Ticker => &mut self.ticker_feeds,
+        };
+
+        match kind {
+            FeedKind::L1 => self.subscribers.l1.clear_last_value(feed_id),
+            FeedKind::L2 => self.subscribers.l2.clear_last_value(feed_id),
+            FeedKind::Ticker => self.subscribers.tickers.clear_last_value(feed_id),
+            FeedKind::ConsolidatedL1 | FeedKind::Trades | FeedKind::Candles(..) => {}
+        }
+
+        if let Some(handle) = feeds.remove(feed_id) {
+            eprintln!("Closing {:?} subscription for {}.", kind, feed_id);
+            handle.abort();
+        }
+    }
+
+    /// The `[feed.endpoints]` mock exchange serves no klines or tickers, so exchange candles
+    /// and tickers are built from its trades instead.
+    fn builds_exchange_data(&self) -> bool {
+        matches!(self.binance_server, BinanceServer::Endpoints)
+    }
+
+    fn synthetic(&self, instrument: &str) -> Option<SyntheticConfig> {
+        self.synthetics
+            .iter()
+            .find(|synthetic| synthetic.symbol().eq_ignore_ascii_case(instrument))
+            .cloned()
+    }
+
+    /// Subscribes the synthetic engine to the L1 feeds of both legs, the same way an
+    /// algorithm would.
+    async fn start_synthetic_feed(&mut self, synthetic: SyntheticConfig, instrument: InstrumentId) {
+        if self
+            .l1_feeds
+            .get(&instrument)
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return;
+        }
+
+        eprintln!("Opening synthetic L1 subscription for {}.", instrument);
+
+        let engine_id = format!("synthetic:{}", instrument);
+        let (sender, receiver) = mpsc::channel(ENGINE_CHANNEL_SIZE);
+        let subscriber = TrackedSender::new(sender, engine_id.clone());
+
+        for (base, quote) in synthetic.legs() {
+            let leg = base.clone() + quote.as_str();
+
+            if let Some(l1_data) = self.subscribers.l1.last_value(&leg) {
+                self.subscribers.l1.conflator.publish(
+                    &subscriber,
+                    FeedUpdate::L1Update(Arc::from(vec![engine_id.clone()]), l1_data),
+                );
+            }
+
+            self.subscribers
+                .l1
+                .subscribe(&leg, &subscriber, &engine_id)
+                .await;
+            self.start_feed(FeedKind::L1, &base, &quote);
+        }
+
+        let handle = tokio::spawn(run_synthetic_feed(
+            receiver,
+            synthetic,
+            self.subscribers.l1.clone(),
+        ));
+        self.l1_feeds.insert(instrument, handle);
+        self.engine_subscribers.insert(engine_id, subscriber);
+    }
+
+    /// Drops the synthetic engine's leg subscriptions, closing legs nobody else uses.
+    async fn stop_synthetic_feed(&mut self, instrument: &str) {
+        let (Some(synthetic), Some(subscriber)) = (
+            self.synthetic(instrument),
+            self.engine_subscribers
+                .remove(&format!("synthetic:{}", instrument)),
+        ) else {
+            return;
+        };
+
+        for (base, quote) in synthetic.legs() {
+            let leg = base + quote.as_str();
+
+            if self
+                .subscribers
+                .l1
+                .unsubscribe(&leg, &subscriber, &subscriber.receiver_id)
+                .await
+            {
+                self.stop_feed(FeedKind::L1, &leg);
+            }
+        }
+        self.subscribers.release(&subscriber);
+    }
+
+    async fn start_l1_feed(&mut self, base: &str, quote: &str) {
+        let instrument = instrument_id::intern(&(base.to_owned() + quote));
+
+        match self.synthetic(&instrument) {
+            Some(synthetic) => self.start_synthetic_feed(synthetic, instrument).await,
+            None => self.start_feed(FeedKind::L1, base, quote),
+        }
+    }
+
+    async fn stop_l1_feed(&mut self, instrument: &str) {
+        self.stop_feed(FeedKind::L1, instrument);
+        self.stop_synthetic_feed(instrument).await;
+    }
+
+    /// Feeds the analytics engine from the instrument's L1 and trades, the same way an
+    /// algorithm would subscribe to them.
+    async fn start_analytics_feed(&mut self, base: &str, quote: &str) {
+        let instrument = instrument_id::intern(&(base.to_owned() + quote));
+
+        if self
+            .analytics_feeds
+            .get(&instrument)
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return;
+        }
+
+        eprintln!("Opening analytics for {}.", instrument);
+
+        let engine_id = format!("analytics:{}", instrument);
+        let (sender, receiver) = mpsc::channel(ENGINE_CHANNEL_SIZE);
+        let subscriber = TrackedSender::new(sender, engine_id.clone());
+
+        if let Some(l1_data) = self.subscribers.l1.last_value(&instrument) {
+            self.subscribers.l1.conflator.publish(
+                &subscriber,
+                FeedUpdate::L1Update(Arc::from(vec![engine_id.clone()]), l1_data),
+            );
+        }
+
+        self.subscribers
+            .l1
+            .subscribe(&instrument, &subscriber, &engine_id)
+            .await;
+        self.subscribers
+            .trades
+            .subscribe(&instrument, &subscriber, &engine_id)
+            .await;
+        self.start_l1_feed(base, quote).await;
+        self.start_feed(FeedKind::Trades, base, quote);
+
+        let handle = tokio::spawn(run_analytics_feed(
+            receiver,
+            instrument.clone(),
+            self.analytics_config.clone(),
+            self.subscribers.analytics.clone(),
+        ));
+        self.analytics_feeds.insert(instrument, handle);
+        self.engine_subscribers.insert(engine_id, subscriber);
+    }
+
+    async fn stop_analytics_feed(&mut self, instrument: &str) {
+        if let Some(handle) = self.analytics_feeds.remove(instrument) {
+            eprintln!("Closing analytics for {}.", instrument);
+            handle.abort();
+        }
+        self.subscribers.analytics.clear_last_value(instrument);
+
+        let Some(subscriber) = self
+            .engine_subscribers
+            .remove(&format!("analytics:{}", instrument))
+        else {
+            return;
+        };
+
+        if self
+            .subscribers
+            .l1
+            .unsubscribe(instrument, &subscriber, &subscriber.receiver_id)
+            .await
+        {
+            self.stop_l1_feed(instrument).await;
+        }
+        if self
+            .subscribers
+            .trades
+            .unsubscribe(instrument, &subscriber, &subscriber.receiver_id)
+            .await
+        {
+            self.stop_feed(FeedKind::Trades, instrument);
+        }
+        self.subscribers.release(&subscriber);
+    }
+
+    pub fn analytics_cache(&self) -> AnalyticsCache {
+        AnalyticsCache::new(self.subscribers.analytics.last_values.clone())
+    }
+
+    async fn handle_message(&mut self, msg: FeedMessages) {
+        match msg {
+            FeedMessages::SubscribeToL1 {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base.clone() + quote.as_str();
+
+                self.subscribers.l1.publish_cached(
+                    &instrument,
+                    FeedStream::L1,
+                    &subscriber,
+                    &algo_id,
+                    FeedUpdate::L1Update,
+                );
+
+                self.subscribers
+                    .l1
+                    .subscribe(&instrument, &subscriber, &algo_id)
+                    .await;
+
+                self.start_l1_feed(&base, &quote).await;
+            }
+
+            FeedMessages::UnsubscribeFromL1 {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base + quote.as_str();
+
+                if self
+                    .subscribers
+                    .l1
+                    .unsubscribe(&instrument, &subscriber, &algo_id)
+                    .await
+                {
+                    self.stop_l1_feed(&instrument).await;
+                }
+            }
+            FeedMessages::SubscribeToConsolidatedL1 {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base.clone() + quote.as_str();
+
+                self.subscribers
+                    .consolidated_l1
+                    .subscribe(&instrument, &subscriber, &algo_id)
+                    .await;
+
+                self.start_feed(FeedKind::ConsolidatedL1, &base, &quote);
+            }
+
+            FeedMessages::UnsubscribeFromConsolidatedL1 {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base + quote.as_str();
+
+                if self
+                    .subscribers
+                    .consolidated_l1
+                    .unsubscribe(&instrument, &subscriber, &algo_id)
+                    .await
+                {
+                    self.stop_feed(FeedKind::ConsolidatedL1, &instrument);
+                }
+            }
+            FeedMessages::SubscribeToL2 {
+                algo_id,
+                base,
+                quote,
+                depth,
+                mode,
+                subscriber,
+            } => {
+                let instrument = base.clone() + quote.as_str();
+                let depth = depth.max(1);
+                let mut needs_snapshot = true;
+
+                if self.subscribers.l2.publish_cached(
+                    &instrument,
+                    FeedStream::L2,
+                    &subscriber,
+                    &algo_id,
+                    |algo_ids, l2_data| {
+                        FeedUpdate::L2Update(algo_ids, Arc::new(l2_data.truncated(depth)))
+                    },
+                ) {
+                    needs_snapshot = depth > CACHED_L2_DEPTH;
+                }
+
+                let subscription = L2Subscription {
+                    algo_id,
+                    depth,
+                    mode,
+                    needs_snapshot: Arc::new(AtomicBool::new(needs_snapshot)),
+                };
+
+                self.subscribers
+                    .l2
+                    .write(&instrument)
+                    .await
+                    .entry(subscriber.receiver_id.clone())
+                    .or_insert_with(|| (subscriber, Vec::new()))
+                    .1
+                    .push(subscription);
+
+                self.start_feed(FeedKind::L2, &base, &quote);
+            }
+
+            FeedMessages::UnsubscribeFromL2 {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base + quote.as_str();
+                let mut senders = self.subscribers.l2.write(&instrument).await;
+
+                if let Some((_, subscriptions)) = senders.get_mut(&subscriber.receiver_id) {
+                    subscriptions.retain(|subscription| subscription.algo_id != algo_id);
+
+                    if subscriptions.is_empty() {
+                        senders.remove(&subscriber.receiver_id);
+                    }
+
+                    if senders.is_empty() {
+                        drop(senders);
+
+                        self.stop_feed(FeedKind::L2, &instrument);
+                    }
+                }
+            }
+            FeedMessages::SubscribeToTrades {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base.clone() + quote.as_str();
+
+                self.subscribers
+                    .trades
+                    .subscribe(&instrument, &subscriber, &algo_id)
+                    .await;
+
+                self.start_feed(FeedKind::Trades, &base, &quote);
+            }
+
+            FeedMessages::UnsubscribeFromTrades {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base + quote.as_str();
+
+                if self
+                    .subscribers
+                    .trades
+                    .unsubscribe(&instrument, &subscriber, &algo_id)
+                    .await
+                {
+                    self.stop_feed(FeedKind::Trades, &instrument);
+                }
+            }
+            FeedMessages::SubscribeToCandles {
+                algo_id,
+                base,
+                quote,
+                interval,
+                source,
+                subscriber,
+            } => {
+                let feed_id = candle_feed_id(&(base.clone() + quote.as_str()), interval, source);
+
+                self.subscribers
+                    .candles
+                    .subscribe(&feed_id, &subscriber, &algo_id)
+                    .await;
+
+                self.start_feed(FeedKind::Candles(interval, source), &base, &quote);
+            }
+
+            FeedMessages::UnsubscribeFromCandles {
+                algo_id,
+                base,
+                quote,
+                interval,
+                source,
+                subscriber,
+            } => {
+                let instrument = base + quote.as_str();
+                let feed_id = candle_feed_id(&instrument, interval, source);
+
+                if self
+                    .subscribers
+                    .candles
+                    .unsubscribe(&feed_id, &subscriber, &algo_id)
+                    .await
+                {
+                    self.stop_feed(FeedKind::Candles(interval, source), &instrument);
+                }
+            }
+
+            FeedMessages::SubscribeToAnalytics {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base.clone() + quote.as_str();
+
+                if let Some(analytics) = self.subscribers.analytics.last_value(&instrument) {
+                    self.subscribers.analytics.conflator.publish(
+                        &subscriber,
+                        FeedUpdate::Analytics(Arc::from(vec![algo_id.clone()]), analytics),
+                    );
+                }
+
+                self.subscribers
+                    .analytics
+                    .subscribe(&instrument, &subscriber, &algo_id)
+                    .await;
+
+                self.start_analytics_feed(&base, &quote).await;
+            }
+
+            FeedMessages::UnsubscribeFromAnalytics {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base + quote.as_str();
+
+                if self
+                    .subscribers
+                    .analytics
+                    .unsubscribe(&instrument, &subscriber, &algo_id)
+                    .await
+                {
+                    self.stop_analytics_feed(&instrument).await;
+                }
+            }
+
+            FeedMessages::SubscribeToTicker {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base.clone() + quote.as_str();
+
+                if let Some(ticker) = self.subscribers.tickers.last_value(&instrument) {
+                    self.subscribers.tickers.conflator.publish(
+                        &subscriber,
+                        FeedUpdate::Ticker(Arc::from(vec![algo_id.clone()]), ticker),
+                    );
+                }
+
+                self.subscribers
+                    .tickers
+                    .subscribe(&instrument, &subscriber, &algo_id)
+                    .await;
+
+                self.start_feed(FeedKind::Ticker, &base, &quote);
+            }
+
+            FeedMessages::UnsubscribeFromTicker {
+                algo_id,
+                base,
+                quote,
+                subscriber,
+            } => {
+                let instrument = base + quote.as_str();
+
+                if self
+                    .subscribers
+                    .tickers
+                    .unsubscribe(&instrument, &subscriber, &algo_id)
+                    .await
+                {
+                    self.stop_feed(FeedKind::Ticker, &instrument);
+                }
+            }
+
+            FeedMessages::GetTicker {
+                base,
+                quote,
+                responder,
+            } => {
+                let instrument = base.clone() + quote.as_str();
+
+                if let Some(ticker) = self.subscribers.tickers.last_value(&instrument) {
+                    responder
+                        .send(Ok(ticker.as_ref().clone()))
+                        .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                    return;
+                }
+
+                if self.replay_config.is_some() {
+                    responder
+                        .send(Err(format!(
+                            "no ticker for {} before the replay publishes a trade",
+                            instrument
+                        )))
+                        .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                    return;
+                }
+
+                if self.environment == Environment::Simulator {
+                    let ticker = self
+                        .simulator_config
+                        .reference_price(&base, &quote)
+                        .map(|reference_price| Ticker {
+                            symbol: instrument.as_str().into(),
+                            last_price: reference_price,
+                            open_price: reference_price,
+                            high_price: reference_price,
+                            low_price: reference_price,
+                            volume: Decimal::ZERO,
+                            price_change_pct: Decimal::ZERO,
+                            close_time: Utc::now(),
+                        })
+                        .ok_or_else(|| format!("no simulator reference price for {}", instrument));
+
+                    responder
+                        .send(ticker)
+                        .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                    return;
+                }
+
+                if self.builds_exchange_data() {
+                    responder
+                        .send(Err(format!(
+                            "no ticker for {} until its trades have been streamed from [feed.endpoints]",
+                            instrument
+                        )))
+                        .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                    return;
+                }
+
+                let binance_config = self.binance_config.clone();
+                tokio::spawn(async move {
+                    let ticker = fetch_ticker(binance_config, &instrument)
+                        .await
+                        .map_err(|error| {
+                            format!("failed to fetch the ticker for {}: {}", instrument, error)
+                        });
+
+                    responder
+                        .send(ticker)
+                        .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                });
+            }
+
+            FeedMessages::RequestSnapshot {
+                algo_id,
+                base,
+                quote,
+                depth,
+                subscriber,
+            } => {
+                let instrument = base + quote.as_str();
+                let stale_streams = [
+                    (FeedStream::L1, self.subscribers.l1.is_stale(&instrument)),
+                    (FeedStream::L2, self.subscribers.l2.is_stale(&instrument)),
+                ];
+
+                let snapshot = FeedSnapshot {
+                    l1: self
+                        .subscribers
+                        .l1
+                        .last_value(&instrument)
+                        .map(|l1_data| l1_data.as_ref().clone()),
+                    l2: self
+                        .subscribers
+                        .l2
+                        .last_value(&instrument)
+                        .map(|l2_data| l2_data.truncated(depth)),
+                    symbol: instrument_id::intern(&instrument),
+                };
+
+                let algo_ids: AlgoIds = Arc::from(vec![algo_id]);
+                let conflator = &self.subscribers.l1.conflator;
+                for (stream, stale) in stale_streams {
+                    if !stale {
+                        continue;
+                    }
+                    let feed_status =
+                        FeedStatus::new(snapshot.symbol.clone(), stream, FeedState::Stale);
+                    conflator.publish(
+                        &subscriber,
+                        FeedUpdate::Status(algo_ids.clone(), Arc::new(feed_status)),
+                    );
+                }
+                conflator.publish(
+                    &subscriber,
+                    FeedUpdate::Snapshot(algo_ids, Arc::new(snapshot)),
+                );
+            }
+
+            FeedMessages::RequestHistory {
+                algo_id,
+                base,
+                quote,
+                request,
+                subscriber,
+            } => {
+                let history_loader = self.history_loader.clone();
+                let conflator = self.subscribers.l1.conflator.clone();
+
+                tokio::spawn(async move {
+                    let history = history_loader
+                        .load(instrument_id::intern(&(base + quote.as_str())), request)
+                        .await;
+
+                    conflator.publish(
+                        &subscriber,
+                        FeedUpdate::History(Arc::from(vec![algo_id]), Arc::new(history)),
+                    );
+                });
+            }
+
+            FeedMessages::GetStatistics { responder } => {
+                responder
+                    .send(self.stats.statistics())
+                    .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+            }
+
+            FeedMessages::GetMailboxStatistics { responder } => {
+                responder
+                    .send(self.conflator.statistics())
+                    .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+            }
+        }
+    }
+}
+
+/// The L1 publishing path without an exchange feed behind it, for the benches.
+#[cfg(feature = "bench")]
+pub struct L1Publisher {
+    subscribers: L1Subscribers,
+}
+
+#[cfg(feature = "bench")]
+impl L1Publisher {
+    pub fn new(sanity: crate::config::SanityConfig) -> Self {
+        let stats = FeedStats::default();
+        Self {
+            subscribers: Registry::new(&Conflator::new(&stats), &stats, &SanityFilter::new(sanity)),
+        }
+    }
+
+    pub async fn subscribe(
+        &self,
+        instrument: &str,
+        subscriber: &TrackedSender<FeedUpdate>,
+        algo_id: &str,
+    ) {
+        self.subscribers
+            .subscribe(instrument, subscriber, algo_id)
+            .await;
+    }
+
+    pub async fn unsubscribe(
+        &self,
+        instrument: &str,
+        subscriber: &TrackedSender<FeedUpdate>,
+        algo_id: &str,
+    ) {
+        self.subscribers
+            .unsubscribe(instrument, subscriber, algo_id)
+            .await;
+    }
+
+    pub fn publish(
+        &self,
+        l1_data: super::messages::l1_data::L1Data,
+        exchange_time: chrono::DateTime<Utc>,
+    ) {
+        publish::publish_l1(&self.subscribers, l1_data, exchange_time);
+    }
+}
+
+pub(super) async fn run_my_actor(mut actor: FeedActor) {
+    if actor.stats_config.log_interval_secs > 0 {
+        let stats = actor.stats.clone();
+        let conflator = actor.conflator.clone();
+        let log_interval = Duration::from_secs(actor.stats_config.log_interval_secs);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(log_interval);
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                for statistics in stats.roll_window() {
+                    eprintln!("Feed statistics: {}", statistics);
+                }
+                for statistics in conflator.statistics() {
+                    eprintln!("Feed statistics: {}", statistics);
+                }
+            }
+        });
+    }
+
+    while let Some(msg) = actor.receiver.recv().await {
+        let unsubscriber = unsubscriber(&msg);
+
+        actor.handle_message(msg).await;
+
+        if let Some(subscriber) = unsubscriber {
+            actor.subscribers.release(&subscriber);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::publish::{publish_l1, publish_status};
+    use super::*;
+    use crate::config::SanityConfig;
+    use crate::feed::messages::l1_data::L1Data;
+
+    fn l1_subscribers() -> L1Subscribers {
+        Registry::new(
+            &Conflator::default(),
+            &FeedStats::default(),
+            &SanityFilter::new(SanityConfig::default()),
+        )
+    }
+
+    fn l1(bid_price: i64) -> L1Data {
+        L1Data::new(
+            "btcusdt",
+            Decimal::ONE,
+            Decimal::from(bid_price),
+            Decimal::ONE,
+            Decimal::from(bid_price + 1),
+        )
+    }
+
+    fn subscriber() -> (TrackedSender<FeedUpdate>, mpsc::Receiver<FeedUpdate>) {
+        let (sender, receiver) = mpsc::channel(10);
+        (TrackedSender::new(sender, "context".to_owned()), receiver)
+    }
+
+    #[tokio::test]
+    async fn subscribers_after_a_stale_status_get_the_status_before_the_cached_value() {
+        let subscribers = l1_subscribers();
+        publish_l1(&subscribers, l1(100), Utc::now());
+        publish_status(
+            &subscribers,
+            FeedStatus::new("btcusdt", FeedStream::L1, FeedState::Stale),
+        );
+
+        let (subscriber, mut receiver) = subscriber();
+        assert!(subscribers.publish_cached(
+            "btcusdt",
+            FeedStream::L1,
+            &subscriber,
+            "algo",
+            FeedUpdate::L1Update
+        ));
+
+        match receiver.recv().await.unwrap() {
+            FeedUpdate::Status(_, feed_status) => assert_eq!(feed_status.state, FeedState::Stale),
+            update => panic!("expected a stale status, got {:?}", update),
+        }
+        match receiver.recv().await.unwrap() {
+            FeedUpdate::L1Update(algo_ids, l1_data) => {
+                assert_eq!(algo_ids.as_ref(), ["algo"]);
+                assert_eq!(l1_data.best_bid_level.price, Decimal::from(100));
+            }
+            update => panic!("expected the cached L1, got {:?}", update),
+        }
+
+        publish_status(
+            &subscribers,
+            FeedStatus::new("btcusdt", FeedStream::L1, FeedState::Recovered),
+        );
+        assert!(subscribers.publish_cached(
+            "btcusdt",
+            FeedStream::L1,
+            &subscriber,
+            "algo",
+            FeedUpdate::L1Update
+        ));
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            FeedUpdate::L1Update(..)
+        ));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn reconnecting_feeds_drop_their_cached_value() {
+        let subscribers = l1_subscribers();
+        publish_l1(&subscribers, l1(100), Utc::now());
+        publish_status(
+            &subscribers,
+            FeedStatus::new("btcusdt", FeedStream::L1, FeedState::Reconnecting),
+        );
+
+        let (subscriber, _receiver) = subscriber();
+        assert!(!subscribers.publish_cached(
+            "btcusdt",
+            FeedStream::L1,
+            &subscriber,
+            "algo",
+            FeedUpdate::L1Update
+        ));
+    }
+
+    fn feed_actor(environment: Environment, feed_config: FeedConfig) -> FeedActor {
+        let (_sender, receiver) = mpsc::channel(1);
+        let simulator_config = SimulatorConfig {
+            reference_prices: HashMap::from([("btcusdt".to_owned(), Decimal::from(100))]),
+            ..SimulatorConfig::default()
+        };
+        FeedActor::new(receiver, environment, simulator_config, feed_config)
+    }
+
+    async fn get_ticker(actor: &mut FeedActor, base: &str) -> Result<Ticker, String> {
+        let (responder, receiver) = tokio::sync::oneshot::channel();
+        actor
+            .handle_message(FeedMessages::GetTicker {
+                base: base.into(),
+                quote: "usdt".into(),
+                responder,
+            })
+            .await;
+        receiver.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn the_simulator_answers_tickers_with_its_reference_prices() {
+        let mut actor = feed_actor(Environment::Simulator, FeedConfig::default());
+
+        let ticker = get_ticker(&mut actor, "btc").await.unwrap();
+        assert_eq!(ticker.last_price, Decimal::from(100));
+        assert!(get_ticker(&mut actor, "eth").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn the_mock_exchange_answers_tickers_once_they_are_built_from_trades() {
+        let feed_config = FeedConfig {
+            endpoints: Some(crate::config::EndpointsConfig {
+                rest_api: "http://127.0.0.1:1".into(),
+                websocket: "ws://127.0.0.1:1/ws".into(),
+            }),
+            ..FeedConfig::default()
+        };
+        let mut actor = feed_actor(Environment::Testnet, feed_config);
+        assert!(get_ticker(&mut actor, "btc").await.is_err());
+
+        let instrument = instrument_id::intern("btcusdt");
+        actor.subscribers.tickers.set_last_value(
+            &instrument,
+            Arc::new(Ticker {
+                symbol: instrument.clone(),
+                last_price: Decimal::from(105),
+                open_price: Decimal::from(100),
+                high_price: Decimal::from(110),
+                low_price: Decimal::from(95),
+                volume: Decimal::ONE,
+                price_change_pct: Decimal::from(5),
+                close_time: Utc::now(),
+            }),
+        );
+        let ticker = get_ticker(&mut actor, "btc").await.unwrap();
+        assert_eq!(ticker.last_price, Decimal::from(105));
+    }
+
+    #[tokio::test]
+    async fn exchange_ticker_failures_reach_the_requester() {
+        let mut actor = feed_actor(Environment::Testnet, FeedConfig::default());
+        actor.binance_config = actor
+            .binance_config
+            .clone()
+            .set_rest_api_endpoint("http://127.0.0.1:1");
+
+        let error = get_ticker(&mut actor, "btc").await.unwrap_err();
+        assert!(error.starts_with("failed to fetch the ticker for btcusdt"));
+    }
+}
@@ -0,0 +1,263 @@
+use super::registry::{
+    AnalyticsSubscribers, L1Subscribers, L2Subscribers, Subscribers, TickerSubscribers,
+};
+use super::{AlgoId, CACHED_L2_DEPTH};
+use crate::common_types::instrument_id::InstrumentId;
+use crate::feed::messages::analytics::Analytics;
+use crate::feed::messages::candle::Candle;
+use crate::feed::messages::consolidated_l1::ConsolidatedL1;
+use crate::feed::messages::feed_status::FeedStatus;
+use crate::feed::messages::l1_data::L1Data;
+use crate::feed::messages::l2_data::L2Data;
+use crate::feed::messages::l2_delta::L2Delta;
+use crate::feed::messages::messages::{FeedUpdate, L2UpdateMode};
+use crate::feed::messages::ticker::Ticker;
+use crate::feed::messages::trade_data::TradeData;
+use crate::feed::order_book::{self, OrderBook};
+use crate::feed::recorder::RecordedUpdate;
+use chrono::{DateTime, Utc};
+use probe::probe_lazy;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+macro_rules! probe {
+    ($name:ident) => {
+        probe_lazy!(l1_updates, $name, { std::ptr::null::<()>() })
+    };
+}
+
+pub(super) fn publish_l1(
+    subscribers: &L1Subscribers,
+    mut l1_data: L1Data,
+    exchange_time: DateTime<Utc>,
+) {
+    subscribers
+        .stats
+        .record("L1", &l1_data.symbol, exchange_time);
+
+    if let Some(violation) = subscribers.sanity.check_l1(&l1_data) {
+        if subscribers.reject("L1", &l1_data.symbol, &violation) {
+            return;
+        }
+        l1_data.violation = Some(violation);
+    }
+
+    subscribers.record(exchange_time, || RecordedUpdate::L1(l1_data.clone()));
+
+    let l1_data = Arc::new(l1_data);
+    subscribers.set_last_value(&l1_data.symbol, l1_data.clone());
+
+    probe!(feed_update_received);
+    subscribers.fan_out(&l1_data.symbol, |algo_ids| {
+        FeedUpdate::L1Update(algo_ids.clone(), l1_data.clone())
+    });
+}
+
+pub(super) fn publish_consolidated_l1(
+    subscribers: &Subscribers,
+    consolidated_l1: ConsolidatedL1,
+    exchange_time: DateTime<Utc>,
+) {
+    subscribers
+        .stats
+        .record("ConsolidatedL1", &consolidated_l1.symbol, exchange_time);
+
+    subscribers.record(exchange_time, || {
+        RecordedUpdate::ConsolidatedL1(consolidated_l1.clone())
+    });
+
+    let consolidated_l1 = Arc::new(consolidated_l1);
+    subscribers.fan_out(&consolidated_l1.symbol, |algo_ids| {
+        FeedUpdate::ConsolidatedL1(algo_ids.clone(), consolidated_l1.clone())
+    });
+}
+
+/// Publishes the local book to every L2 subscriber, truncated to the subscriber's depth.
+/// Delta subscribers get a snapshot when they join or the exchange resyncs the book.
+pub(super) fn publish_l2(
+    subscribers: &L2Subscribers,
+    instrument: &InstrumentId,
+    order_book: &OrderBook,
+    published_books: &mut HashMap<usize, Arc<L2Data>>,
+    resync: bool,
+    exchange_time: DateTime<Utc>,
+) {
+    subscribers.stats.record("L2", instrument, exchange_time);
+
+    let violation = subscribers.sanity.check_l2(instrument, order_book);
+    if let Some(violation) = &violation {
+        if subscribers.reject("L2", instrument, violation) {
+            return;
+        }
+    }
+    let snapshot = |depth| L2Data {
+        violation: violation.clone(),
+        ..order_book.snapshot(depth)
+    };
+
+    let cached = Arc::new(snapshot(CACHED_L2_DEPTH));
+    subscribers.record(exchange_time, || RecordedUpdate::L2(cached.clone()));
+    subscribers.set_last_value(instrument, cached);
+
+    let conflator = &subscribers.conflator;
+    let Some(senders_map) = subscribers.senders(instrument) else {
+        return;
+    };
+
+    let mut snapshots: HashMap<usize, Arc<L2Data>> = HashMap::new();
+    let mut deltas: HashMap<usize, Arc<L2Delta>> = HashMap::new();
+    for subscription in senders_map
+        .values()
+        .flat_map(|(_, subscriptions)| subscriptions)
+    {
+        if snapshots.contains_key(&subscription.depth) {
+            continue;
+        }
+
+        let snapshot = snapshot(subscription.depth);
+        if let Some(previous) = published_books.get(&subscription.depth) {
+            deltas.insert(
+                subscription.depth,
+                Arc::new(order_book::diff(previous, &snapshot)),
+            );
+        }
+        snapshots.insert(subscription.depth, Arc::new(snapshot));
+    }
+
+    for (tracked_sender, subscriptions) in senders_map.values() {
+        let mut snapshot_algo_ids: HashMap<usize, Vec<AlgoId>> = HashMap::new();
+        let mut delta_algo_ids: HashMap<usize, Vec<AlgoId>> = HashMap::new();
+
+        for subscription in subscriptions {
+            let needs_snapshot = subscription.needs_snapshot.swap(false, Ordering::Relaxed);
+            let algo_ids = if subscription.mode == L2UpdateMode::Snapshot
+                || needs_snapshot
+                || resync
+                || !deltas.contains_key(&subscription.depth)
+            {
+                &mut snapshot_algo_ids
+            } else {
+                &mut delta_algo_ids
+            };
+
+            algo_ids
+                .entry(subscription.depth)
+                .or_default()
+                .push(subscription.algo_id.clone());
+        }
+
+        // A subscriber that fell behind missed part of the book and starts over from the
+        // next snapshot.
+        let resync_after_drop = |depth| {
+            for subscription in subscriptions {
+                if subscription.depth == depth {
+                    subscription.needs_snapshot.store(true, Ordering::Relaxed);
+                }
+            }
+        };
+
+        for (depth, algo_ids) in snapshot_algo_ids {
+            if !conflator.publish(
+                tracked_sender,
+                FeedUpdate::L2Update(algo_ids.into(), snapshots[&depth].clone()),
+            ) {
+                resync_after_drop(depth);
+            }
+        }
+
+        for (depth, algo_ids) in delta_algo_ids {
+            let delta = &deltas[&depth];
+            if !delta.is_empty()
+                && !conflator.publish(
+                    tracked_sender,
+                    FeedUpdate::L2Delta(algo_ids.into(), delta.clone()),
+                )
+            {
+                resync_after_drop(depth);
+            }
+        }
+    }
+    drop(senders_map);
+
+    *published_books = snapshots;
+}
+
+pub(super) fn publish_trade(subscribers: &Subscribers, mut trade_data: TradeData) {
+    subscribers
+        .stats
+        .record("Trades", &trade_data.symbol, trade_data.trade_time);
+
+    if let Some(violation) = subscribers
+        .sanity
+        .check_trade(&trade_data.symbol, trade_data.price)
+    {
+        if subscribers.reject("Trades", &trade_data.symbol, &violation) {
+            return;
+        }
+        trade_data.violation = Some(violation);
+    }
+
+    subscribers.record(trade_data.trade_time, || {
+        RecordedUpdate::Trade(trade_data.clone())
+    });
+
+    let trade_data = Arc::new(trade_data);
+    subscribers.fan_out(&trade_data.symbol, |algo_ids| {
+        FeedUpdate::Trade(algo_ids.clone(), trade_data.clone())
+    });
+}
+
+pub(super) fn publish_candle(subscribers: &Subscribers, feed_id: &str, candle: Candle) {
+    let candle = Arc::new(candle);
+    subscribers.fan_out(feed_id, |algo_ids| {
+        FeedUpdate::Candle(algo_ids.clone(), candle.clone())
+    });
+}
+
+pub(super) fn publish_ticker(
+    subscribers: &TickerSubscribers,
+    ticker: Ticker,
+    exchange_time: DateTime<Utc>,
+) {
+    subscribers
+        .stats
+        .record("Ticker", &ticker.symbol, exchange_time);
+
+    let ticker = Arc::new(ticker);
+    subscribers.set_last_value(&ticker.symbol, ticker.clone());
+    subscribers.fan_out(&ticker.symbol, |algo_ids| {
+        FeedUpdate::Ticker(algo_ids.clone(), ticker.clone())
+    });
+}
+
+pub(super) fn publish_analytics(subscribers: &AnalyticsSubscribers, analytics: Analytics) {
+    let analytics = Arc::new(analytics);
+    subscribers.set_last_value(&analytics.symbol, analytics.clone());
+    subscribers.fan_out(&analytics.symbol, |algo_ids| {
+        FeedUpdate::Analytics(algo_ids.clone(), analytics.clone())
+    });
+}
+
+pub(super) fn publish_status(subscribers: &L1Subscribers, feed_status: FeedStatus) {
+    subscribers.on_status(&feed_status);
+
+    let feed_status = Arc::new(feed_status);
+    subscribers.fan_out(&feed_status.symbol, |algo_ids| {
+        FeedUpdate::Status(algo_ids.clone(), feed_status.clone())
+    });
+}
+
+pub(super) fn publish_l2_status(subscribers: &L2Subscribers, feed_status: FeedStatus) {
+    subscribers.on_status(&feed_status);
+
+    let feed_status = Arc::new(feed_status);
+    subscribers.fan_out(&feed_status.symbol, |subscriptions| {
+        let algo_ids = subscriptions
+            .iter()
+            .map(|subscription| subscription.algo_id.clone())
+            .collect();
+
+        FeedUpdate::Status(algo_ids, feed_status.clone())
+    });
+}
@@ -0,0 +1,325 @@
+use super::AlgoId;
+use crate::common_types::instrument_id::{self, InstrumentId};
+use crate::common_types::tracked_sender::TrackedSender;
+use crate::feed::conflation::Conflator;
+use crate::feed::feed_stats::FeedStats;
+use crate::feed::messages::analytics::Analytics;
+use crate::feed::messages::feed_status::{FeedState, FeedStatus, FeedStream};
+use crate::feed::messages::l1_data::L1Data;
+use crate::feed::messages::l2_data::L2Data;
+use crate::feed::messages::messages::{AlgoIds, FeedUpdate, L2UpdateMode};
+use crate::feed::messages::ticker::Ticker;
+use crate::feed::messages::violation::Violation;
+use crate::feed::recorder::{RecordedUpdate, Recorder};
+use crate::feed::sanity::SanityFilter;
+use crate::feed::shared_map::SharedMap;
+use arc_swap::{ArcSwap, ArcSwapOption};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+
+pub(super) type Subscribers = Registry<AlgoIds>;
+pub(super) type L1Subscribers = Registry<AlgoIds, L1Data>;
+pub(super) type L2Subscribers = Registry<Vec<L2Subscription>, L2Data>;
+pub(super) type AnalyticsSubscribers = Registry<AlgoIds, Analytics>;
+pub(super) type TickerSubscribers = Registry<AlgoIds, Ticker>;
+type LastValues<Value> = SharedMap<InstrumentId, ArcSwapOption<Value>>;
+/// The subscribers of one instrument with their subscriptions, keyed by receiver id.
+type Senders<Subscriptions> = HashMap<String, (TrackedSender<FeedUpdate>, Subscriptions)>;
+
+/// Subscriptions per instrument. Updates are handed to the shared conflator, so publishing
+/// never waits on a subscriber's channel. The last published value per instrument is kept
+/// for new subscribers and snapshot requests.
+///
+/// Each instrument's subscribers are copy-on-write: publishers read them without locking,
+/// while subscribe and unsubscribe edit a copy of that one instrument's subscribers and
+/// swap it in once they are done.
+pub(super) struct Registry<Subscriptions, LastValue = ()> {
+    pub(super) entries: Arc<SharedMap<InstrumentId, ArcSwap<Senders<Subscriptions>>>>,
+    pub(super) writer: Arc<Mutex<()>>,
+    pub(super) last_values: Arc<LastValues<LastValue>>,
+    /// Instruments whose feed went stale while their last value stayed cached.
+    pub(super) stale: Arc<SharedMap<InstrumentId, AtomicBool>>,
+    pub(super) conflator: Conflator,
+    pub(super) stats: FeedStats,
+    pub(super) sanity: SanityFilter,
+    /// Records what passed the sanity checks, as it is distributed.
+    pub(super) recorder: Option<Recorder>,
+}
+
+impl<Subscriptions, LastValue> Clone for Registry<Subscriptions, LastValue> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            writer: self.writer.clone(),
+            last_values: self.last_values.clone(),
+            stale: self.stale.clone(),
+            conflator: self.conflator.clone(),
+            stats: self.stats.clone(),
+            sanity: self.sanity.clone(),
+            recorder: self.recorder.clone(),
+        }
+    }
+}
+
+impl<Subscriptions: Clone, LastValue> Registry<Subscriptions, LastValue> {
+    pub(super) fn new(conflator: &Conflator, stats: &FeedStats, sanity: &SanityFilter) -> Self {
+        Self {
+            entries: Arc::default(),
+            writer: Arc::new(Mutex::new(())),
+            last_values: Arc::default(),
+            stale: Arc::default(),
+            conflator: conflator.clone(),
+            stats: stats.clone(),
+            sanity: sanity.clone(),
+            recorder: None,
+        }
+    }
+
+    pub(super) fn recording(self, recorder: &Option<Recorder>) -> Self {
+        Self {
+            recorder: recorder.clone(),
+            ..self
+        }
+    }
+
+    pub(super) fn record(
+        &self,
+        exchange_time: DateTime<Utc>,
+        update: impl FnOnce() -> RecordedUpdate,
+    ) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(exchange_time, update());
+        }
+    }
+
+    /// Current subscribers of the instrument, for the publishing path.
+    pub(super) fn senders(&self, instrument: &str) -> Option<Arc<Senders<Subscriptions>>> {
+        self.entries
+            .get(instrument)
+            .map(|senders| senders.load_full())
+    }
+
+    /// Hands each current subscriber of the instrument the update built from its
+    /// subscriptions.
+    pub(super) fn fan_out(&self, instrument: &str, update: impl Fn(&Subscriptions) -> FeedUpdate) {
+        if let Some(senders_map) = self.senders(instrument) {
+            for (tracked_sender, subscriptions) in senders_map.values() {
+                self.conflator
+                    .publish(tracked_sender, update(subscriptions));
+            }
+        }
+    }
+
+    /// Waits for other writers and hands out a copy of the instrument's subscribers,
+    /// published again when the writer is dropped.
+    pub(super) async fn write(&self, instrument: &str) -> RegistryWriter<'_, Subscriptions> {
+        let guard = self.writer.lock().await;
+
+        RegistryWriter {
+            entries: &self.entries,
+            instrument: instrument_id::intern(instrument),
+            senders: self
+                .senders(instrument)
+                .map(|senders| senders.as_ref().clone())
+                .unwrap_or_default(),
+            _guard: guard,
+        }
+    }
+
+    pub(super) fn last_value(&self, instrument: &str) -> Option<Arc<LastValue>> {
+        self.last_values
+            .get(instrument)
+            .and_then(|last_value| last_value.load_full())
+    }
+
+    pub(super) fn set_last_value(&self, instrument: &InstrumentId, value: Arc<LastValue>) {
+        self.last_values
+            .get_or_insert_with(instrument, ArcSwapOption::empty)
+            .store(Some(value));
+    }
+
+    /// Reports an update that failed the sanity checks and tells whether to drop it.
+    pub(super) fn reject(&self, stream: &'static str, symbol: &str, violation: &Violation) -> bool {
+        self.stats.record_rejection(stream, symbol);
+
+        let dropped = self.sanity.drops();
+        eprintln!(
+            "Suspicious {} update for {} ({}), {}.",
+            stream,
+            symbol,
+            violation,
+            if dropped {
+                "dropping it"
+            } else {
+                "publishing it anyway"
+            }
+        );
+        dropped
+    }
+
+    pub(super) fn clear_last_value(&self, instrument: &str) {
+        if let Some(last_value) = self.last_values.get(instrument) {
+            last_value.store(None);
+        }
+    }
+
+    /// A stale feed keeps its cached value, as quiet instruments go stale as a matter of
+    /// course. Feeds that are reconnecting or resyncing drop it: it may no longer be right.
+    pub(super) fn on_status(&self, feed_status: &FeedStatus) {
+        let stale = match feed_status.state {
+            FeedState::Stale => true,
+            FeedState::Reconnecting | FeedState::Invalid => {
+                self.clear_last_value(&feed_status.symbol);
+                false
+            }
+            FeedState::Recovered => false,
+            FeedState::Gap => return,
+        };
+
+        self.stale
+            .get_or_insert_with(&feed_status.symbol, AtomicBool::default)
+            .store(stale, Ordering::Release);
+    }
+
+    pub(super) fn is_stale(&self, instrument: &str) -> bool {
+        self.stale
+            .get(instrument)
+            .is_some_and(|stale| stale.load(Ordering::Acquire))
+    }
+
+    /// Sends a new subscriber the cached value. When the feed stopped updating, a `Stale`
+    /// status goes first, so the subscriber never acts on the old value as if it were live.
+    /// False without a cached value.
+    pub(super) fn publish_cached(
+        &self,
+        instrument: &str,
+        stream: FeedStream,
+        subscriber: &TrackedSender<FeedUpdate>,
+        algo_id: &str,
+        update: impl FnOnce(AlgoIds, Arc<LastValue>) -> FeedUpdate,
+    ) -> bool {
+        let Some(last_value) = self.last_value(instrument) else {
+            return false;
+        };
+
+        let algo_ids: AlgoIds = Arc::from(vec![algo_id.to_owned()]);
+        if self.is_stale(instrument) {
+            let feed_status =
+                FeedStatus::new(instrument_id::intern(instrument), stream, FeedState::Stale);
+            self.conflator.publish(
+                subscriber,
+                FeedUpdate::Status(algo_ids.clone(), Arc::new(feed_status)),
+            );
+        }
+
+        self.conflator
+            .publish(subscriber, update(algo_ids, last_value));
+        true
+    }
+
+    pub(super) fn has_subscriber(&self, subscriber: &TrackedSender<FeedUpdate>) -> bool {
+        self.entries
+            .snapshot()
+            .values()
+            .any(|senders| senders.load().contains_key(&subscriber.receiver_id))
+    }
+}
+
+pub(super) struct RegistryWriter<'a, Subscriptions> {
+    entries: &'a SharedMap<InstrumentId, ArcSwap<Senders<Subscriptions>>>,
+    instrument: InstrumentId,
+    senders: Senders<Subscriptions>,
+    _guard: MutexGuard<'a, ()>,
+}
+
+impl<Subscriptions> Deref for RegistryWriter<'_, Subscriptions> {
+    type Target = Senders<Subscriptions>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.senders
+    }
+}
+
+impl<Subscriptions> DerefMut for RegistryWriter<'_, Subscriptions> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.senders
+    }
+}
+
+impl<Subscriptions> Drop for RegistryWriter<'_, Subscriptions> {
+    fn drop(&mut self) {
+        let senders = std::mem::take(&mut self.senders);
+
+        if !senders.is_empty() {
+            self.entries
+                .get_or_insert_with(&self.instrument, ArcSwap::default)
+                .store(Arc::new(senders));
+        } else if self.entries.get(&self.instrument).is_some() {
+            self.entries.remove(&self.instrument);
+        }
+    }
+}
+
+impl<LastValue> Registry<AlgoIds, LastValue> {
+    pub(super) async fn subscribe(
+        &self,
+        instrument: &str,
+        subscriber: &TrackedSender<FeedUpdate>,
+        algo_id: &str,
+    ) {
+        let mut senders = self.write(instrument).await;
+
+        let (_, algo_ids) = senders
+            .entry(subscriber.receiver_id.clone())
+            .or_insert_with(|| (subscriber.clone(), Arc::from(Vec::new())));
+        *algo_ids = with_algo(algo_ids, algo_id.to_owned());
+    }
+
+    /// Removes the algo's subscription and tells whether that left the instrument without
+    /// subscribers.
+    pub(super) async fn unsubscribe(
+        &self,
+        instrument: &str,
+        subscriber: &TrackedSender<FeedUpdate>,
+        algo_id: &str,
+    ) -> bool {
+        let mut senders = self.write(instrument).await;
+
+        let Some((_, algo_ids)) = senders.get_mut(&subscriber.receiver_id) else {
+            return false;
+        };
+
+        *algo_ids = without_algo(algo_ids, algo_id);
+        if algo_ids.is_empty() {
+            senders.remove(&subscriber.receiver_id);
+        }
+        senders.is_empty()
+    }
+}
+
+/// Subscription lists are rebuilt when they change so every update can share them.
+fn with_algo(algo_ids: &AlgoIds, algo_id: AlgoId) -> AlgoIds {
+    algo_ids.iter().cloned().chain([algo_id]).collect()
+}
+
+fn without_algo(algo_ids: &AlgoIds, algo_id: &str) -> AlgoIds {
+    algo_ids
+        .iter()
+        .filter(|id| *id != algo_id)
+        .cloned()
+        .collect()
+}
+
+/// Copies of the registry share the snapshot flag, so the publisher can clear it without
+/// writing the registry.
+#[derive(Clone)]
+pub(super) struct L2Subscription {
+    pub(super) algo_id: AlgoId,
+    pub(super) depth: usize,
+    pub(super) mode: L2UpdateMode,
+    pub(super) needs_snapshot: Arc<AtomicBool>,
+}
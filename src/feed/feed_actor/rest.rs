@@ -0,0 +1,112 @@
+use crate::common_types::instrument_id::InstrumentId;
+use crate::feed::messages::ticker::Ticker;
+use binance::api::Binance;
+use binance::config::Config as BinanceConfig;
+use binance::market::Market as BinanceMarket;
+use binance::model::{OrderBook as BinanceOrderBook, PriceStats};
+use chrono::{TimeZone, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::task::{self};
+
+const DEPTH_SNAPSHOT_LIMIT: u64 = 1000;
+/// Bounds on the wait between depth snapshots while the book keeps falling out of sequence,
+/// so a flapping stream cannot spend the REST request weight.
+pub(super) const DEPTH_SNAPSHOT_MIN_INTERVAL: Duration = Duration::from_secs(1);
+pub(super) const DEPTH_SNAPSHOT_MAX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Binance sends bid and ask levels as different types with the same fields. A level whose
+/// price or quantity has no decimal representation is logged and skipped: reading it as zero
+/// would delete the real level from the book.
+pub(super) fn to_levels<Level>(
+    instrument: &str,
+    levels: &[Level],
+    level: impl Fn(&Level) -> (f64, f64),
+) -> Vec<(Decimal, Decimal)> {
+    levels
+        .iter()
+        .filter_map(|binance_level| {
+            let (price, quantity) = level(binance_level);
+            match (Decimal::from_f64(price), Decimal::from_f64(quantity)) {
+                (Some(price), Some(quantity)) => Some((price, quantity)),
+                _ => {
+                    eprintln!(
+                        "Skipping L2 level for {} with price {} and quantity {}.",
+                        instrument, price, quantity
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+pub(super) async fn fetch_depth_snapshot(
+    config: &BinanceConfig,
+    symbol: &str,
+) -> Result<BinanceOrderBook, String> {
+    let config = config.clone();
+    let symbol = symbol.to_uppercase();
+
+    task::spawn_blocking(move || {
+        let market: BinanceMarket = Binance::new_with_config(None, None, &config);
+        market
+            .get_custom_depth(symbol, DEPTH_SNAPSHOT_LIMIT)
+            .map_err(|error| error.to_string())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+fn price_stats_to_ticker(instrument: &InstrumentId, price_stats: &PriceStats) -> Option<Ticker> {
+    let decimal = |value: f64| Decimal::from_f64(value).unwrap_or_default();
+
+    Some(Ticker {
+        symbol: instrument.clone(),
+        last_price: decimal(price_stats.last_price),
+        open_price: decimal(price_stats.open_price),
+        high_price: decimal(price_stats.high_price),
+        low_price: decimal(price_stats.low_price),
+        volume: decimal(price_stats.volume),
+        price_change_pct: Decimal::from_str(&price_stats.price_change_percent).unwrap_or_default(),
+        close_time: Utc
+            .timestamp_millis_opt(price_stats.close_time as i64)
+            .single()?,
+    })
+}
+
+pub(super) async fn fetch_ticker(
+    config: BinanceConfig,
+    instrument: &str,
+) -> Result<Ticker, String> {
+    let symbol = instrument.to_uppercase();
+
+    let price_stats = task::spawn_blocking(move || {
+        let market: BinanceMarket = Binance::new_with_config(None, None, &config);
+        market
+            .get_24h_price_stats(symbol)
+            .map_err(|error| error.to_string())
+    })
+    .await
+    .map_err(|error| error.to_string())??;
+
+    price_stats_to_ticker(&instrument.into(), &price_stats)
+        .ok_or_else(|| format!("invalid close time {}", price_stats.close_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_without_a_decimal_value_are_skipped() {
+        let levels = [(100.5, 2.0), (f64::NAN, 1.0), (101.0, f64::INFINITY)];
+
+        assert_eq!(
+            to_levels("btcusdt", &levels, |&level| level),
+            vec![(Decimal::new(1005, 1), Decimal::new(2, 0))]
+        );
+    }
+}
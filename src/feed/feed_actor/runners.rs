@@ -0,0 +1,724 @@
+use super::publish::{
+    publish_analytics, publish_candle, publish_consolidated_l1, publish_l1, publish_l2,
+    publish_l2_status, publish_status, publish_ticker, publish_trade,
+};
+use super::registry::{
+    AnalyticsSubscribers, L1Subscribers, L2Subscribers, Subscribers, TickerSubscribers,
+};
+use super::rest::{
+    fetch_depth_snapshot, to_levels, DEPTH_SNAPSHOT_MAX_INTERVAL, DEPTH_SNAPSHOT_MIN_INTERVAL,
+};
+use super::streams::{
+    binance_trades, init_binance_streams, init_consolidated_l1_streams, init_streams,
+    kline_to_candle, spawn_websocket_stream, to_ticker, to_venue, BinanceServer, StopOnDrop,
+    StreamEvent, STREAM_CHANNEL_SIZE,
+};
+use super::{candle_feed_id, FeedKind, FeedSubscribers, ReplayKinds};
+use crate::common_types::instrument_id::{self, InstrumentId};
+use crate::common_types::venue::Venue;
+use crate::config::{AnalyticsConfig, Environment, ReplayConfig, SyntheticConfig};
+use crate::feed::analytics_engine::AnalyticsEngine;
+use crate::feed::candle_builder::CandleBuilder;
+use crate::feed::consolidated_book::ConsolidatedBook;
+use crate::feed::messages::candle::{CandleInterval, CandleSource};
+use crate::feed::messages::feed_status::{FeedState, FeedStatus, FeedStream};
+use crate::feed::messages::l1_data::L1Data;
+use crate::feed::messages::messages::FeedUpdate;
+use crate::feed::order_book::OrderBook;
+use crate::feed::recorder::RecordedUpdate;
+use crate::feed::replay::ReplayReader;
+use crate::feed::sequence::{SequenceCheck, SequenceTracker, SnapshotBackoff};
+use crate::feed::simulated_feed::SimulatedBook;
+use crate::feed::staleness::FeedHealth;
+use crate::feed::synthetic_book::SyntheticBook;
+use crate::feed::ticker_builder::TickerBuilder;
+use barter_data_sniper::streams::reconnect::stream::ReconnectingStream;
+use barter_data_sniper::streams::reconnect::Event;
+use barter_data_sniper::subscription::book::OrderBooksL1;
+use barter_instrument_copy::exchange::ExchangeId;
+use binance::config::Config as BinanceConfig;
+use binance::websockets::WebsocketEvent;
+use chrono::{TimeZone, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self};
+use tokio_stream::StreamExt;
+
+pub(super) async fn run_l1_feed(
+    server: BinanceServer,
+    base: String,
+    quote: String,
+    subscribers: L1Subscribers,
+    staleness_threshold: Duration,
+) {
+    let instrument = instrument_id::intern(&(base.clone() + quote.as_str()));
+    let mut health = FeedHealth::new(staleness_threshold);
+
+    let mut l1_stream =
+        match init_streams(move || init_binance_streams(server, base, quote, OrderBooksL1)).await {
+            Ok(l1_stream) => l1_stream,
+            Err(error) => {
+                eprintln!(
+                    "Failed to subscribe to L1 updates for {}: {}",
+                    instrument, error
+                );
+                return;
+            }
+        };
+
+    let mut binance_l1_stream = l1_stream
+        .select(ExchangeId::BinanceSpot)
+        .unwrap()
+        .with_error_handler(|error| eprintln!("MarketStream generated error {}", error));
+
+    loop {
+        let msg = match tokio::time::timeout(health.threshold(), binance_l1_stream.next()).await {
+            Ok(Some(msg)) => msg,
+            Ok(None) => break,
+            Err(_) => {
+                if let Some(state) = health.on_timeout() {
+                    eprintln!(
+                        "No L1 updates for {} in {:?}.",
+                        instrument,
+                        health.threshold()
+                    );
+                    publish_status(
+                        &subscribers,
+                        FeedStatus::new(instrument.clone(), FeedStream::L1, state),
+                    );
+                }
+                continue;
+            }
+        };
+
+        match msg {
+            barter_data_sniper::streams::reconnect::Event::Item(l1_update) => {
+                if let Some(state) = health.on_update() {
+                    publish_status(
+                        &subscribers,
+                        FeedStatus::new(instrument.clone(), FeedStream::L1, state),
+                    );
+                }
+
+                let l1_data = L1Data::new(
+                    instrument.clone(),
+                    l1_update.kind.best_bid.amount,
+                    l1_update.kind.best_bid.price,
+                    l1_update.kind.best_ask.amount,
+                    l1_update.kind.best_ask.price,
+                );
+
+                publish_l1(&subscribers, l1_data, l1_update.time_exchange);
+            }
+            barter_data_sniper::streams::reconnect::Event::Reconnecting(origin) => {
+                eprintln!("Reconnecting to L1 updates{}.", origin);
+
+                if let Some(state) = health.on_reconnecting() {
+                    publish_status(
+                        &subscribers,
+                        FeedStatus::new(instrument.clone(), FeedStream::L1, state),
+                    );
+                }
+            }
+        }
+    }
+}
+
+pub(super) async fn run_consolidated_l1_feed(
+    environment: Environment,
+    base: String,
+    quote: String,
+    venues: Vec<Venue>,
+    subscribers: Subscribers,
+) {
+    let instrument = instrument_id::intern(&(base.clone() + quote.as_str()));
+    let mut consolidated_book = ConsolidatedBook::new(instrument.clone());
+
+    let l1_streams =
+        match init_streams(move || init_consolidated_l1_streams(environment, base, quote, venues))
+            .await
+        {
+            Ok(l1_streams) => l1_streams,
+            Err(error) => {
+                eprintln!(
+                    "Failed to subscribe to consolidated L1 updates for {}: {}",
+                    instrument, error
+                );
+                return;
+            }
+        };
+
+    let mut l1_stream = l1_streams
+        .select_all()
+        .with_error_handler(|error| eprintln!("MarketStream generated error {}", error));
+
+    while let Some(msg) = l1_stream.next().await {
+        match msg {
+            Event::Item(l1_update) => {
+                let Some(venue) = to_venue(l1_update.exchange) else {
+                    continue;
+                };
+
+                let l1_data = L1Data::new(
+                    instrument.clone(),
+                    l1_update.kind.best_bid.amount,
+                    l1_update.kind.best_bid.price,
+                    l1_update.kind.best_ask.amount,
+                    l1_update.kind.best_ask.price,
+                );
+
+                if let Some(consolidated_l1) = consolidated_book.update(venue, l1_data) {
+                    publish_consolidated_l1(&subscribers, consolidated_l1, l1_update.time_exchange);
+                }
+            }
+            Event::Reconnecting(origin) => {
+                eprintln!("Reconnecting to consolidated L1 updates {}.", origin);
+
+                // A disconnected venue's last quote must not keep winning the consolidated view.
+                if let Some(venue) = to_venue(origin) {
+                    consolidated_book.remove(venue);
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the local book from Binance diff depth updates, resyncing from a REST snapshot
+/// whenever an update id is missed, with backoff between snapshots. Subscribers see the
+/// instrument as INVALID, and get no book, until it is consistent again.
+pub(super) async fn run_l2_feed(
+    binance_config: BinanceConfig,
+    base: String,
+    quote: String,
+    subscribers: L2Subscribers,
+    staleness_threshold: Duration,
+) {
+    let instrument = instrument_id::intern(&(base.clone() + quote.as_str()));
+    let mut health = FeedHealth::new(staleness_threshold);
+    let mut order_book = OrderBook::new(instrument.clone());
+    let mut published_books = HashMap::new();
+    let mut sequence = SequenceTracker::default();
+    let mut snapshot_backoff =
+        SnapshotBackoff::new(DEPTH_SNAPSHOT_MIN_INTERVAL, DEPTH_SNAPSHOT_MAX_INTERVAL);
+    let mut invalid = false;
+    let mut resync = false;
+
+    let keep_running = Arc::new(AtomicBool::new(true));
+    let _stop_on_drop = StopOnDrop(keep_running.clone());
+    let (depth_sender, mut depth_receiver) = mpsc::channel(STREAM_CHANNEL_SIZE);
+
+    spawn_websocket_stream(
+        binance_config.clone(),
+        format!("{}@depth@100ms", instrument.to_lowercase()),
+        keep_running,
+        depth_sender,
+        |event| match event {
+            WebsocketEvent::DepthOrderBook(depth_event) => Some(depth_event),
+            _ => None,
+        },
+    );
+
+    loop {
+        let msg = match tokio::time::timeout(health.threshold(), depth_receiver.recv()).await {
+            Ok(Some(msg)) => msg,
+            Ok(None) => break,
+            Err(_) => {
+                if let Some(state) = health.on_timeout() {
+                    eprintln!(
+                        "No L2 updates for {} in {:?}.",
+                        instrument,
+                        health.threshold()
+                    );
+                    publish_l2_status(
+                        &subscribers,
+                        FeedStatus::new(instrument.clone(), FeedStream::L2, state),
+                    );
+                }
+                continue;
+            }
+        };
+
+        let depth_event = match msg {
+            StreamEvent::Update(depth_event) => depth_event,
+            StreamEvent::Reconnecting => {
+                eprintln!("Reconnecting to L2 updates for {}.", instrument);
+
+                if sequence.is_synced() {
+                    sequence.invalidate();
+                    invalid = true;
+                    publish_l2_status(
+                        &subscribers,
+                        FeedStatus::new(instrument.clone(), FeedStream::L2, FeedState::Invalid),
+                    );
+                }
+                continue;
+            }
+        };
+
+        if let Some(state) = health.on_update() {
+            if !invalid {
+                publish_l2_status(
+                    &subscribers,
+                    FeedStatus::new(instrument.clone(), FeedStream::L2, state),
+                );
+            }
+        }
+
+        if !sequence.is_synced() {
+            // Updates queue up behind the wait and are skipped as already in the snapshot.
+            if let Some(next_snapshot) = snapshot_backoff.next_snapshot() {
+                tokio::time::sleep_until(next_snapshot.into()).await;
+            }
+
+            snapshot_backoff.on_snapshot(Instant::now());
+            match fetch_depth_snapshot(&binance_config, &instrument).await {
+                Ok(snapshot) => {
+                    order_book.apply_snapshot(
+                        to_levels(&instrument, &snapshot.bids, |level| {
+                            (level.price, level.qty)
+                        }),
+                        to_levels(&instrument, &snapshot.asks, |level| {
+                            (level.price, level.qty)
+                        }),
+                    );
+                    sequence.reset(snapshot.last_update_id);
+                    resync = true;
+                }
+                Err(error) => {
+                    eprintln!("Failed to fetch L2 snapshot for {}: {}", instrument, error);
+                    continue;
+                }
+            }
+        }
+
+        match sequence.check(depth_event.first_update_id, depth_event.final_update_id) {
+            SequenceCheck::Outdated => continue,
+            SequenceCheck::Gap { expected, received } => {
+                eprintln!(
+                    "Gap in L2 updates for {}: expected update {}, received {}. Resyncing.",
+                    instrument, expected, received
+                );
+
+                sequence.invalidate();
+                if !invalid {
+                    invalid = true;
+                    publish_l2_status(
+                        &subscribers,
+                        FeedStatus::new(instrument.clone(), FeedStream::L2, FeedState::Invalid),
+                    );
+                }
+                continue;
+            }
+            SequenceCheck::InSequence => snapshot_backoff.on_synced(),
+        }
+
+        order_book.apply_update(
+            to_levels(&instrument, &depth_event.bids, |level| {
+                (level.price, level.qty)
+            }),
+            to_levels(&instrument, &depth_event.asks, |level| {
+                (level.price, level.qty)
+            }),
+        );
+
+        if invalid {
+            invalid = false;
+            eprintln!("L2 book for {} is consistent again.", instrument);
+            publish_l2_status(
+                &subscribers,
+                FeedStatus::new(instrument.clone(), FeedStream::L2, FeedState::Recovered),
+            );
+        }
+
+        let exchange_time = Utc
+            .timestamp_millis_opt(depth_event.event_time as i64)
+            .single()
+            .unwrap_or_else(Utc::now);
+
+        publish_l2(
+            &subscribers,
+            &instrument,
+            &order_book,
+            &mut published_books,
+            resync,
+            exchange_time,
+        );
+        resync = false;
+    }
+}
+
+pub(super) async fn run_trade_feed(
+    server: BinanceServer,
+    base: String,
+    quote: String,
+    subscribers: Subscribers,
+) {
+    let Some(mut trades) = binance_trades(server, base, quote).await else {
+        return;
+    };
+
+    while let Some(trade_data) = trades.next().await {
+        publish_trade(&subscribers, trade_data);
+    }
+}
+
+pub(super) async fn run_local_candle_feed(
+    server: BinanceServer,
+    base: String,
+    quote: String,
+    interval: CandleInterval,
+    source: CandleSource,
+    subscribers: Subscribers,
+) {
+    let instrument = instrument_id::intern(&(base.clone() + quote.as_str()));
+    let feed_id = candle_feed_id(&instrument, interval, source);
+    let mut builder = CandleBuilder::new(instrument.clone(), interval);
+
+    let Some(mut trades) = binance_trades(server, base, quote).await else {
+        return;
+    };
+
+    let mut close_timer = tokio::time::interval(Duration::from_millis(100));
+
+    loop {
+        tokio::select! {
+            trade_data = trades.next() => match trade_data {
+                Some(trade_data) => {
+                    for candle in builder.on_trade(&trade_data) {
+                        publish_candle(&subscribers, &feed_id, candle);
+                    }
+                }
+                None => break,
+            },
+            _ = close_timer.tick() => {
+                if let Some(candle) = builder.close_until(Utc::now()) {
+                    publish_candle(&subscribers, &feed_id, candle);
+                }
+            }
+        }
+    }
+}
+
+/// Builds the 24h ticker from public trades, for endpoints without a ticker stream.
+pub(super) async fn run_local_ticker_feed(
+    server: BinanceServer,
+    base: String,
+    quote: String,
+    subscribers: TickerSubscribers,
+) {
+    let instrument = instrument_id::intern(&(base.clone() + quote.as_str()));
+    let mut builder = TickerBuilder::new(instrument.clone());
+
+    let Some(mut trades) = binance_trades(server, base, quote).await else {
+        return;
+    };
+
+    while let Some(trade_data) = trades.next().await {
+        let ticker = builder.on_trade(&trade_data);
+        publish_ticker(&subscribers, ticker, trade_data.trade_time);
+    }
+}
+
+pub(super) async fn run_ticker_feed(
+    config: BinanceConfig,
+    instrument: InstrumentId,
+    subscribers: TickerSubscribers,
+) {
+    let keep_running = Arc::new(AtomicBool::new(true));
+    let _stop_on_drop = StopOnDrop(keep_running.clone());
+    let (ticker_sender, mut ticker_receiver) = mpsc::channel(STREAM_CHANNEL_SIZE);
+
+    spawn_websocket_stream(
+        config,
+        format!("{}@ticker", instrument.to_lowercase()),
+        keep_running,
+        ticker_sender,
+        |event| match event {
+            WebsocketEvent::DayTicker(ticker_event) => Some(ticker_event),
+            _ => None,
+        },
+    );
+
+    while let Some(msg) = ticker_receiver.recv().await {
+        match msg {
+            StreamEvent::Update(ticker_event) => {
+                let Some(ticker) = to_ticker(&instrument, &ticker_event) else {
+                    eprintln!(
+                        "Dropping {} ticker with invalid close time {}.",
+                        instrument, ticker_event.close_time
+                    );
+                    continue;
+                };
+                let exchange_time = Utc
+                    .timestamp_millis_opt(ticker_event.event_time as i64)
+                    .single()
+                    .unwrap_or_else(Utc::now);
+
+                publish_ticker(&subscribers, ticker, exchange_time);
+            }
+            StreamEvent::Reconnecting => {
+                eprintln!("Reconnecting to ticker updates for {}.", instrument);
+            }
+        }
+    }
+}
+
+pub(super) async fn run_exchange_candle_feed(
+    config: BinanceConfig,
+    instrument: InstrumentId,
+    interval: CandleInterval,
+    subscribers: Subscribers,
+) {
+    let feed_id = candle_feed_id(&instrument, interval, CandleSource::Exchange);
+    let keep_running = Arc::new(AtomicBool::new(true));
+    let _stop_on_drop = StopOnDrop(keep_running.clone());
+    let (kline_sender, mut kline_receiver) = mpsc::channel(STREAM_CHANNEL_SIZE);
+
+    spawn_websocket_stream(
+        config,
+        format!("{}@kline_{}", instrument.to_lowercase(), interval),
+        keep_running,
+        kline_sender,
+        |event| match event {
+            WebsocketEvent::Kline(kline_event) => Some(kline_event.kline),
+            _ => None,
+        },
+    );
+
+    while let Some(msg) = kline_receiver.recv().await {
+        match msg {
+            StreamEvent::Update(kline) => match kline_to_candle(&instrument, interval, &kline) {
+                Some(candle) => publish_candle(&subscribers, &feed_id, candle),
+                None => eprintln!(
+                    "Dropping {} kline with invalid times: open {}, close {}.",
+                    feed_id, kline.open_time, kline.close_time
+                ),
+            },
+            StreamEvent::Reconnecting => {
+                eprintln!("Reconnecting to {} candles.", feed_id);
+            }
+        }
+    }
+}
+
+/// Publishes one recording to every feed of its instrument subscribed in `kinds`, so they
+/// all follow the same clock and keep the recorded order. The book and ticker follow every
+/// event, so feeds subscribed later start from the replayed state.
+pub(super) async fn run_replay_feed(
+    instrument: InstrumentId,
+    replay_config: ReplayConfig,
+    kinds: ReplayKinds,
+    subscribers: FeedSubscribers,
+) {
+    let mut reader = ReplayReader::open(&replay_config, &instrument);
+    let mut order_book = OrderBook::new(instrument.clone());
+    let mut published_books = HashMap::new();
+    let mut candle_builders: HashMap<FeedKind, CandleBuilder> = HashMap::new();
+    let mut ticker_builder = TickerBuilder::new(instrument.clone());
+    let active = |kind| kinds.get(&kind).is_some();
+
+    while let Some(event) = reader.next().await {
+        // Keeps the recorded exchange latency rather than the age of the recording.
+        let exchange_time = Utc::now() - (event.receive_time - event.exchange_time);
+
+        match event.update {
+            RecordedUpdate::L1(l1_data) => {
+                if active(FeedKind::L1) {
+                    publish_l1(&subscribers.l1, l1_data, exchange_time);
+                }
+            }
+            RecordedUpdate::ConsolidatedL1(consolidated_l1) => {
+                if active(FeedKind::ConsolidatedL1) {
+                    publish_consolidated_l1(
+                        &subscribers.consolidated_l1,
+                        consolidated_l1,
+                        exchange_time,
+                    );
+                }
+            }
+            RecordedUpdate::L2(l2_data) => {
+                order_book.apply_l2_data(&l2_data);
+                if active(FeedKind::L2) {
+                    publish_l2(
+                        &subscribers.l2,
+                        &instrument,
+                        &order_book,
+                        &mut published_books,
+                        false,
+                        exchange_time,
+                    );
+                }
+            }
+            RecordedUpdate::L2Delta(l2_delta) => {
+                order_book.apply_update(
+                    l2_delta
+                        .bid_changes
+                        .iter()
+                        .map(|level| (level.price, level.quantity)),
+                    l2_delta
+                        .ask_changes
+                        .iter()
+                        .map(|level| (level.price, level.quantity)),
+                );
+                if active(FeedKind::L2) {
+                    publish_l2(
+                        &subscribers.l2,
+                        &instrument,
+                        &order_book,
+                        &mut published_books,
+                        false,
+                        exchange_time,
+                    );
+                }
+            }
+            RecordedUpdate::Trade(trade_data) => {
+                let ticker = ticker_builder.on_trade(&trade_data);
+                if active(FeedKind::Ticker) {
+                    publish_ticker(&subscribers.tickers, ticker, exchange_time);
+                }
+
+                // Bars start with the first trade after their feed was subscribed.
+                candle_builders.retain(|kind, _| active(*kind));
+                for kind in kinds.snapshot().keys() {
+                    let FeedKind::Candles(interval, _) = *kind else {
+                        continue;
+                    };
+                    let candle_builder = candle_builders
+                        .entry(*kind)
+                        .or_insert_with(|| CandleBuilder::new(instrument.clone(), interval));
+
+                    let feed_id = kind.feed_id(&instrument);
+                    for candle in candle_builder.on_trade(&trade_data) {
+                        publish_candle(&subscribers.candles, &feed_id, candle);
+                    }
+                }
+
+                if active(FeedKind::Trades) {
+                    publish_trade(&subscribers.trades, trade_data);
+                }
+            }
+        }
+    }
+}
+
+pub(super) async fn run_synthetic_feed(
+    mut receiver: mpsc::Receiver<FeedUpdate>,
+    synthetic: SyntheticConfig,
+    subscribers: L1Subscribers,
+) {
+    let mut book = SyntheticBook::new(&synthetic);
+
+    while let Some(update) = receiver.recv().await {
+        match update {
+            FeedUpdate::L1Update(_, l1_data) => {
+                if let Some(l1_data) = book.update(&l1_data) {
+                    publish_l1(&subscribers, l1_data, Utc::now());
+                }
+            }
+            FeedUpdate::Status(_, feed_status) => {
+                if let Some(state) = book.on_status(&feed_status.symbol, feed_status.state) {
+                    publish_status(
+                        &subscribers,
+                        FeedStatus::new(synthetic.symbol(), FeedStream::L1, state),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(super) async fn run_analytics_feed(
+    mut receiver: mpsc::Receiver<FeedUpdate>,
+    instrument: InstrumentId,
+    analytics_config: AnalyticsConfig,
+    subscribers: AnalyticsSubscribers,
+) {
+    let mut engine = AnalyticsEngine::new(instrument, &analytics_config);
+
+    while let Some(update) = receiver.recv().await {
+        let analytics = match update {
+            FeedUpdate::L1Update(_, l1_data) => engine.on_l1(&l1_data),
+            FeedUpdate::Trade(_, trade_data) => engine.on_trade(&trade_data),
+            _ => None,
+        };
+
+        if let Some(analytics) = analytics {
+            publish_analytics(&subscribers, analytics);
+        }
+    }
+}
+
+pub(super) async fn run_simulated_feed(
+    kind: FeedKind,
+    instrument: InstrumentId,
+    reference_price: Decimal,
+    tick_interval: Duration,
+    subscribers: FeedSubscribers,
+) {
+    let mut book = SimulatedBook::new(instrument.clone(), reference_price);
+    let mut order_book = OrderBook::new(instrument.clone());
+    let mut consolidated_book = ConsolidatedBook::new(instrument.clone());
+    let mut candle_builder = match kind {
+        FeedKind::Candles(interval, _) => Some(CandleBuilder::new(instrument.clone(), interval)),
+        _ => None,
+    };
+    let mut ticker_builder = TickerBuilder::new(instrument.clone());
+    let mut published_books = HashMap::new();
+    let mut interval = tokio::time::interval(tick_interval);
+
+    loop {
+        interval.tick().await;
+
+        let (l1_data, l2_data) = book.step();
+        match kind {
+            FeedKind::L1 => {
+                publish_l1(&subscribers.l1, l1_data, Utc::now());
+            }
+            FeedKind::ConsolidatedL1 => {
+                if let Some(consolidated_l1) = consolidated_book.update(Venue::BinanceSpot, l1_data)
+                {
+                    publish_consolidated_l1(
+                        &subscribers.consolidated_l1,
+                        consolidated_l1,
+                        Utc::now(),
+                    );
+                }
+            }
+            FeedKind::L2 => {
+                order_book.apply_l2_data(&l2_data);
+
+                publish_l2(
+                    &subscribers.l2,
+                    &instrument,
+                    &order_book,
+                    &mut published_books,
+                    false,
+                    Utc::now(),
+                );
+            }
+            FeedKind::Trades => {
+                let trade_data = book.trade();
+
+                publish_trade(&subscribers.trades, trade_data);
+            }
+            FeedKind::Candles(..) => {
+                let Some(candle_builder) = candle_builder.as_mut() else {
+                    continue;
+                };
+
+                let feed_id = kind.feed_id(&instrument);
+                for candle in candle_builder.on_trade(&book.trade()) {
+                    publish_candle(&subscribers.candles, &feed_id, candle);
+                }
+            }
+            FeedKind::Ticker => {
+                let ticker = ticker_builder.on_trade(&book.trade());
+                publish_ticker(&subscribers.tickers, ticker, Utc::now());
+            }
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use crate::common_types::instrument_id::{self, InstrumentId};
+use crate::common_types::side::Side as AggressorSide;
+use crate::common_types::venue::Venue;
+use crate::config::{Environment, FeedConfig};
+use crate::feed::endpoint_server::{self, EndpointBinance};
+use crate::feed::messages::candle::{Candle, CandleInterval};
+use crate::feed::messages::ticker::Ticker;
+use crate::feed::messages::trade_data::TradeData;
+use barter_data_sniper::error::DataError;
+use barter_data_sniper::event::MarketEvent;
+use barter_data_sniper::exchange::binance::channel::BinanceChannel;
+use barter_data_sniper::exchange::binance::market::BinanceMarket as BinanceMarketId;
+use barter_data_sniper::exchange::binance::spot::{BinanceSpot, BinanceSpotTestnet};
+use barter_data_sniper::exchange::kraken::Kraken;
+use barter_data_sniper::exchange::StreamSelector;
+use barter_data_sniper::streams::consumer::MarketStreamResult;
+use barter_data_sniper::streams::reconnect::stream::ReconnectingStream;
+use barter_data_sniper::streams::reconnect::Event;
+use barter_data_sniper::streams::Streams;
+use barter_data_sniper::subscription::book::{OrderBookL1, OrderBooksL1};
+use barter_data_sniper::subscription::trade::{PublicTrade, PublicTrades};
+use barter_data_sniper::subscription::{Subscription, SubscriptionKind};
+use barter_data_sniper::Identifier;
+use barter_instrument_copy::exchange::ExchangeId;
+use barter_instrument_copy::instrument::market_data::kind::MarketDataInstrumentKind;
+use barter_instrument_copy::instrument::market_data::MarketDataInstrument;
+use barter_integration_copy::Side;
+use binance::config::Config as BinanceConfig;
+use binance::model::{DayTickerEvent, Kline};
+use binance::websockets::{WebSockets, WebsocketEvent};
+use chrono::{TimeZone, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{self};
+use tokio::task::{self};
+use tokio_stream::{Stream, StreamExt};
+
+pub(super) const STREAM_CHANNEL_SIZE: usize = 1000;
+const STREAM_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+type L1Streams =
+    Streams<Event<ExchangeId, Result<MarketEvent<MarketDataInstrument, OrderBookL1>, DataError>>>;
+
+/// Stops a blocking websocket event loop when the owning feed task is dropped or aborted.
+pub(super) struct StopOnDrop(pub(super) Arc<AtomicBool>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// barter's stream builder holds futures that are not `Send`, so streams are initialised on a
+/// blocking thread and only the resulting streams are moved into the feed task.
+pub(super) async fn init_streams<Init, InitFuture, MarketStreams>(
+    init: Init,
+) -> Result<MarketStreams, String>
+where
+    Init: FnOnce() -> InitFuture + Send + 'static,
+    InitFuture: Future<Output = Result<MarketStreams, DataError>>,
+    MarketStreams: Send + 'static,
+{
+    let runtime = Handle::current();
+
+    match task::spawn_blocking(move || runtime.block_on(init())).await {
+        Ok(result) => result.map_err(|error| error.to_string()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// The Binance server barter streams L1 and trades from.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum BinanceServer {
+    Mainnet,
+    Testnet,
+    /// The `[feed.endpoints]` websocket, such as the mock exchange.
+    Endpoints,
+}
+
+impl BinanceServer {
+    pub(super) fn new(environment: &Environment, feed_config: &FeedConfig) -> Self {
+        if let Some(endpoints) = &feed_config.endpoints {
+            match endpoint_server::set_websocket_url(&endpoints.websocket) {
+                Ok(()) => return BinanceServer::Endpoints,
+                Err(error) => eprintln!(
+                    "Cannot stream L1 and trades from {}, {}.",
+                    endpoints.websocket, error
+                ),
+            }
+        }
+
+        match environment {
+            Environment::Mainnet => BinanceServer::Mainnet,
+            Environment::Testnet | Environment::Simulator => BinanceServer::Testnet,
+        }
+    }
+}
+
+/// Streams `kind` updates of `base`/`quote` from the Binance `server`.
+pub(super) async fn init_binance_streams<Kind>(
+    server: BinanceServer,
+    base: String,
+    quote: String,
+    kind: Kind,
+) -> Result<Streams<MarketStreamResult<MarketDataInstrument, Kind::Event>>, DataError>
+where
+    Kind: SubscriptionKind + Ord + Send + Sync + 'static,
+    Kind::Event: Clone + Send,
+    BinanceSpot: StreamSelector<MarketDataInstrument, Kind>,
+    BinanceSpotTestnet: StreamSelector<MarketDataInstrument, Kind>,
+    EndpointBinance: StreamSelector<MarketDataInstrument, Kind>,
+    Subscription<BinanceSpot, MarketDataInstrument, Kind>:
+        Identifier<BinanceChannel> + Identifier<BinanceMarketId>,
+    Subscription<BinanceSpotTestnet, MarketDataInstrument, Kind>:
+        Identifier<BinanceChannel> + Identifier<BinanceMarketId>,
+    Subscription<EndpointBinance, MarketDataInstrument, Kind>:
+        Identifier<BinanceChannel> + Identifier<BinanceMarketId>,
+{
+    let (base, quote) = (base.as_str(), quote.as_str());
+    let builder = Streams::<Kind>::builder();
+    let instrument_kind = MarketDataInstrumentKind::Spot;
+
+    let builder = match server {
+        BinanceServer::Mainnet => {
+            builder.subscribe([(BinanceSpot::default(), base, quote, instrument_kind, kind)])
+        }
+        BinanceServer::Testnet => builder.subscribe([(
+            BinanceSpotTestnet::default(),
+            base,
+            quote,
+            instrument_kind,
+            kind,
+        )]),
+        BinanceServer::Endpoints => builder.subscribe([(
+            EndpointBinance::default(),
+            base,
+            quote,
+            instrument_kind,
+            kind,
+        )]),
+    };
+
+    builder.init().await
+}
+
+/// Binance follows the environment; the other venues have no testnet and always stream
+/// production data.
+pub(super) async fn init_consolidated_l1_streams(
+    environment: Environment,
+    base: String,
+    quote: String,
+    venues: Vec<Venue>,
+) -> Result<L1Streams, DataError> {
+    let (base, quote) = (base.as_str(), quote.as_str());
+    let mut builder = Streams::<OrderBooksL1>::builder();
+
+    for venue in venues {
+        builder = match (venue, &environment) {
+            (Venue::BinanceSpot, Environment::Mainnet) => builder.subscribe([(
+                BinanceSpot::default(),
+                base,
+                quote,
+                MarketDataInstrumentKind::Spot,
+                OrderBooksL1,
+            )]),
+            (Venue::BinanceSpot, Environment::Testnet | Environment::Simulator) => builder
+                .subscribe([(
+                    BinanceSpotTestnet::default(),
+                    base,
+                    quote,
+                    MarketDataInstrumentKind::Spot,
+                    OrderBooksL1,
+                )]),
+            (Venue::Kraken, _) => builder.subscribe([(
+                Kraken,
+                base,
+                quote,
+                MarketDataInstrumentKind::Spot,
+                OrderBooksL1,
+            )]),
+        };
+    }
+
+    builder.init().await
+}
+
+pub(super) fn to_venue(exchange: ExchangeId) -> Option<Venue> {
+    match exchange {
+        ExchangeId::BinanceSpot => Some(Venue::BinanceSpot),
+        ExchangeId::Kraken => Some(Venue::Kraken),
+        _ => None,
+    }
+}
+
+/// Updates from an exchange websocket thread.
+pub(super) enum StreamEvent<Update> {
+    Update(Update),
+    Reconnecting,
+}
+
+/// Runs a Binance websocket stream on a blocking thread, reconnecting until `keep_running`
+/// is cleared. `extract` picks the updates of interest from the stream's events.
+pub(super) fn spawn_websocket_stream<Update, Extract>(
+    config: BinanceConfig,
+    stream: String,
+    keep_running: Arc<AtomicBool>,
+    sender: mpsc::Sender<StreamEvent<Update>>,
+    extract: Extract,
+) where
+    Update: Send + 'static,
+    Extract: Fn(WebsocketEvent) -> Option<Update> + Send + 'static,
+{
+    task::spawn_blocking(move || {
+        while keep_running.load(Ordering::Relaxed) {
+            // binance-rs takes its own error type back from the callback.
+            #[allow(clippy::result_large_err)]
+            let mut web_socket: WebSockets<'_> = WebSockets::new(|event: WebsocketEvent| {
+                if let Some(update) = extract(event) {
+                    sender
+                        .blocking_send(StreamEvent::Update(update))
+                        .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                }
+
+                Ok(())
+            });
+
+            if let Err(error) = web_socket.connect_with_config(&stream, &config) {
+                eprintln!("Failed to connect to {}: {}", stream, error);
+            } else {
+                if let Err(error) = web_socket.event_loop(&keep_running) {
+                    eprintln!("Error: {}", error);
+                }
+                web_socket
+                    .disconnect()
+                    .unwrap_or_else(|err| eprintln!("Failed to disconnect {}: {}", stream, err));
+            }
+
+            if keep_running.load(Ordering::Relaxed) {
+                sender
+                    .blocking_send(StreamEvent::Reconnecting)
+                    .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                std::thread::sleep(STREAM_RECONNECT_DELAY);
+            }
+        }
+    });
+}
+
+fn to_trade_data(
+    instrument: &InstrumentId,
+    trade: MarketEvent<MarketDataInstrument, PublicTrade>,
+) -> Option<TradeData> {
+    let (Some(price), Some(quantity)) = (
+        Decimal::from_f64(trade.kind.price),
+        Decimal::from_f64(trade.kind.amount),
+    ) else {
+        eprintln!(
+            "Dropping trade {} with invalid price or quantity.",
+            trade.kind.id
+        );
+        return None;
+    };
+
+    let aggressor_side = match trade.kind.side {
+        Side::Buy => AggressorSide::Buy,
+        Side::Sell => AggressorSide::Sell,
+    };
+
+    Some(TradeData::new(
+        instrument.clone(),
+        price,
+        quantity,
+        aggressor_side,
+        trade.time_exchange,
+    ))
+}
+
+/// Binance public trades of `base`/`quote`, or `None` when subscribing failed.
+pub(super) async fn binance_trades(
+    server: BinanceServer,
+    base: String,
+    quote: String,
+) -> Option<impl Stream<Item = TradeData> + Unpin> {
+    let instrument = instrument_id::intern(&(base.clone() + quote.as_str()));
+
+    let trade_stream =
+        match init_streams(move || init_binance_streams(server, base, quote, PublicTrades)).await {
+            Ok(trade_stream) => trade_stream,
+            Err(error) => {
+                eprintln!(
+                    "Failed to subscribe to trades for {}: {}",
+                    instrument, error
+                );
+                return None;
+            }
+        };
+
+    // Binance is the only exchange subscribed.
+    let binance_trade_stream = trade_stream
+        .select_all()
+        .with_error_handler(|error| eprintln!("MarketStream generated error {}", error));
+
+    Some(binance_trade_stream.filter_map(move |msg| match msg {
+        Event::Item(trade) => to_trade_data(&instrument, trade),
+        Event::Reconnecting(origin) => {
+            eprintln!("Reconnecting to trades {}.", origin);
+            None
+        }
+    }))
+}
+
+/// None when the exchange sent a close time that is out of range.
+pub(super) fn to_ticker(
+    instrument: &InstrumentId,
+    ticker_event: &DayTickerEvent,
+) -> Option<Ticker> {
+    let parse = |value: &str| Decimal::from_str(value).unwrap_or_default();
+
+    Some(Ticker {
+        symbol: instrument.clone(),
+        last_price: parse(&ticker_event.current_close),
+        open_price: parse(&ticker_event.open),
+        high_price: parse(&ticker_event.high),
+        low_price: parse(&ticker_event.low),
+        volume: parse(&ticker_event.volume),
+        price_change_pct: parse(&ticker_event.price_change_percent),
+        close_time: Utc
+            .timestamp_millis_opt(ticker_event.close_time as i64)
+            .single()?,
+    })
+}
+
+/// None when the exchange sent an open or close time that is out of range.
+pub(super) fn kline_to_candle(
+    instrument: &InstrumentId,
+    interval: CandleInterval,
+    kline: &Kline,
+) -> Option<Candle> {
+    let parse = |value: &str| Decimal::from_str(value).unwrap_or_default();
+
+    Some(Candle {
+        symbol: instrument.clone(),
+        interval,
+        open_time: Utc.timestamp_millis_opt(kline.open_time).single()?,
+        close_time: Utc.timestamp_millis_opt(kline.close_time).single()?,
+        open: parse(&kline.open),
+        high: parse(&kline.high),
+        low: parse(&kline.low),
+        close: parse(&kline.close),
+        volume: parse(&kline.volume),
+        trade_count: kline.number_of_trades as u64,
+        is_closed: kline.is_final_bar,
+    })
+}
@@ -1,70 +1,46 @@
+use super::analytics_engine::AnalyticsCache;
 use super::feed_actor::{run_my_actor, FeedActor};
-use super::messages::messages::FeedUpdate;
+use super::messages::analytics::Analytics;
+use super::messages::candle::{CandleInterval, CandleSource};
+use super::messages::feed_statistics::{FeedStatistics, MailboxStatistics};
+use super::messages::history::HistoryRequest;
+use super::messages::messages::{FeedUpdate, L2UpdateMode};
+use super::messages::ticker::Ticker;
 use super::FeedMessages;
 use crate::common_types::tracked_sender::TrackedSender;
-use barter_data_sniper::exchange::binance::spot::BinanceSpotTestnet;
-use barter_data_sniper::streams::Streams;
-use barter_data_sniper::subscription::book::{OrderBooksL1, OrderBooksL2};
-use barter_instrument_copy::instrument::market_data::kind::MarketDataInstrumentKind;
-use std::collections::HashSet;
-use std::sync::Arc;
+use crate::config::{Environment, FeedConfig, SimulatorConfig};
 use tokio::sync::mpsc::{self};
-use tokio::sync::Mutex;
+use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
 #[derive(Clone)]
 pub struct FeedHandle {
     sender: mpsc::Sender<FeedMessages>,
+    analytics: AnalyticsCache,
 }
 
 impl FeedHandle {
-    pub async fn new(trading_pairs: HashSet<(String, String)>) -> (Self, JoinHandle<()>) {
+    pub fn new(
+        environment: Environment,
+        simulator_config: SimulatorConfig,
+        feed_config: FeedConfig,
+    ) -> (Self, JoinHandle<()>) {
         let (sender, receiver) = mpsc::channel(100);
 
-        let l1_stream = Arc::new(Mutex::new(
-            trading_pairs
-                .iter()
-                .fold(
-                    Streams::<OrderBooksL1>::builder(),
-                    |builder, (base, quote)| {
-                        builder.subscribe([(
-                            BinanceSpotTestnet::default(),
-                            base.as_str(),
-                            quote.as_str(),
-                            MarketDataInstrumentKind::Spot,
-                            OrderBooksL1,
-                        )])
-                    },
-                )
-                .init()
-                .await
-                .unwrap(),
-        ));
-
-        let l2_stream = Arc::new(Mutex::new(
-            trading_pairs
-                .iter()
-                .fold(
-                    Streams::<OrderBooksL2>::builder(),
-                    |builder, (base, quote)| {
-                        builder.subscribe([(
-                            BinanceSpotTestnet::default(),
-                            base.as_str(),
-                            quote.as_str(),
-                            MarketDataInstrumentKind::Spot,
-                            OrderBooksL2,
-                        )])
-                    },
-                )
-                .init()
-                .await
-                .unwrap(),
-        ));
-
-        let actor = FeedActor::new(receiver, l1_stream, l2_stream);
+        let actor = FeedActor::new(receiver, environment, simulator_config, feed_config);
+        let analytics = actor.analytics_cache();
         let handle = tokio::spawn(run_my_actor(actor));
 
-        (Self { sender }, handle)
+        (Self { sender, analytics }, handle)
+    }
+
+    /// A handle whose messages go to `sender`, for tests of what algorithms ask of the feed.
+    #[cfg(test)]
+    pub(crate) fn with_sender(sender: mpsc::Sender<FeedMessages>) -> Self {
+        Self {
+            sender,
+            analytics: AnalyticsCache::new(Default::default()),
+        }
     }
 
     pub fn subscribe_to_l1<AlgoId, Symbol>(
@@ -81,7 +57,7 @@ impl FeedHandle {
             algo_id: algo_id.into(),
             base: base.into(),
             quote: quote.into(),
-            subscriber: subscriber,
+            subscriber,
         });
 
         if sending_result.is_err() {
@@ -116,6 +92,8 @@ impl FeedHandle {
         algo_id: AlgoId,
         base: Symbol,
         quote: Symbol,
+        depth: usize,
+        mode: L2UpdateMode,
         subscriber: TrackedSender<FeedUpdate>,
     ) where
         Symbol: Into<String>,
@@ -125,7 +103,9 @@ impl FeedHandle {
             algo_id: algo_id.into(),
             base: base.into(),
             quote: quote.into(),
-            subscriber: subscriber,
+            depth,
+            mode,
+            subscriber,
         });
 
         if sending_result.is_err() {
@@ -154,4 +134,349 @@ impl FeedHandle {
             eprintln!("Failed to send message: {:?}", sending_result);
         }
     }
+
+    pub fn subscribe_to_consolidated_l1<AlgoId, Symbol>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        subscriber: TrackedSender<FeedUpdate>,
+    ) where
+        Symbol: Into<String>,
+        AlgoId: Into<String>,
+    {
+        let sending_result = self
+            .sender
+            .try_send(FeedMessages::SubscribeToConsolidatedL1 {
+                algo_id: algo_id.into(),
+                base: base.into(),
+                quote: quote.into(),
+                subscriber,
+            });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn unsubscribe_from_consolidated_l1<Symbol, AlgoId>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        subscriber: &TrackedSender<FeedUpdate>,
+    ) where
+        AlgoId: Into<String>,
+        Symbol: Into<String>,
+    {
+        let sending_result = self
+            .sender
+            .try_send(FeedMessages::UnsubscribeFromConsolidatedL1 {
+                algo_id: algo_id.into(),
+                base: base.into(),
+                quote: quote.into(),
+                subscriber: subscriber.clone(),
+            });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn subscribe_to_trades<AlgoId, Symbol>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        subscriber: TrackedSender<FeedUpdate>,
+    ) where
+        Symbol: Into<String>,
+        AlgoId: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::SubscribeToTrades {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            subscriber,
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn unsubscribe_from_trades<Symbol, AlgoId>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        subscriber: &TrackedSender<FeedUpdate>,
+    ) where
+        AlgoId: Into<String>,
+        Symbol: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::UnsubscribeFromTrades {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            subscriber: subscriber.clone(),
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn subscribe_to_candles<AlgoId, Symbol>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        interval: CandleInterval,
+        source: CandleSource,
+        subscriber: TrackedSender<FeedUpdate>,
+    ) where
+        Symbol: Into<String>,
+        AlgoId: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::SubscribeToCandles {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            interval,
+            source,
+            subscriber,
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn unsubscribe_from_candles<Symbol, AlgoId>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        interval: CandleInterval,
+        source: CandleSource,
+        subscriber: &TrackedSender<FeedUpdate>,
+    ) where
+        AlgoId: Into<String>,
+        Symbol: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::UnsubscribeFromCandles {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            interval,
+            source,
+            subscriber: subscriber.clone(),
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn request_snapshot<AlgoId, Symbol>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        depth: usize,
+        subscriber: TrackedSender<FeedUpdate>,
+    ) where
+        Symbol: Into<String>,
+        AlgoId: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::RequestSnapshot {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            depth,
+            subscriber,
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn request_history<AlgoId, Symbol>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        request: HistoryRequest,
+        subscriber: TrackedSender<FeedUpdate>,
+    ) where
+        Symbol: Into<String>,
+        AlgoId: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::RequestHistory {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            request,
+            subscriber,
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn subscribe_to_analytics<AlgoId, Symbol>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        subscriber: TrackedSender<FeedUpdate>,
+    ) where
+        Symbol: Into<String>,
+        AlgoId: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::SubscribeToAnalytics {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            subscriber,
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn unsubscribe_from_analytics<Symbol, AlgoId>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        subscriber: &TrackedSender<FeedUpdate>,
+    ) where
+        AlgoId: Into<String>,
+        Symbol: Into<String>,
+    {
+        let sending_result = self
+            .sender
+            .try_send(FeedMessages::UnsubscribeFromAnalytics {
+                algo_id: algo_id.into(),
+                base: base.into(),
+                quote: quote.into(),
+                subscriber: subscriber.clone(),
+            });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn subscribe_to_ticker<AlgoId, Symbol>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        subscriber: TrackedSender<FeedUpdate>,
+    ) where
+        Symbol: Into<String>,
+        AlgoId: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::SubscribeToTicker {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            subscriber,
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    pub fn unsubscribe_from_ticker<Symbol, AlgoId>(
+        &self,
+        algo_id: AlgoId,
+        base: Symbol,
+        quote: Symbol,
+        subscriber: &TrackedSender<FeedUpdate>,
+    ) where
+        AlgoId: Into<String>,
+        Symbol: Into<String>,
+    {
+        let sending_result = self.sender.try_send(FeedMessages::UnsubscribeFromTicker {
+            algo_id: algo_id.into(),
+            base: base.into(),
+            quote: quote.into(),
+            subscriber: subscriber.clone(),
+        });
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+        }
+    }
+
+    /// Current 24 hour statistics of an instrument, without subscribing. Meant for checks
+    /// before algorithms start, such as validating configured limit prices. Fails with the
+    /// reason when there is no ticker, e.g. when the exchange could not be reached.
+    pub async fn get_ticker<Symbol>(&self, base: Symbol, quote: Symbol) -> Result<Ticker, String>
+    where
+        Symbol: Into<String>,
+    {
+        let (responder, receiver) = oneshot::channel();
+
+        self.sender
+            .send(FeedMessages::GetTicker {
+                base: base.into(),
+                quote: quote.into(),
+                responder,
+            })
+            .await
+            .map_err(|error| format!("failed to send message: {}", error))?;
+
+        receiver
+            .await
+            .map_err(|error| format!("no answer from the feed: {}", error))?
+    }
+
+    /// Latest analytics for an instrument somebody is subscribed to.
+    pub fn analytics(&self, base: &str, quote: &str) -> Option<Analytics> {
+        self.analytics.get(&(base.to_owned() + quote))
+    }
+
+    /// Update rates, exchange latency and delivery latency per stream since the last logged
+    /// summary.
+    pub async fn get_statistics(&self) -> Option<Vec<FeedStatistics>> {
+        let (responder, receiver) = oneshot::channel();
+
+        let sending_result = self
+            .sender
+            .send(FeedMessages::GetStatistics { responder })
+            .await;
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+            return None;
+        }
+
+        receiver.await.ok()
+    }
+
+    /// Updates currently queued for each subscriber's algo context.
+    pub async fn get_mailbox_statistics(&self) -> Option<Vec<MailboxStatistics>> {
+        let (responder, receiver) = oneshot::channel();
+
+        let sending_result = self
+            .sender
+            .send(FeedMessages::GetMailboxStatistics { responder })
+            .await;
+
+        if sending_result.is_err() {
+            eprintln!("Failed to send message: {:?}", sending_result);
+            return None;
+        }
+
+        receiver.await.ok()
+    }
 }
@@ -1,7 +1,17 @@
-use super::{feed_handle::FeedHandle, messages::messages::FeedUpdate};
+use super::{
+    feed_handle::FeedHandle,
+    messages::{
+        analytics::Analytics,
+        candle::{CandleInterval, CandleSource},
+        history::HistoryRequest,
+        messages::{FeedUpdate, L2UpdateMode},
+    },
+};
 use crate::common_types::tracked_sender::TrackedSender;
 use tokio::sync::mpsc;
 
+pub const DEFAULT_L2_DEPTH: usize = 20;
+
 #[derive(Clone)]
 pub struct FeedService {
     feed_handle: FeedHandle,
@@ -12,7 +22,7 @@ pub struct FeedService {
 impl FeedService {
     pub fn new<AlgoId>(
         feed_handle: &FeedHandle,
-        context_id: &String,
+        context_id: &str,
         algo_id: AlgoId,
         sender: &mpsc::Sender<FeedUpdate>,
     ) -> Self
@@ -22,7 +32,7 @@ impl FeedService {
         Self {
             feed_handle: feed_handle.clone(),
             algo_id: algo_id.into(),
-            meesage_sender: TrackedSender::new(sender.clone(), context_id.clone()),
+            meesage_sender: TrackedSender::new(sender.clone(), context_id.to_owned()),
         }
     }
 
@@ -53,11 +63,27 @@ impl FeedService {
     pub fn subscribe_to_l2<Symbol>(&self, base: Symbol, quote: Symbol)
     where
         Symbol: Into<String>,
+    {
+        self.subscribe_to_l2_with_depth(base, quote, DEFAULT_L2_DEPTH, L2UpdateMode::Snapshot);
+    }
+
+    /// Subscribes to the book truncated to `depth` levels per side. In delta mode the first
+    /// update is an `L2Update` snapshot and later ones are `L2Delta` changes.
+    pub fn subscribe_to_l2_with_depth<Symbol>(
+        &self,
+        base: Symbol,
+        quote: Symbol,
+        depth: usize,
+        mode: L2UpdateMode,
+    ) where
+        Symbol: Into<String>,
     {
         self.feed_handle.subscribe_to_l2(
             self.algo_id.as_str(),
             base.into(),
             quote.into(),
+            depth,
+            mode,
             self.meesage_sender.clone(),
         );
     }
@@ -73,4 +99,180 @@ impl FeedService {
             &self.meesage_sender,
         );
     }
+
+    /// Subscribes to the best bid and ask across the venues configured for the instrument.
+    pub fn subscribe_to_consolidated_l1<Symbol>(&self, base: Symbol, quote: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.subscribe_to_consolidated_l1(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            self.meesage_sender.clone(),
+        );
+    }
+
+    pub fn unsubscribe_from_consolidated_l1<Symbol>(&self, base: Symbol, quote: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.unsubscribe_from_consolidated_l1(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            &self.meesage_sender,
+        );
+    }
+
+    /// Subscribes to mid, microprice, spread, VWAP, volatility and imbalance for the instrument.
+    pub fn subscribe_to_analytics<Symbol>(&self, base: Symbol, quote: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.subscribe_to_analytics(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            self.meesage_sender.clone(),
+        );
+    }
+
+    pub fn unsubscribe_from_analytics<Symbol>(&self, base: Symbol, quote: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.unsubscribe_from_analytics(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            &self.meesage_sender,
+        );
+    }
+
+    /// Latest analytics for the instrument, available while subscribed to them.
+    pub fn analytics(&self, base: &str, quote: &str) -> Option<Analytics> {
+        self.feed_handle.analytics(base, quote)
+    }
+
+    /// Subscribes to rolling 24 hour high, low, last price and volume for the instrument.
+    pub fn subscribe_to_ticker<Symbol>(&self, base: Symbol, quote: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.subscribe_to_ticker(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            self.meesage_sender.clone(),
+        );
+    }
+
+    pub fn unsubscribe_from_ticker<Symbol>(&self, base: Symbol, quote: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.unsubscribe_from_ticker(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            &self.meesage_sender,
+        );
+    }
+
+    /// Subscribes to every market trade of the instrument, from Binance's raw trade stream
+    /// rather than the aggregated one.
+    pub fn subscribe_to_trades<Symbol>(&self, base: Symbol, quote: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.subscribe_to_trades(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            self.meesage_sender.clone(),
+        );
+    }
+
+    pub fn unsubscribe_from_trades<Symbol>(&self, base: Symbol, quote: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.unsubscribe_from_trades(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            &self.meesage_sender,
+        );
+    }
+
+    /// Subscribes to closed and in-progress bars for the instrument.
+    pub fn subscribe_to_candles<Symbol>(
+        &self,
+        base: Symbol,
+        quote: Symbol,
+        interval: CandleInterval,
+        source: CandleSource,
+    ) where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.subscribe_to_candles(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            interval,
+            source,
+            self.meesage_sender.clone(),
+        );
+    }
+
+    pub fn unsubscribe_from_candles<Symbol>(
+        &self,
+        base: Symbol,
+        quote: Symbol,
+        interval: CandleInterval,
+        source: CandleSource,
+    ) where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.unsubscribe_from_candles(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            interval,
+            source,
+            &self.meesage_sender,
+        );
+    }
+
+    /// Requests the last known L1 and L2 (truncated to `depth`) for the instrument. The answer
+    /// arrives as a `FeedUpdate::Snapshot`.
+    pub fn request_snapshot<Symbol>(&self, base: Symbol, quote: Symbol, depth: usize)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.request_snapshot(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            depth,
+            self.meesage_sender.clone(),
+        );
+    }
+
+    /// Requests recent bars and trades for the instrument. They arrive as a
+    /// `FeedUpdate::History` and are handed to `Algorithm::warm_up`; subscribe to live data
+    /// from there to get history first.
+    pub fn request_history<Symbol>(&self, base: Symbol, quote: Symbol, request: HistoryRequest)
+    where
+        Symbol: Into<String>,
+    {
+        self.feed_handle.request_history(
+            self.algo_id.as_str(),
+            base.into(),
+            quote.into(),
+            request,
+            self.meesage_sender.clone(),
+        );
+    }
 }
@@ -0,0 +1,211 @@
+use super::messages::feed_statistics::FeedStatistics;
+use super::shared_map::SharedMap;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Counters of one stream and instrument. Publishers only add to them, so recording never
+/// waits on the statistics being read or rolled.
+struct StreamStats {
+    total_updates: AtomicU64,
+    rejected_updates: AtomicU64,
+    window_updates: AtomicU64,
+    window_start_us: AtomicU64,
+    window_latency_sum_us: AtomicI64,
+    window_max_latency_us: AtomicI64,
+    window_deliveries: AtomicU64,
+    window_delivery_sum_us: AtomicI64,
+    window_max_delivery_us: AtomicI64,
+}
+
+impl StreamStats {
+    fn new(window_start_us: u64) -> Self {
+        Self {
+            total_updates: AtomicU64::new(0),
+            rejected_updates: AtomicU64::new(0),
+            window_updates: AtomicU64::new(0),
+            window_start_us: AtomicU64::new(window_start_us),
+            window_latency_sum_us: AtomicI64::new(0),
+            window_max_latency_us: AtomicI64::new(i64::MIN),
+            window_deliveries: AtomicU64::new(0),
+            window_delivery_sum_us: AtomicI64::new(0),
+            window_max_delivery_us: AtomicI64::new(i64::MIN),
+        }
+    }
+
+    fn statistics(&self, stream: &str, symbol: &str, window: Window) -> FeedStatistics {
+        let elapsed = window.elapsed_us as f64 / 1_000_000.0;
+        let (average_latency_ms, max_latency_ms) =
+            latency_ms(window.updates, window.latency_sum_us, window.max_latency_us);
+        let (average_delivery_latency_ms, max_delivery_latency_ms) = latency_ms(
+            window.deliveries,
+            window.delivery_sum_us,
+            window.max_delivery_us,
+        );
+
+        FeedStatistics {
+            stream: stream.to_owned(),
+            symbol: symbol.to_owned(),
+            total_updates: self.total_updates.load(Ordering::Relaxed),
+            rejected_updates: self.rejected_updates.load(Ordering::Relaxed),
+            updates_per_second: if elapsed > 0.0 {
+                window.updates as f64 / elapsed
+            } else {
+                0.0
+            },
+            average_latency_ms,
+            max_latency_ms,
+            average_delivery_latency_ms,
+            max_delivery_latency_ms,
+        }
+    }
+}
+
+fn latency_ms(count: u64, sum_us: i64, max_us: i64) -> (f64, f64) {
+    if count > 0 {
+        (
+            sum_us as f64 / count as f64 / 1000.0,
+            max_us as f64 / 1000.0,
+        )
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// Figures of the current window, read or reset by the reporting side.
+struct Window {
+    updates: u64,
+    elapsed_us: u64,
+    latency_sum_us: i64,
+    max_latency_us: i64,
+    deliveries: u64,
+    delivery_sum_us: i64,
+    max_delivery_us: i64,
+}
+
+/// Counts updates per stream and instrument with their latency from the exchange to the
+/// feed, and from the feed to the algo contexts.
+#[derive(Clone)]
+pub(super) struct FeedStats {
+    started: Instant,
+    streams: Arc<SharedMap<&'static str, SharedMap<String, StreamStats>>>,
+}
+
+impl Default for FeedStats {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            streams: Arc::default(),
+        }
+    }
+}
+
+impl FeedStats {
+    pub fn record(&self, stream: &'static str, symbol: &str, exchange_time: DateTime<Utc>) {
+        let latency_us = (Utc::now() - exchange_time).num_microseconds().unwrap_or(0);
+
+        let stats = self.stream_stats(stream, symbol);
+        stats.total_updates.fetch_add(1, Ordering::Relaxed);
+        stats.window_updates.fetch_add(1, Ordering::Relaxed);
+        stats
+            .window_latency_sum_us
+            .fetch_add(latency_us, Ordering::Relaxed);
+        stats
+            .window_max_latency_us
+            .fetch_max(latency_us, Ordering::Relaxed);
+    }
+
+    /// Called once an update published at `received_time` was handed to an algo context.
+    pub fn record_delivery(
+        &self,
+        stream: &'static str,
+        symbol: &str,
+        received_time: DateTime<Utc>,
+    ) {
+        let latency_us = (Utc::now() - received_time).num_microseconds().unwrap_or(0);
+
+        let stats = self.stream_stats(stream, symbol);
+        stats.window_deliveries.fetch_add(1, Ordering::Relaxed);
+        stats
+            .window_delivery_sum_us
+            .fetch_add(latency_us, Ordering::Relaxed);
+        stats
+            .window_max_delivery_us
+            .fetch_max(latency_us, Ordering::Relaxed);
+    }
+
+    pub fn record_rejection(&self, stream: &'static str, symbol: &str) {
+        self.stream_stats(stream, symbol)
+            .rejected_updates
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn statistics(&self) -> Vec<FeedStatistics> {
+        let now_us = self.elapsed_us();
+
+        self.collect(|stream, symbol, stats| {
+            let window = Window {
+                updates: stats.window_updates.load(Ordering::Relaxed),
+                elapsed_us: now_us.saturating_sub(stats.window_start_us.load(Ordering::Relaxed)),
+                latency_sum_us: stats.window_latency_sum_us.load(Ordering::Relaxed),
+                max_latency_us: stats.window_max_latency_us.load(Ordering::Relaxed),
+                deliveries: stats.window_deliveries.load(Ordering::Relaxed),
+                delivery_sum_us: stats.window_delivery_sum_us.load(Ordering::Relaxed),
+                max_delivery_us: stats.window_max_delivery_us.load(Ordering::Relaxed),
+            };
+            stats.statistics(stream, symbol, window)
+        })
+    }
+
+    /// Returns the statistics of the current window and starts a new one.
+    pub fn roll_window(&self) -> Vec<FeedStatistics> {
+        let now_us = self.elapsed_us();
+
+        self.collect(|stream, symbol, stats| {
+            let window = Window {
+                updates: stats.window_updates.swap(0, Ordering::Relaxed),
+                elapsed_us: now_us
+                    .saturating_sub(stats.window_start_us.swap(now_us, Ordering::Relaxed)),
+                latency_sum_us: stats.window_latency_sum_us.swap(0, Ordering::Relaxed),
+                max_latency_us: stats
+                    .window_max_latency_us
+                    .swap(i64::MIN, Ordering::Relaxed),
+                deliveries: stats.window_deliveries.swap(0, Ordering::Relaxed),
+                delivery_sum_us: stats.window_delivery_sum_us.swap(0, Ordering::Relaxed),
+                max_delivery_us: stats
+                    .window_max_delivery_us
+                    .swap(i64::MIN, Ordering::Relaxed),
+            };
+            stats.statistics(stream, symbol, window)
+        })
+    }
+
+    /// Looks the instrument up by reference so only its first update allocates a key.
+    fn stream_stats(&self, stream: &'static str, symbol: &str) -> Arc<StreamStats> {
+        self.streams
+            .get_or_insert_with(&stream, SharedMap::default)
+            .get_or_insert_with(symbol, || StreamStats::new(self.elapsed_us()))
+    }
+
+    fn collect(
+        &self,
+        statistics: impl Fn(&str, &str, &StreamStats) -> FeedStatistics,
+    ) -> Vec<FeedStatistics> {
+        self.streams
+            .snapshot()
+            .iter()
+            .flat_map(|(stream, symbols)| {
+                symbols
+                    .snapshot()
+                    .iter()
+                    .map(|(symbol, stats)| statistics(stream, symbol, stats))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn elapsed_us(&self) -> u64 {
+        self.started.elapsed().as_micros() as u64
+    }
+}
@@ -0,0 +1,417 @@
+use super::candle_builder::CandleBuilder;
+use super::messages::candle::{Candle, CandleInterval};
+use super::messages::history::{History, HistoryRequest};
+use super::messages::trade_data::TradeData;
+use super::recorder::{self, RecordedUpdate};
+use crate::common_types::instrument_id::InstrumentId;
+use crate::common_types::side::Side;
+use crate::config::{Environment, FeedConfig, HistorySource};
+use binance::api::Binance;
+use binance::config::Config as BinanceConfig;
+use binance::market::Market as BinanceMarket;
+use binance::model::{AggTrade, KlineSummaries, KlineSummary};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tokio::task;
+
+/// Binance caps klines and aggregate trades at 1000 per request.
+const MAX_REST_LIMIT: usize = 1000;
+
+/// Loads recent bars and trades for warming up algorithms, from the exchange REST API or
+/// from recorded files.
+#[derive(Clone)]
+pub(super) struct HistoryLoader {
+    /// `None` when exchange history is unavailable: the simulator or `[feed.endpoints]`.
+    binance_config: Option<BinanceConfig>,
+    source: HistorySource,
+    directory: String,
+    /// With replay, only recordings from before the replayed day count as history.
+    before_date: Option<String>,
+}
+
+impl HistoryLoader {
+    pub fn new(environment: &Environment, feed_config: &FeedConfig) -> Self {
+        Self {
+            binance_config: feed_config.history_config(environment),
+            source: feed_config.history.source.clone(),
+            directory: feed_config.recorder.directory.clone(),
+            before_date: feed_config
+                .replay
+                .as_ref()
+                .map(|replay_config| replay_config.date.clone()),
+        }
+    }
+
+    pub async fn load(&self, instrument: InstrumentId, request: HistoryRequest) -> History {
+        let loader = self.clone();
+        let symbol = instrument.clone();
+
+        let result = task::spawn_blocking(move || {
+            let (candles, trades) = match loader.source {
+                HistorySource::Exchange => match loader.market() {
+                    Some(market) => (
+                        loader.exchange_candles(&market, &instrument, &request),
+                        loader.exchange_trades(&market, &instrument, &request),
+                    ),
+                    None => {
+                        eprintln!(
+                            "No exchange history for {} without Binance, building it from recordings.",
+                            instrument
+                        );
+                        loader.recorded_history(&instrument, &request, Utc::now())
+                    }
+                },
+                HistorySource::Recordings => loader.recorded_history(&instrument, &request, Utc::now()),
+            };
+
+            History {
+                symbol: instrument,
+                candles,
+                trades,
+            }
+        })
+        .await;
+
+        result.unwrap_or_else(|error| {
+            eprintln!("Failed to load history for {}: {}", symbol, error);
+            History {
+                symbol,
+                candles: Vec::new(),
+                trades: Vec::new(),
+            }
+        })
+    }
+
+    fn market(&self) -> Option<BinanceMarket> {
+        self.binance_config
+            .as_ref()
+            .map(|binance_config| Binance::new_with_config(None, None, binance_config))
+    }
+
+    fn exchange_candles(
+        &self,
+        market: &BinanceMarket,
+        instrument: &str,
+        request: &HistoryRequest,
+    ) -> Vec<Candle> {
+        if request.candle_count == 0 {
+            return Vec::new();
+        }
+
+        let klines = market.get_klines(
+            instrument.to_uppercase(),
+            request.candle_interval.as_str(),
+            request.candle_count.min(MAX_REST_LIMIT) as u16,
+            None::<u64>,
+            None::<u64>,
+        );
+
+        match klines {
+            Ok(KlineSummaries::AllKlineSummaries(klines)) => klines
+                .iter()
+                .map(|kline| to_candle(instrument, request.candle_interval, kline))
+                .collect(),
+            Err(error) => {
+                eprintln!("Failed to fetch klines for {}: {}", instrument, error);
+                Vec::new()
+            }
+        }
+    }
+
+    fn exchange_trades(
+        &self,
+        market: &BinanceMarket,
+        instrument: &str,
+        request: &HistoryRequest,
+    ) -> Vec<TradeData> {
+        if request.trade_count == 0 {
+            return Vec::new();
+        }
+
+        let trades = market.get_agg_trades(
+            instrument.to_uppercase(),
+            None::<u64>,
+            None::<u64>,
+            None::<u64>,
+            request.trade_count.min(MAX_REST_LIMIT) as u16,
+        );
+
+        match trades {
+            Ok(trades) => trades
+                .iter()
+                .map(|trade| to_trade_data(instrument, trade))
+                .collect(),
+            Err(error) => {
+                eprintln!("Failed to fetch trades for {}: {}", instrument, error);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Reads recorded trades from the newest day back until the days read hold enough
+    /// trades and reach back to the first requested bar, then builds the bars locally.
+    fn recorded_history(
+        &self,
+        instrument: &str,
+        request: &HistoryRequest,
+        now: DateTime<Utc>,
+    ) -> (Vec<Candle>, Vec<TradeData>) {
+        let bars = request.candle_interval.duration() * request.candle_count as u32;
+        let first_bar = Duration::from_std(bars)
+            .ok()
+            .and_then(|bars| now.checked_sub_signed(bars))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let days = self.recorded_days(instrument, request.trade_count, first_bar);
+
+        let mut candle_builder = CandleBuilder::new(instrument, request.candle_interval);
+        let mut candles = Vec::new();
+        let mut trades = Vec::new();
+
+        for trade_data in days.into_iter().rev().flatten() {
+            candles.extend(
+                candle_builder
+                    .on_trade(&trade_data)
+                    .into_iter()
+                    .filter(|candle| candle.is_closed),
+            );
+            trades.push(trade_data);
+        }
+        candles.extend(candle_builder.close_until(now));
+
+        (
+            last(candles, request.candle_count),
+            last(trades, request.trade_count),
+        )
+    }
+
+    /// Trades of each recorded day, newest day first, stopping at the first day by which
+    /// there are `trade_count` trades and the day starts no later than `first_bar`.
+    fn recorded_days(
+        &self,
+        instrument: &str,
+        trade_count: usize,
+        first_bar: DateTime<Utc>,
+    ) -> Vec<Vec<TradeData>> {
+        let mut days = Vec::new();
+        let mut read_count = 0;
+        for (date, path) in self.recorded_files(instrument).into_iter().rev() {
+            let day = recorded_trades(instrument, &path);
+            read_count += day.len();
+            days.push(day);
+
+            let day_start = date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+            if read_count >= trade_count && day_start <= first_bar {
+                break;
+            }
+        }
+
+        days
+    }
+
+    /// Recorded days of the instrument, oldest first.
+    fn recorded_files(&self, instrument: &str) -> Vec<(NaiveDate, PathBuf)> {
+        let directory = PathBuf::from(&self.directory).join(instrument);
+
+        let entries = match fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(error) => {
+                eprintln!(
+                    "Failed to read recordings in {}: {}",
+                    directory.display(),
+                    error
+                );
+                return Vec::new();
+            }
+        };
+
+        let mut files: Vec<(NaiveDate, PathBuf)> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter_map(|path| {
+                let stem = path.file_stem().and_then(|stem| stem.to_str())?;
+                let date = NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()?;
+
+                let wanted = path.extension().is_some_and(|extension| extension == "csv")
+                    && self
+                        .before_date
+                        .as_ref()
+                        .is_none_or(|before_date| stem < before_date.as_str());
+                wanted.then_some((date, path))
+            })
+            .collect();
+
+        files.sort();
+        files
+    }
+}
+
+fn recorded_trades(instrument: &str, path: &Path) -> Vec<TradeData> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("Failed to open recording {}: {}", path.display(), error);
+            return Vec::new();
+        }
+    };
+
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| recorder::decode_event(instrument, &line))
+        .filter_map(|event| match event.update {
+            RecordedUpdate::Trade(trade_data) => Some(trade_data),
+            _ => None,
+        })
+        .collect()
+}
+
+fn last<T>(mut values: Vec<T>, count: usize) -> Vec<T> {
+    values.split_off(values.len().saturating_sub(count))
+}
+
+fn to_candle(instrument: &str, interval: CandleInterval, kline: &KlineSummary) -> Candle {
+    let parse = |value: &str| Decimal::from_str(value).unwrap_or_default();
+    let close_time = Utc.timestamp_millis_opt(kline.close_time).unwrap();
+
+    Candle {
+        symbol: instrument.into(),
+        interval,
+        open_time: Utc.timestamp_millis_opt(kline.open_time).unwrap(),
+        close_time,
+        open: parse(&kline.open),
+        high: parse(&kline.high),
+        low: parse(&kline.low),
+        close: parse(&kline.close),
+        volume: parse(&kline.volume),
+        trade_count: kline.number_of_trades as u64,
+        is_closed: close_time < Utc::now(),
+    }
+}
+
+fn to_trade_data(instrument: &str, trade: &AggTrade) -> TradeData {
+    TradeData::new(
+        instrument,
+        Decimal::from_f64(trade.price).unwrap_or_default(),
+        Decimal::from_f64(trade.qty).unwrap_or_default(),
+        // The buyer being the maker means the seller crossed the spread.
+        if trade.maker { Side::Sell } else { Side::Buy },
+        Utc.timestamp_millis_opt(trade.time as i64).unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::recorder::RecordedEvent;
+
+    /// Records two trades at 10:00 on each day, priced by day and order, and returns a
+    /// loader of recordings from before the last day.
+    fn recordings(name: &str, days: &[&str]) -> HistoryLoader {
+        let directory =
+            std::env::temp_dir().join(format!("history_{}_{}", name, std::process::id()));
+        let instrument_directory = directory.join("btcusdt");
+        fs::create_dir_all(&instrument_directory).unwrap();
+
+        for (day, date) in days.iter().enumerate() {
+            let lines: Vec<String> = (0..2)
+                .map(|trade| {
+                    let trade_time = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                        .unwrap()
+                        .and_hms_opt(10, 0, 10 * (trade + 1))
+                        .unwrap()
+                        .and_utc();
+                    let trade_data = TradeData::new(
+                        "btcusdt",
+                        Decimal::from(100 + 10 * day as u32 + trade),
+                        Decimal::ONE,
+                        Side::Buy,
+                        trade_time,
+                    );
+                    recorder::encode_event(&RecordedEvent {
+                        receive_time: trade_time,
+                        exchange_time: trade_time,
+                        update: RecordedUpdate::Trade(trade_data),
+                    })
+                })
+                .collect();
+            fs::write(
+                instrument_directory.join(format!("{}.csv", date)),
+                lines.join("\n") + "\n",
+            )
+            .unwrap();
+        }
+
+        HistoryLoader {
+            binance_config: None,
+            source: HistorySource::Recordings,
+            directory: directory.to_string_lossy().into_owned(),
+            before_date: days.last().map(|date| date.to_string()),
+        }
+    }
+
+    const DAYS: [&str; 4] = ["2023-11-12", "2023-11-13", "2023-11-14", "2023-11-15"];
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 11, 14, 10, 5, 0).unwrap()
+    }
+
+    fn prices(trades: &[TradeData]) -> Vec<Decimal> {
+        trades.iter().map(|trade| trade.price).collect()
+    }
+
+    #[test]
+    fn reading_stops_once_the_newest_days_hold_enough_trades() {
+        let loader = recordings("trades", &DAYS);
+        let request = HistoryRequest {
+            candle_interval: CandleInterval::OneMinute,
+            candle_count: 2,
+            trade_count: 3,
+        };
+
+        let first_bar = now() - Duration::minutes(2);
+        let days = loader.recorded_days("btcusdt", request.trade_count, first_bar);
+        assert_eq!(days.len(), 2);
+        assert_eq!(prices(&days[0]), [Decimal::from(120), Decimal::from(121)]);
+
+        let (candles, trades) = loader.recorded_history("btcusdt", &request, now());
+        assert_eq!(
+            prices(&trades),
+            [Decimal::from(111), Decimal::from(120), Decimal::from(121)]
+        );
+        assert_eq!(candles.len(), 2);
+        assert!(candles.iter().all(|candle| candle.is_closed));
+        assert_eq!(candles[0].close, Decimal::from(111));
+        assert_eq!(candles[1].close, Decimal::from(121));
+    }
+
+    #[test]
+    fn reading_goes_back_to_the_first_requested_bar() {
+        let loader = recordings("bars", &DAYS);
+
+        // 25 hours of bars start on the day before, even though one day holds the trades.
+        let first_bar = now() - Duration::hours(25);
+        assert_eq!(loader.recorded_days("btcusdt", 1, first_bar).len(), 2);
+    }
+
+    #[test]
+    fn every_day_before_the_replayed_one_is_read_when_there_are_too_few_trades() {
+        let loader = recordings("all", &DAYS);
+        let request = HistoryRequest {
+            candle_interval: CandleInterval::FiveMinutes,
+            candle_count: 0,
+            trade_count: 10,
+        };
+
+        let (candles, trades) = loader.recorded_history("btcusdt", &request, now());
+        assert!(candles.is_empty());
+        assert_eq!(
+            prices(&trades),
+            [100, 101, 110, 111, 120, 121].map(Decimal::from)
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use crate::common_types::instrument_id::InstrumentId;
+use core::fmt;
+use rust_decimal::Decimal;
+
+/// Signals derived from the top of book and recent trades of one instrument.
+#[derive(Debug, Clone)]
+pub struct Analytics {
+    pub symbol: InstrumentId,
+    pub mid: Decimal,
+    /// Mid weighted towards the side with less quantity, where the next trade is more likely.
+    pub microprice: Decimal,
+    pub spread_bps: Decimal,
+    /// (bid quantity - ask quantity) / (bid quantity + ask quantity), from -1 to 1.
+    pub book_imbalance: Decimal,
+    /// Volume-weighted trade price over the configured window; `None` without trades.
+    pub vwap: Option<Decimal>,
+    /// Standard deviation of mid log returns over the configured number of mid changes.
+    pub realized_volatility: Option<f64>,
+}
+
+impl fmt::Display for Analytics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Analytics {{ symbol: {}, mid: {}, microprice: {}, spread_bps: {}, book_imbalance: {}, vwap: {:?}, realized_volatility: {:?} }}",
+            self.symbol,
+            self.mid,
+            self.microprice,
+            self.spread_bps,
+            self.book_imbalance,
+            self.vwap,
+            self.realized_volatility
+        )
+    }
+}
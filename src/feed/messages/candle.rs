@@ -0,0 +1,87 @@
+use crate::common_types::instrument_id::InstrumentId;
+use chrono::{DateTime, Utc};
+use core::fmt;
+use rust_decimal::Decimal;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    OneSecond,
+    OneMinute,
+    FiveMinutes,
+}
+
+impl CandleInterval {
+    pub fn duration(&self) -> Duration {
+        match self {
+            CandleInterval::OneSecond => Duration::from_secs(1),
+            CandleInterval::OneMinute => Duration::from_secs(60),
+            CandleInterval::FiveMinutes => Duration::from_secs(300),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandleInterval::OneSecond => "1s",
+            CandleInterval::OneMinute => "1m",
+            CandleInterval::FiveMinutes => "5m",
+        }
+    }
+}
+
+impl fmt::Display for CandleInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Where bars come from: the exchange kline stream, or built locally from public trades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleSource {
+    Exchange,
+    Local,
+}
+
+impl fmt::Display for CandleSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CandleSource::Exchange => write!(f, "exchange"),
+            CandleSource::Local => write!(f, "local"),
+        }
+    }
+}
+
+/// OHLCV bar. `is_closed` is false while the bar is still in progress.
+#[derive(Debug, Clone)]
+pub struct Candle {
+    pub symbol: InstrumentId,
+    pub interval: CandleInterval,
+    pub open_time: DateTime<Utc>,
+    pub close_time: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+    pub trade_count: u64,
+    pub is_closed: bool,
+}
+
+impl fmt::Display for Candle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Candle {{ symbol: {}, interval: {}, open_time: {}, open: {:.2}, high: {:.2}, low: {:.2}, close: {:.2}, volume: {:.6}, trades: {}, closed: {} }}",
+            self.symbol,
+            self.interval,
+            self.open_time,
+            self.open,
+            self.high,
+            self.low,
+            self.close,
+            self.volume,
+            self.trade_count,
+            self.is_closed
+        )
+    }
+}
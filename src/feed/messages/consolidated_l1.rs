@@ -0,0 +1,42 @@
+use super::l1_data::L1Data;
+use crate::common_types::instrument_id::InstrumentId;
+use crate::common_types::venue::Venue;
+use core::fmt;
+
+#[derive(Debug, Clone)]
+pub struct VenueL1 {
+    pub venue: Venue,
+    pub l1_data: L1Data,
+}
+
+/// Best bid and ask across venues, tagged with the venue each side comes from, together
+/// with the top of book of every venue.
+#[derive(Debug, Clone)]
+pub struct ConsolidatedL1 {
+    pub symbol: InstrumentId,
+    pub consolidated: L1Data,
+    pub best_bid_venue: Venue,
+    pub best_ask_venue: Venue,
+    pub venues: Vec<VenueL1>,
+}
+
+impl fmt::Display for ConsolidatedL1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ConsolidatedL1 {{ symbol: {}, best_bid: {} @ {}, best_ask: {} @ {}, venues: [",
+            self.symbol,
+            self.consolidated.best_bid_level,
+            self.best_bid_venue,
+            self.consolidated.best_ask_level,
+            self.best_ask_venue
+        )?;
+        for (i, venue_l1) in self.venues.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", venue_l1.venue, venue_l1.l1_data)?;
+        }
+        write!(f, "] }}")
+    }
+}
@@ -0,0 +1,27 @@
+use super::{l1_data::L1Data, l2_data::L2Data};
+use crate::common_types::instrument_id::InstrumentId;
+use core::fmt;
+
+/// Last known market data for an instrument. A side is `None` if no feed has published it yet.
+#[derive(Debug, Clone)]
+pub struct FeedSnapshot {
+    pub symbol: InstrumentId,
+    pub l1: Option<L1Data>,
+    pub l2: Option<L2Data>,
+}
+
+impl fmt::Display for FeedSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FeedSnapshot {{ symbol: {}, l1: ", self.symbol)?;
+        match &self.l1 {
+            Some(l1_data) => write!(f, "{}", l1_data)?,
+            None => write!(f, "None")?,
+        }
+        write!(f, ", l2: ")?;
+        match &self.l2 {
+            Some(l2_data) => write!(f, "{}", l2_data)?,
+            None => write!(f, "None")?,
+        }
+        write!(f, " }}")
+    }
+}
@@ -0,0 +1,53 @@
+use core::fmt;
+
+/// Throughput and latency of one stream over the current reporting window: from the exchange
+/// to the feed, and from the feed to the algo contexts.
+#[derive(Debug, Clone)]
+pub struct FeedStatistics {
+    pub stream: String,
+    pub symbol: String,
+    pub total_updates: u64,
+    pub rejected_updates: u64,
+    pub updates_per_second: f64,
+    pub average_latency_ms: f64,
+    pub max_latency_ms: f64,
+    pub average_delivery_latency_ms: f64,
+    pub max_delivery_latency_ms: f64,
+}
+
+/// Updates waiting in one subscriber's mailbox for its algo context to take them.
+#[derive(Debug, Clone)]
+pub struct MailboxStatistics {
+    pub subscriber: String,
+    pub queued: usize,
+    pub capacity: usize,
+}
+
+impl fmt::Display for FeedStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} updates, {} rejected, {:.1}/s, latency avg {:.2} ms, max {:.2} ms, \
+            delivery avg {:.2} ms, max {:.2} ms",
+            self.stream,
+            self.symbol,
+            self.total_updates,
+            self.rejected_updates,
+            self.updates_per_second,
+            self.average_latency_ms,
+            self.max_latency_ms,
+            self.average_delivery_latency_ms,
+            self.max_delivery_latency_ms
+        )
+    }
+}
+
+impl fmt::Display for MailboxStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mailbox {}: {} of {} queued",
+            self.subscriber, self.queued, self.capacity
+        )
+    }
+}
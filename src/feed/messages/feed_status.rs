@@ -0,0 +1,75 @@
+use crate::common_types::instrument_id::InstrumentId;
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedState {
+    Stale,
+    Reconnecting,
+    /// The local book missed exchange updates and is being resynchronised.
+    Invalid,
+    Recovered,
+    /// The subscriber fell behind and missed updates. Later updates are complete again.
+    Gap,
+}
+
+impl fmt::Display for FeedState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedState::Stale => write!(f, "STALE"),
+            FeedState::Reconnecting => write!(f, "RECONNECTING"),
+            FeedState::Invalid => write!(f, "INVALID"),
+            FeedState::Recovered => write!(f, "RECOVERED"),
+            FeedState::Gap => write!(f, "GAP"),
+        }
+    }
+}
+
+/// Which of an instrument's feeds a status describes. They fail independently, so a
+/// recovered L2 book says nothing about a stale L1 feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeedStream {
+    L1,
+    L2,
+    /// The subscriber's own mailbox, for gaps in what it was delivered.
+    Mailbox,
+}
+
+impl fmt::Display for FeedStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedStream::L1 => write!(f, "L1"),
+            FeedStream::L2 => write!(f, "L2"),
+            FeedStream::Mailbox => write!(f, "Mailbox"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedStatus {
+    pub symbol: InstrumentId,
+    pub stream: FeedStream,
+    pub state: FeedState,
+}
+
+impl FeedStatus {
+    pub fn new<Symbol>(symbol: Symbol, stream: FeedStream, state: FeedState) -> Self
+    where
+        Symbol: Into<InstrumentId>,
+    {
+        FeedStatus {
+            symbol: symbol.into(),
+            stream,
+            state,
+        }
+    }
+}
+
+impl fmt::Display for FeedStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "FeedStatus {{ symbol: {}, stream: {}, state: {} }}",
+            self.symbol, self.stream, self.state
+        )
+    }
+}
@@ -0,0 +1,34 @@
+use super::{
+    candle::{Candle, CandleInterval},
+    trade_data::TradeData,
+};
+use crate::common_types::instrument_id::InstrumentId;
+use core::fmt;
+
+/// How much history an algorithm wants before it starts on live data.
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryRequest {
+    pub candle_interval: CandleInterval,
+    pub candle_count: usize,
+    pub trade_count: usize,
+}
+
+/// Recent bars and trades of one instrument, oldest first.
+#[derive(Debug, Clone)]
+pub struct History {
+    pub symbol: InstrumentId,
+    pub candles: Vec<Candle>,
+    pub trades: Vec<TradeData>,
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "History {{ symbol: {}, candles: {}, trades: {} }}",
+            self.symbol,
+            self.candles.len(),
+            self.trades.len()
+        )
+    }
+}
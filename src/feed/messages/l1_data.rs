@@ -1,12 +1,16 @@
 use super::level::Level;
+use super::violation::Violation;
+use crate::common_types::instrument_id::InstrumentId;
 use core::fmt;
 use rust_decimal::Decimal;
 
 #[derive(Debug, Clone)]
 pub struct L1Data {
-    pub symbol: String,
+    pub symbol: InstrumentId,
     pub best_bid_level: Level,
     pub best_ask_level: Level,
+    /// Set when the update failed the sanity checks and was published anyway.
+    pub violation: Option<Violation>,
 }
 
 impl L1Data {
@@ -18,12 +22,13 @@ impl L1Data {
         best_ask_price: Decimal,
     ) -> Self
     where
-        Symbol: Into<String>,
+        Symbol: Into<InstrumentId>,
     {
         L1Data {
             symbol: symbol.into(),
             best_bid_level: Level::new(1, best_bid_quantity, best_bid_price),
             best_ask_level: Level::new(1, best_ask_quantity, best_ask_price),
+            violation: None,
         }
     }
 }
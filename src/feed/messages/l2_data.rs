@@ -1,12 +1,16 @@
 use super::level::Level;
+use super::violation::Violation;
+use crate::common_types::instrument_id::InstrumentId;
 use core::fmt;
 
 #[derive(Debug, Clone)]
 
 pub struct L2Data {
-    pub symbol: String,
+    pub symbol: InstrumentId,
     pub bid_side_levels: Vec<Level>,
     pub ask_side_levels: Vec<Level>,
+    /// Set when the book failed the sanity checks and was published anyway.
+    pub violation: Option<Violation>,
 }
 
 impl L2Data {
@@ -16,12 +20,22 @@ impl L2Data {
         ask_side_levels: Vec<Level>,
     ) -> Self
     where
-        Symbol: Into<String>,
+        Symbol: Into<InstrumentId>,
     {
         L2Data {
             symbol: symbol.into(),
             bid_side_levels,
             ask_side_levels,
+            violation: None,
+        }
+    }
+
+    pub fn truncated(&self, depth: usize) -> L2Data {
+        L2Data {
+            symbol: self.symbol.clone(),
+            bid_side_levels: self.bid_side_levels.iter().take(depth).cloned().collect(),
+            ask_side_levels: self.ask_side_levels.iter().take(depth).cloned().collect(),
+            violation: self.violation.clone(),
         }
     }
 }
@@ -0,0 +1,53 @@
+use super::level::Level;
+use super::violation::Violation;
+use crate::common_types::instrument_id::InstrumentId;
+use core::fmt;
+
+/// Changes to a depth-truncated book since the previous update. A level with zero
+/// quantity has left the book (or the subscriber's depth window).
+#[derive(Debug, Clone)]
+pub struct L2Delta {
+    pub symbol: InstrumentId,
+    pub bid_changes: Vec<Level>,
+    pub ask_changes: Vec<Level>,
+    /// Set when the book failed the sanity checks and was published anyway.
+    pub violation: Option<Violation>,
+}
+
+impl L2Delta {
+    pub fn new<Symbol>(symbol: Symbol, bid_changes: Vec<Level>, ask_changes: Vec<Level>) -> Self
+    where
+        Symbol: Into<InstrumentId>,
+    {
+        L2Delta {
+            symbol: symbol.into(),
+            bid_changes,
+            ask_changes,
+            violation: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bid_changes.is_empty() && self.ask_changes.is_empty()
+    }
+}
+
+impl fmt::Display for L2Delta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "L2Delta {{ symbol: {}, bid_changes: [", self.symbol)?;
+        for (i, level) in self.bid_changes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", level)?;
+        }
+        write!(f, "], ask_changes: [")?;
+        for (i, level) in self.ask_changes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", level)?;
+        }
+        write!(f, "] }}")
+    }
+}
@@ -11,9 +11,9 @@ pub struct Level {
 impl Level {
     pub fn new(level: i32, quantity: Decimal, price: Decimal) -> Self {
         Level {
-            level: level,
-            quantity: quantity,
-            price: price,
+            level,
+            quantity,
+            price,
         }
     }
 }
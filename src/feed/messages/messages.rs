@@ -1,12 +1,51 @@
-use super::{l1_data::L1Data, l2_data::L2Data};
+use super::{
+    analytics::Analytics,
+    candle::{Candle, CandleInterval, CandleSource},
+    consolidated_l1::ConsolidatedL1,
+    feed_snapshot::FeedSnapshot,
+    feed_statistics::{FeedStatistics, MailboxStatistics},
+    feed_status::FeedStatus,
+    history::{History, HistoryRequest},
+    l1_data::L1Data,
+    l2_data::L2Data,
+    l2_delta::L2Delta,
+    ticker::Ticker,
+    trade_data::TradeData,
+};
 use crate::common_types::tracked_sender::TrackedSender;
+use std::sync::Arc;
+use tokio::sync::oneshot;
 type AlgoId = String;
 
+/// Algorithms an update is addressed to. Shared between updates and only rebuilt when a
+/// subscription changes.
+pub type AlgoIds = Arc<[AlgoId]>;
+
+/// Payloads are shared, so fanning an update out to many subscribers never copies a book or
+/// a symbol.
 #[derive(Debug, Clone)]
 pub enum FeedUpdate {
-    L1Update(Vec<AlgoId>, L1Data),
-    L2Update(Vec<AlgoId>, L2Data),
+    L1Update(AlgoIds, Arc<L1Data>),
+    ConsolidatedL1(AlgoIds, Arc<ConsolidatedL1>),
+    L2Update(AlgoIds, Arc<L2Data>),
+    L2Delta(AlgoIds, Arc<L2Delta>),
+    Trade(AlgoIds, Arc<TradeData>),
+    Candle(AlgoIds, Arc<Candle>),
+    Status(AlgoIds, Arc<FeedStatus>),
+    Snapshot(AlgoIds, Arc<FeedSnapshot>),
+    Analytics(AlgoIds, Arc<Analytics>),
+    Ticker(AlgoIds, Arc<Ticker>),
+    History(AlgoIds, Arc<History>),
+}
+
+/// How a subscriber wants to receive L2 updates: the full truncated book on every change,
+/// or a snapshot followed by deltas against the previous update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum L2UpdateMode {
+    Snapshot,
+    Delta,
 }
+
 pub(crate) enum FeedMessages {
     SubscribeToL1 {
         algo_id: String,
@@ -20,10 +59,24 @@ pub(crate) enum FeedMessages {
         quote: String,
         subscriber: TrackedSender<FeedUpdate>,
     },
+    SubscribeToConsolidatedL1 {
+        algo_id: String,
+        base: String,
+        quote: String,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    UnsubscribeFromConsolidatedL1 {
+        algo_id: String,
+        base: String,
+        quote: String,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
     SubscribeToL2 {
         algo_id: String,
         base: String,
         quote: String,
+        depth: usize,
+        mode: L2UpdateMode,
         subscriber: TrackedSender<FeedUpdate>,
     },
     UnsubscribeFromL2 {
@@ -32,4 +85,81 @@ pub(crate) enum FeedMessages {
         quote: String,
         subscriber: TrackedSender<FeedUpdate>,
     },
+    SubscribeToTrades {
+        algo_id: String,
+        base: String,
+        quote: String,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    UnsubscribeFromTrades {
+        algo_id: String,
+        base: String,
+        quote: String,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    SubscribeToCandles {
+        algo_id: String,
+        base: String,
+        quote: String,
+        interval: CandleInterval,
+        source: CandleSource,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    UnsubscribeFromCandles {
+        algo_id: String,
+        base: String,
+        quote: String,
+        interval: CandleInterval,
+        source: CandleSource,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    SubscribeToAnalytics {
+        algo_id: String,
+        base: String,
+        quote: String,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    UnsubscribeFromAnalytics {
+        algo_id: String,
+        base: String,
+        quote: String,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    SubscribeToTicker {
+        algo_id: String,
+        base: String,
+        quote: String,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    UnsubscribeFromTicker {
+        algo_id: String,
+        base: String,
+        quote: String,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    GetTicker {
+        base: String,
+        quote: String,
+        responder: oneshot::Sender<Result<Ticker, String>>,
+    },
+    RequestSnapshot {
+        algo_id: String,
+        base: String,
+        quote: String,
+        depth: usize,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    RequestHistory {
+        algo_id: String,
+        base: String,
+        quote: String,
+        request: HistoryRequest,
+        subscriber: TrackedSender<FeedUpdate>,
+    },
+    GetStatistics {
+        responder: oneshot::Sender<Vec<FeedStatistics>>,
+    },
+    GetMailboxStatistics {
+        responder: oneshot::Sender<Vec<MailboxStatistics>>,
+    },
 }
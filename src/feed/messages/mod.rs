@@ -1,5 +1,17 @@
+pub mod analytics;
+pub mod candle;
+pub mod consolidated_l1;
+pub mod feed_snapshot;
+pub mod feed_statistics;
+pub mod feed_status;
+pub mod history;
 pub mod l1_data;
 pub mod l2_data;
+pub mod l2_delta;
 pub mod level;
+#[allow(clippy::module_inception)]
 pub mod messages;
 pub mod symbol_information;
+pub mod ticker;
+pub mod trade_data;
+pub mod violation;
@@ -1,5 +1,7 @@
+use crate::common_types::symbol_status::SymbolStatus;
 use rust_decimal::Decimal;
 
+#[derive(Default)]
 pub struct SymbolInformation {
     pub min_quantity: Option<Decimal>,
     pub max_quantity: Option<Decimal>,
@@ -8,21 +10,15 @@ pub struct SymbolInformation {
     pub max_price: Option<Decimal>,
     pub tick_size: Option<Decimal>,
     pub min_amount: Option<Decimal>,
+    pub status: Option<SymbolStatus>,
 }
 
 impl SymbolInformation {
     pub fn new() -> Self {
-        SymbolInformation {
-            min_quantity: None,
-            max_quantity: None,
-            lot_size: None,
-            min_price: None,
-            max_price: None,
-            tick_size: None,
-            min_amount: None,
-        }
+        Self::default()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn set_values(
         &mut self,
         min_quantity: Option<Decimal>,
@@ -55,4 +51,16 @@ impl SymbolInformation {
             self.min_amount = Some(value);
         }
     }
+
+    pub fn set_status(&mut self, status: Option<SymbolStatus>) {
+        if let Some(value) = status {
+            self.status = Some(value);
+        }
+    }
+
+    pub fn is_trading(&self) -> bool {
+        self.status
+            .as_ref()
+            .is_none_or(|status| status.is_trading())
+    }
 }
@@ -0,0 +1,46 @@
+use crate::common_types::instrument_id::InstrumentId;
+use chrono::{DateTime, Utc};
+use core::fmt;
+use rust_decimal::Decimal;
+
+/// Rolling 24 hour statistics of one instrument.
+#[derive(Debug, Clone)]
+pub struct Ticker {
+    pub symbol: InstrumentId,
+    pub last_price: Decimal,
+    pub open_price: Decimal,
+    pub high_price: Decimal,
+    pub low_price: Decimal,
+    /// Traded base quantity.
+    pub volume: Decimal,
+    pub price_change_pct: Decimal,
+    pub close_time: DateTime<Utc>,
+}
+
+impl Ticker {
+    /// How far `price` is from the last price, in percent of the last price.
+    pub fn distance_pct(&self, price: Decimal) -> Option<Decimal> {
+        if self.last_price.is_zero() {
+            return None;
+        }
+
+        Some(((price - self.last_price) / self.last_price).abs() * Decimal::ONE_HUNDRED)
+    }
+}
+
+impl fmt::Display for Ticker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Ticker {{ symbol: {}, last_price: {}, open_price: {}, high_price: {}, low_price: {}, volume: {}, price_change_pct: {}, close_time: {} }}",
+            self.symbol,
+            self.last_price,
+            self.open_price,
+            self.high_price,
+            self.low_price,
+            self.volume,
+            self.price_change_pct,
+            self.close_time
+        )
+    }
+}
@@ -0,0 +1,49 @@
+use super::violation::Violation;
+use crate::common_types::instrument_id::InstrumentId;
+use crate::common_types::side::Side;
+use chrono::{DateTime, Utc};
+use core::fmt;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone)]
+pub struct TradeData {
+    pub symbol: InstrumentId,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub aggressor_side: Side,
+    pub trade_time: DateTime<Utc>,
+    /// Set when the trade failed the sanity checks and was published anyway.
+    pub violation: Option<Violation>,
+}
+
+impl TradeData {
+    pub fn new<Symbol>(
+        symbol: Symbol,
+        price: Decimal,
+        quantity: Decimal,
+        aggressor_side: Side,
+        trade_time: DateTime<Utc>,
+    ) -> Self
+    where
+        Symbol: Into<InstrumentId>,
+    {
+        TradeData {
+            symbol: symbol.into(),
+            price,
+            quantity,
+            aggressor_side,
+            trade_time,
+            violation: None,
+        }
+    }
+}
+
+impl fmt::Display for TradeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TradeData {{ symbol: {}, price: {:.2}, quantity: {:.6}, aggressor_side: {}, trade_time: {} }}",
+            self.symbol, self.price, self.quantity, self.aggressor_side, self.trade_time
+        )
+    }
+}
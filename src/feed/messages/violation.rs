@@ -0,0 +1,25 @@
+use core::fmt;
+use rust_decimal::Decimal;
+
+/// Why an update failed the feed's sanity checks. Updates published with
+/// `action = "flag"` carry it so algorithms can tell them apart.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    ZeroPrice,
+    CrossedBook { bid: Decimal, ask: Decimal },
+    PriceJump { previous: Decimal, current: Decimal },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::ZeroPrice => write!(f, "zero price"),
+            Violation::CrossedBook { bid, ask } => {
+                write!(f, "crossed book, bid {} >= ask {}", bid, ask)
+            }
+            Violation::PriceJump { previous, current } => {
+                write!(f, "price jumped from {} to {}", previous, current)
+            }
+        }
+    }
+}
@@ -1,5 +1,22 @@
+mod analytics_engine;
+mod candle_builder;
+mod conflation;
+mod consolidated_book;
+mod endpoint_server;
 pub mod feed_actor;
 pub mod feed_handle;
 pub mod feed_service;
+mod feed_stats;
+mod history_loader;
 pub mod messages;
+mod order_book;
+mod recorder;
+mod replay;
+mod sanity;
+mod sequence;
+mod shared_map;
+mod simulated_feed;
+mod staleness;
+mod synthetic_book;
+mod ticker_builder;
 pub(crate) use messages::messages::FeedMessages;
@@ -0,0 +1,168 @@
+use super::messages::{l2_data::L2Data, l2_delta::L2Delta, level::Level};
+use crate::common_types::instrument_id::InstrumentId;
+use rust_decimal::{prelude::Zero, Decimal};
+use std::collections::{BTreeMap, HashMap};
+
+/// Local price ladder for one instrument, kept in sync from exchange snapshots and
+/// incremental updates.
+pub(super) struct OrderBook {
+    symbol: InstrumentId,
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+}
+
+impl OrderBook {
+    pub fn new<Symbol>(symbol: Symbol) -> Self
+    where
+        Symbol: Into<InstrumentId>,
+    {
+        Self {
+            symbol: symbol.into(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+        }
+    }
+
+    pub fn apply_snapshot<Bids, Asks>(&mut self, bids: Bids, asks: Asks)
+    where
+        Bids: IntoIterator<Item = (Decimal, Decimal)>,
+        Asks: IntoIterator<Item = (Decimal, Decimal)>,
+    {
+        self.bids.clear();
+        self.asks.clear();
+        self.apply_update(bids, asks);
+    }
+
+    pub fn apply_l2_data(&mut self, l2_data: &L2Data) {
+        self.apply_snapshot(
+            l2_data
+                .bid_side_levels
+                .iter()
+                .map(|level| (level.price, level.quantity)),
+            l2_data
+                .ask_side_levels
+                .iter()
+                .map(|level| (level.price, level.quantity)),
+        );
+    }
+
+    pub fn apply_update<Bids, Asks>(&mut self, bids: Bids, asks: Asks)
+    where
+        Bids: IntoIterator<Item = (Decimal, Decimal)>,
+        Asks: IntoIterator<Item = (Decimal, Decimal)>,
+    {
+        Self::apply_levels(&mut self.bids, bids);
+        Self::apply_levels(&mut self.asks, asks);
+    }
+
+    fn apply_levels<Levels>(side: &mut BTreeMap<Decimal, Decimal>, levels: Levels)
+    where
+        Levels: IntoIterator<Item = (Decimal, Decimal)>,
+    {
+        for (price, quantity) in levels {
+            if quantity.is_zero() {
+                side.remove(&price);
+            } else {
+                side.insert(price, quantity);
+            }
+        }
+    }
+
+    pub fn bids(&self, depth: usize) -> Vec<Level> {
+        Self::to_levels(self.bids.iter().rev(), depth)
+    }
+
+    pub fn asks(&self, depth: usize) -> Vec<Level> {
+        Self::to_levels(self.asks.iter(), depth)
+    }
+
+    fn to_levels<'a, Levels>(levels: Levels, depth: usize) -> Vec<Level>
+    where
+        Levels: Iterator<Item = (&'a Decimal, &'a Decimal)>,
+    {
+        levels
+            .take(depth)
+            .enumerate()
+            .map(|(i, (price, quantity))| Level::new(i as i32 + 1, *quantity, *price))
+            .collect()
+    }
+
+    pub fn snapshot(&self, depth: usize) -> L2Data {
+        L2Data::new(self.symbol.clone(), self.bids(depth), self.asks(depth))
+    }
+}
+
+/// Computes the changes that turn `previous` into `current`, both truncated to the
+/// same depth.
+pub(super) fn diff(previous: &L2Data, current: &L2Data) -> L2Delta {
+    let mut delta = L2Delta::new(
+        current.symbol.clone(),
+        diff_levels(&previous.bid_side_levels, &current.bid_side_levels),
+        diff_levels(&previous.ask_side_levels, &current.ask_side_levels),
+    );
+    delta.violation = current.violation.clone();
+    delta
+}
+
+fn diff_levels(previous: &[Level], current: &[Level]) -> Vec<Level> {
+    let previous_quantities: HashMap<Decimal, Decimal> = previous
+        .iter()
+        .map(|level| (level.price, level.quantity))
+        .collect();
+    let current_quantities: HashMap<Decimal, Decimal> = current
+        .iter()
+        .map(|level| (level.price, level.quantity))
+        .collect();
+
+    let mut changes: Vec<Level> = current
+        .iter()
+        .filter(|level| previous_quantities.get(&level.price) != Some(&level.quantity))
+        .cloned()
+        .collect();
+
+    changes.extend(
+        previous
+            .iter()
+            .filter(|level| !current_quantities.contains_key(&level.price))
+            .map(|level| Level::new(0, Decimal::zero(), level.price)),
+    );
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(price: i64, quantity: i64) -> (Decimal, Decimal) {
+        (Decimal::from(price), Decimal::from(quantity))
+    }
+
+    #[test]
+    fn zero_quantity_deletes_the_level() {
+        let mut book = OrderBook::new("BTCUSDT");
+        book.apply_snapshot(
+            [level(100, 1), level(99, 2)],
+            [level(101, 1), level(102, 3)],
+        );
+
+        book.apply_update(
+            [level(100, 0), level(98, 4)],
+            [level(101, 0), level(105, 0)],
+        );
+
+        let bids: Vec<_> = book
+            .bids(10)
+            .iter()
+            .map(|l| (l.price, l.quantity))
+            .collect();
+        let asks: Vec<_> = book
+            .asks(10)
+            .iter()
+            .map(|l| (l.price, l.quantity))
+            .collect();
+        assert_eq!(bids, vec![level(99, 2), level(98, 4)]);
+        assert_eq!(asks, vec![level(102, 3)]);
+        assert_eq!(book.bids(10)[0].level, 1);
+    }
+}
@@ -0,0 +1,509 @@
+use super::messages::{
+    consolidated_l1::{ConsolidatedL1, VenueL1},
+    l1_data::L1Data,
+    l2_data::L2Data,
+    l2_delta::L2Delta,
+    level::Level,
+    trade_data::TradeData,
+};
+use super::order_book;
+use crate::common_types::side::Side;
+use crate::common_types::venue::Venue;
+use crate::config::RecorderConfig;
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, error::TryRecvError};
+use tokio::task;
+
+const RECORDER_CHANNEL_SIZE: usize = 10_000;
+
+/// The feed records whole books at the depth it caches; the writer stores each as the changes
+/// to the previous one, starting every file with a full book so a day replays on its own.
+pub(super) enum RecordedUpdate {
+    L1(L1Data),
+    ConsolidatedL1(ConsolidatedL1),
+    L2(Arc<L2Data>),
+    L2Delta(L2Delta),
+    Trade(TradeData),
+}
+
+impl RecordedUpdate {
+    fn symbol(&self) -> &str {
+        match self {
+            RecordedUpdate::L1(l1_data) => &l1_data.symbol,
+            RecordedUpdate::ConsolidatedL1(consolidated_l1) => &consolidated_l1.symbol,
+            RecordedUpdate::L2(l2_data) => &l2_data.symbol,
+            RecordedUpdate::L2Delta(l2_delta) => &l2_delta.symbol,
+            RecordedUpdate::Trade(trade_data) => &trade_data.symbol,
+        }
+    }
+}
+
+pub(super) struct RecordedEvent {
+    pub receive_time: DateTime<Utc>,
+    pub exchange_time: DateTime<Utc>,
+    pub update: RecordedUpdate,
+}
+
+/// Writes distributed feed updates to `<directory>/<instrument>/<yyyy-mm-dd>.csv`, one line
+/// per update. Recording never blocks the feed: updates are dropped if the writer falls behind.
+#[derive(Clone)]
+pub(super) struct Recorder {
+    sender: mpsc::Sender<RecordedEvent>,
+}
+
+impl Recorder {
+    pub fn start(config: &RecorderConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let (sender, receiver) = mpsc::channel(RECORDER_CHANNEL_SIZE);
+        let directory = PathBuf::from(&config.directory);
+
+        eprintln!("Recording market data to {}.", directory.display());
+        task::spawn_blocking(move || run_writer(directory, receiver));
+
+        Some(Self { sender })
+    }
+
+    pub fn record(&self, exchange_time: DateTime<Utc>, update: RecordedUpdate) {
+        let event = RecordedEvent {
+            receive_time: Utc::now(),
+            exchange_time,
+            update,
+        };
+
+        self.sender
+            .try_send(event)
+            .unwrap_or_else(|err| eprintln!("Failed to record market data: {}", err));
+    }
+}
+
+#[derive(Default)]
+struct Files {
+    writers: HashMap<(String, String), BufWriter<File>>,
+    /// The last book written to each instrument's open file.
+    books: HashMap<String, Arc<L2Data>>,
+}
+
+fn run_writer(directory: PathBuf, mut receiver: mpsc::Receiver<RecordedEvent>) {
+    let mut files = Files::default();
+
+    while let Some(event) = receiver.blocking_recv() {
+        write_event(&directory, &mut files, event);
+
+        loop {
+            match receiver.try_recv() {
+                Ok(event) => write_event(&directory, &mut files, event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return flush_all(&mut files),
+            }
+        }
+
+        flush_all(&mut files);
+    }
+}
+
+fn write_event(directory: &Path, files: &mut Files, mut event: RecordedEvent) {
+    let symbol = event.update.symbol().to_owned();
+    let day = event.receive_time.format("%Y-%m-%d").to_string();
+
+    // Only one day per instrument stays open; the previous one is closed on rollover.
+    files
+        .writers
+        .retain(|(file_symbol, file_day), _| file_symbol != &symbol || file_day == &day);
+
+    let key = (symbol, day);
+    if !files.writers.contains_key(&key) {
+        files.books.remove(&key.0);
+
+        let instrument_directory = directory.join(&key.0);
+        let file = fs::create_dir_all(&instrument_directory).and_then(|_| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(instrument_directory.join(format!("{}.csv", key.1)))
+        });
+
+        match file {
+            Ok(file) => {
+                files.writers.insert(key.clone(), BufWriter::new(file));
+            }
+            Err(error) => {
+                eprintln!("Failed to open recording for {}: {}", key.0, error);
+                return;
+            }
+        }
+    }
+
+    if let RecordedUpdate::L2(l2_data) = &event.update {
+        let l2_data = l2_data.clone();
+        if let Some(previous) = files.books.insert(key.0.clone(), l2_data.clone()) {
+            event.update = RecordedUpdate::L2Delta(order_book::diff(&previous, &l2_data));
+        }
+    }
+
+    if let Some(writer) = files.writers.get_mut(&key) {
+        writeln!(writer, "{}", encode_event(&event))
+            .unwrap_or_else(|err| eprintln!("Failed to write recording: {}", err));
+    }
+}
+
+fn flush_all(files: &mut Files) {
+    for writer in files.writers.values_mut() {
+        writer
+            .flush()
+            .unwrap_or_else(|err| eprintln!("Failed to flush recording: {}", err));
+    }
+}
+
+fn encode_levels(levels: &[Level]) -> String {
+    levels
+        .iter()
+        .map(|level| format!("{}:{}", level.price, level.quantity))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn encode_venues(venues: &[VenueL1]) -> String {
+    venues
+        .iter()
+        .map(|venue_l1| {
+            format!(
+                "{}:{}:{}:{}:{}",
+                venue_l1.venue,
+                venue_l1.l1_data.best_bid_level.quantity,
+                venue_l1.l1_data.best_bid_level.price,
+                venue_l1.l1_data.best_ask_level.quantity,
+                venue_l1.l1_data.best_ask_level.price
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Line format, times in microseconds since the epoch:
+/// `L1,receive,exchange,bid_qty,bid_px,ask_qty,ask_px`
+/// `C,receive,exchange,bid_qty,bid_px,ask_qty,ask_px,bid_venue,ask_venue,venue:bid_qty:bid_px:ask_qty:ask_px|...`
+/// `L2,receive,exchange,px:qty|px:qty,px:qty|px:qty` for a full book
+/// `D,receive,exchange,px:qty|px:qty,px:qty|px:qty` for changed levels, quantity 0 when gone
+/// `T,receive,exchange,price,quantity,side`
+pub(super) fn encode_event(event: &RecordedEvent) -> String {
+    let receive_time = event.receive_time.timestamp_micros();
+    let exchange_time = event.exchange_time.timestamp_micros();
+
+    match &event.update {
+        RecordedUpdate::L1(l1_data) => format!(
+            "L1,{},{},{},{},{},{}",
+            receive_time,
+            exchange_time,
+            l1_data.best_bid_level.quantity,
+            l1_data.best_bid_level.price,
+            l1_data.best_ask_level.quantity,
+            l1_data.best_ask_level.price
+        ),
+        RecordedUpdate::ConsolidatedL1(consolidated_l1) => format!(
+            "C,{},{},{},{},{},{},{},{},{}",
+            receive_time,
+            exchange_time,
+            consolidated_l1.consolidated.best_bid_level.quantity,
+            consolidated_l1.consolidated.best_bid_level.price,
+            consolidated_l1.consolidated.best_ask_level.quantity,
+            consolidated_l1.consolidated.best_ask_level.price,
+            consolidated_l1.best_bid_venue,
+            consolidated_l1.best_ask_venue,
+            encode_venues(&consolidated_l1.venues)
+        ),
+        RecordedUpdate::L2Delta(l2_delta) => format!(
+            "D,{},{},{},{}",
+            receive_time,
+            exchange_time,
+            encode_levels(&l2_delta.bid_changes),
+            encode_levels(&l2_delta.ask_changes)
+        ),
+        RecordedUpdate::L2(l2_data) => format!(
+            "L2,{},{},{},{}",
+            receive_time,
+            exchange_time,
+            encode_levels(&l2_data.bid_side_levels),
+            encode_levels(&l2_data.ask_side_levels)
+        ),
+        RecordedUpdate::Trade(trade_data) => format!(
+            "T,{},{},{},{},{}",
+            receive_time,
+            exchange_time,
+            trade_data.price,
+            trade_data.quantity,
+            trade_data.aggressor_side
+        ),
+    }
+}
+
+fn decode_levels(levels: &str) -> Option<Vec<Level>> {
+    if levels.is_empty() {
+        return Some(Vec::new());
+    }
+
+    levels
+        .split('|')
+        .enumerate()
+        .map(|(i, level)| {
+            let (price, quantity) = level.split_once(':')?;
+            Some(Level::new(
+                i as i32 + 1,
+                Decimal::from_str(quantity).ok()?,
+                Decimal::from_str(price).ok()?,
+            ))
+        })
+        .collect()
+}
+
+fn decode_venues(symbol: &str, venues: &str) -> Option<Vec<VenueL1>> {
+    if venues.is_empty() {
+        return Some(Vec::new());
+    }
+
+    venues
+        .split('|')
+        .map(
+            |venue_l1| match venue_l1.split(':').collect::<Vec<_>>().as_slice() {
+                [venue, bid_quantity, bid_price, ask_quantity, ask_price] => Some(VenueL1 {
+                    venue: Venue::from_str(venue).ok()?,
+                    l1_data: decode_l1(symbol, bid_quantity, bid_price, ask_quantity, ask_price)?,
+                }),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+fn decode_l1(
+    symbol: &str,
+    bid_quantity: &str,
+    bid_price: &str,
+    ask_quantity: &str,
+    ask_price: &str,
+) -> Option<L1Data> {
+    Some(L1Data::new(
+        symbol,
+        Decimal::from_str(bid_quantity).ok()?,
+        Decimal::from_str(bid_price).ok()?,
+        Decimal::from_str(ask_quantity).ok()?,
+        Decimal::from_str(ask_price).ok()?,
+    ))
+}
+
+fn decode_time(micros: &str) -> Option<DateTime<Utc>> {
+    Utc.timestamp_micros(micros.parse().ok()?).single()
+}
+
+/// Parses a line written by `encode_event` for the given instrument.
+pub(super) fn decode_event(symbol: &str, line: &str) -> Option<RecordedEvent> {
+    let fields: Vec<&str> = line.trim().split(',').collect();
+    let (kind, receive_time, exchange_time) = match fields.as_slice() {
+        [kind, receive_time, exchange_time, ..] => (
+            *kind,
+            decode_time(receive_time)?,
+            decode_time(exchange_time)?,
+        ),
+        _ => return None,
+    };
+
+    let update = match (kind, &fields[3..]) {
+        ("L1", [bid_quantity, bid_price, ask_quantity, ask_price]) => RecordedUpdate::L1(
+            decode_l1(symbol, bid_quantity, bid_price, ask_quantity, ask_price)?,
+        ),
+        (
+            "C",
+            [bid_quantity, bid_price, ask_quantity, ask_price, best_bid_venue, best_ask_venue, venues],
+        ) => RecordedUpdate::ConsolidatedL1(ConsolidatedL1 {
+            symbol: symbol.into(),
+            consolidated: decode_l1(symbol, bid_quantity, bid_price, ask_quantity, ask_price)?,
+            best_bid_venue: Venue::from_str(best_bid_venue).ok()?,
+            best_ask_venue: Venue::from_str(best_ask_venue).ok()?,
+            venues: decode_venues(symbol, venues)?,
+        }),
+        ("L2", [bids, asks]) => RecordedUpdate::L2(Arc::new(L2Data::new(
+            symbol,
+            decode_levels(bids)?,
+            decode_levels(asks)?,
+        ))),
+        ("D", [bids, asks]) => RecordedUpdate::L2Delta(L2Delta::new(
+            symbol,
+            decode_levels(bids)?,
+            decode_levels(asks)?,
+        )),
+        ("T", [price, quantity, side]) => RecordedUpdate::Trade(TradeData::new(
+            symbol,
+            Decimal::from_str(price).ok()?,
+            Decimal::from_str(quantity).ok()?,
+            Side::from_str(side)?,
+            exchange_time,
+        )),
+        _ => return None,
+    };
+
+    Some(RecordedEvent {
+        receive_time,
+        exchange_time,
+        update,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(update: RecordedUpdate) -> RecordedEvent {
+        RecordedEvent {
+            receive_time: Utc.timestamp_micros(1_700_000_000_123_456).unwrap(),
+            exchange_time: Utc.timestamp_micros(1_700_000_000_100_000).unwrap(),
+            update,
+        }
+    }
+
+    fn round_trip(update: RecordedUpdate) -> RecordedEvent {
+        let event = event(update);
+        let line = encode_event(&event);
+        let decoded = decode_event("btcusdt", &line).unwrap();
+
+        assert_eq!(decoded.receive_time, event.receive_time);
+        assert_eq!(decoded.exchange_time, event.exchange_time);
+        assert_eq!(encode_event(&decoded), line);
+        decoded
+    }
+
+    fn levels(levels: &[(i64, i64)]) -> Vec<Level> {
+        levels
+            .iter()
+            .enumerate()
+            .map(|(i, (price, quantity))| {
+                Level::new(
+                    i as i32 + 1,
+                    Decimal::from(*quantity),
+                    Decimal::from(*price),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn l1_and_trades_round_trip() {
+        let l1_data = L1Data::new(
+            "btcusdt",
+            Decimal::new(15, 1),
+            Decimal::from(100),
+            Decimal::ONE,
+            Decimal::from(101),
+        );
+        match round_trip(RecordedUpdate::L1(l1_data)).update {
+            RecordedUpdate::L1(l1_data) => {
+                assert_eq!(l1_data.best_bid_level.quantity, Decimal::new(15, 1));
+                assert_eq!(l1_data.best_ask_level.price, Decimal::from(101));
+            }
+            _ => panic!("expected an L1 update"),
+        }
+
+        let trade = TradeData::new(
+            "btcusdt",
+            Decimal::from(100),
+            Decimal::ONE,
+            Side::Sell,
+            Utc.timestamp_micros(1_700_000_000_100_000).unwrap(),
+        );
+        match round_trip(RecordedUpdate::Trade(trade)).update {
+            RecordedUpdate::Trade(trade) => assert!(matches!(trade.aggressor_side, Side::Sell)),
+            _ => panic!("expected a trade"),
+        }
+    }
+
+    #[test]
+    fn consolidated_l1_round_trips() {
+        let l1 = |bid_price: i64| {
+            L1Data::new(
+                "btcusdt",
+                Decimal::ONE,
+                Decimal::from(bid_price),
+                Decimal::ONE,
+                Decimal::from(bid_price + 2),
+            )
+        };
+        let consolidated_l1 = ConsolidatedL1 {
+            symbol: "btcusdt".into(),
+            consolidated: l1(101),
+            best_bid_venue: Venue::Kraken,
+            best_ask_venue: Venue::BinanceSpot,
+            venues: vec![
+                VenueL1 {
+                    venue: Venue::BinanceSpot,
+                    l1_data: l1(100),
+                },
+                VenueL1 {
+                    venue: Venue::Kraken,
+                    l1_data: l1(101),
+                },
+            ],
+        };
+
+        match round_trip(RecordedUpdate::ConsolidatedL1(consolidated_l1)).update {
+            RecordedUpdate::ConsolidatedL1(consolidated_l1) => {
+                assert_eq!(consolidated_l1.best_bid_venue, Venue::Kraken);
+                assert_eq!(consolidated_l1.venues.len(), 2);
+            }
+            _ => panic!("expected a consolidated L1 update"),
+        }
+    }
+
+    #[test]
+    fn books_and_book_changes_round_trip() {
+        let l2_data = L2Data::new("btcusdt", levels(&[(100, 2), (99, 1)]), levels(&[(101, 3)]));
+        match round_trip(RecordedUpdate::L2(Arc::new(l2_data))).update {
+            RecordedUpdate::L2(l2_data) => assert_eq!(l2_data.bid_side_levels.len(), 2),
+            _ => panic!("expected a book"),
+        }
+
+        let l2_delta = L2Delta::new("btcusdt", levels(&[(99, 0)]), Vec::new());
+        match round_trip(RecordedUpdate::L2Delta(l2_delta)).update {
+            RecordedUpdate::L2Delta(l2_delta) => {
+                assert!(l2_delta.bid_changes[0].quantity.is_zero());
+                assert!(l2_delta.ask_changes.is_empty());
+            }
+            _ => panic!("expected book changes"),
+        }
+    }
+
+    #[test]
+    fn files_start_with_a_book_followed_by_its_changes() {
+        let directory = std::env::temp_dir().join(format!("recorder-{}", std::process::id()));
+        let mut files = Files::default();
+
+        let book = |bids| RecordedUpdate::L2(Arc::new(L2Data::new("btcusdt", bids, Vec::new())));
+        write_event(
+            &directory,
+            &mut files,
+            event(book(levels(&[(100, 2), (99, 1)]))),
+        );
+        write_event(&directory, &mut files, event(book(levels(&[(100, 3)]))));
+        flush_all(&mut files);
+
+        let contents = fs::read_to_string(directory.join("btcusdt").join("2023-11-14.csv"));
+        fs::remove_dir_all(&directory).unwrap();
+
+        let lines: Vec<String> = contents
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                format!("{} {}", fields[0], fields[3])
+            })
+            .collect();
+        assert_eq!(lines, ["L2 100:2|99:1", "D 100:3|99:0"]);
+    }
+}
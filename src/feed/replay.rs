@@ -0,0 +1,212 @@
+use super::recorder::{self, RecordedEvent};
+use crate::config::{ReplayConfig, ReplayMode};
+use chrono::{DateTime, Utc};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task;
+use tokio::time::Instant;
+
+const REPLAY_CHANNEL_SIZE: usize = 1000;
+
+/// Streams the recorded events of one instrument and day, paced according to the replay mode
+/// using the recorded local receive times.
+pub(super) struct ReplayReader {
+    receiver: mpsc::Receiver<RecordedEvent>,
+    mode: ReplayMode,
+    speed_multiplier: f64,
+    start: Option<(Instant, DateTime<Utc>)>,
+}
+
+impl ReplayReader {
+    pub fn open(config: &ReplayConfig, instrument: &str) -> Self {
+        let path = PathBuf::from(&config.directory)
+            .join(instrument)
+            .join(format!("{}.csv", config.date));
+        let instrument = instrument.to_owned();
+        let (sender, receiver) = mpsc::channel(REPLAY_CHANNEL_SIZE);
+
+        task::spawn_blocking(move || {
+            let file = match File::open(&path) {
+                Ok(file) => file,
+                Err(error) => {
+                    eprintln!("Failed to open replay file {}: {}", path.display(), error);
+                    return;
+                }
+            };
+
+            for line in BufReader::new(file).lines() {
+                let Ok(line) = line else {
+                    eprintln!("Failed to read replay file {}.", path.display());
+                    return;
+                };
+
+                match recorder::decode_event(&instrument, &line) {
+                    Some(event) => {
+                        if sender.blocking_send(event).is_err() {
+                            return;
+                        }
+                    }
+                    None => eprintln!("Skipping malformed replay line: {}", line),
+                }
+            }
+
+            eprintln!("Finished replaying {}.", path.display());
+        });
+
+        Self {
+            receiver,
+            mode: config.mode.clone(),
+            speed_multiplier: config.speed_multiplier.max(f64::EPSILON),
+            start: None,
+        }
+    }
+
+    /// Waits until the next recorded event is due and returns it.
+    pub async fn next(&mut self) -> Option<RecordedEvent> {
+        let event = self.receiver.recv().await?;
+
+        let speed_multiplier = match self.mode {
+            ReplayMode::RealTime => 1.0,
+            ReplayMode::Accelerated => self.speed_multiplier,
+            ReplayMode::AsFastAsPossible => {
+                task::yield_now().await;
+                return Some(event);
+            }
+        };
+
+        let Some((started_at, first_receive_time)) = self.start else {
+            self.start = Some((Instant::now(), event.receive_time));
+            return Some(event);
+        };
+
+        let recorded_elapsed = (event.receive_time - first_receive_time)
+            .to_std()
+            .unwrap_or_default();
+        let due =
+            started_at + Duration::from_secs_f64(recorded_elapsed.as_secs_f64() / speed_multiplier);
+        tokio::time::sleep_until(due).await;
+
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::side::Side;
+    use crate::feed::messages::trade_data::TradeData;
+    use crate::feed::recorder::RecordedUpdate;
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+    use std::fs;
+
+    const DATE: &str = "2023-11-14";
+
+    /// Records trades received at the given offsets in seconds, with a malformed line after
+    /// the first, and returns a config replaying them.
+    fn recording(name: &str, offsets: &[i64], mode: ReplayMode) -> ReplayConfig {
+        let directory =
+            std::env::temp_dir().join(format!("replay_{}_{}", name, std::process::id()));
+        let instrument_directory = directory.join("btcusdt");
+        fs::create_dir_all(&instrument_directory).unwrap();
+
+        let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let mut lines: Vec<String> = offsets
+            .iter()
+            .map(|offset| {
+                let receive_time = start + chrono::Duration::seconds(*offset);
+                let trade = TradeData::new(
+                    "btcusdt",
+                    Decimal::from(100 + offset),
+                    Decimal::ONE,
+                    Side::Buy,
+                    receive_time,
+                );
+                recorder::encode_event(&RecordedEvent {
+                    receive_time,
+                    exchange_time: receive_time,
+                    update: RecordedUpdate::Trade(trade),
+                })
+            })
+            .collect();
+        lines.insert(1, "not,a,recorded,event".to_owned());
+        fs::write(
+            instrument_directory.join(format!("{}.csv", DATE)),
+            lines.join("\n") + "\n",
+        )
+        .unwrap();
+
+        ReplayConfig {
+            directory: directory.to_string_lossy().into_owned(),
+            date: DATE.to_owned(),
+            mode,
+            speed_multiplier: 10.0,
+        }
+    }
+
+    /// Replays the recording and returns each event's price and delay after the first.
+    async fn replay(config: &ReplayConfig) -> Vec<(Decimal, Duration)> {
+        let mut reader = ReplayReader::open(config, "btcusdt");
+        let mut events = Vec::new();
+        let mut started_at = None;
+
+        while let Some(event) = reader.next().await {
+            let started_at = *started_at.get_or_insert_with(Instant::now);
+            let RecordedUpdate::Trade(trade) = event.update else {
+                panic!("expected a trade");
+            };
+            events.push((trade.price, started_at.elapsed()));
+        }
+
+        fs::remove_dir_all(&config.directory).unwrap();
+        events
+    }
+
+    fn at(price: i64, millis: u64) -> (Decimal, Duration) {
+        (Decimal::from(price), Duration::from_millis(millis))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn real_time_keeps_the_recorded_gaps() {
+        let config = recording("real_time", &[0, 1, 3], ReplayMode::RealTime);
+
+        assert_eq!(
+            replay(&config).await,
+            vec![at(100, 0), at(101, 1000), at(103, 3000)]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn accelerated_divides_the_gaps_by_the_speed_multiplier() {
+        let config = recording("accelerated", &[0, 1, 3], ReplayMode::Accelerated);
+
+        assert_eq!(
+            replay(&config).await,
+            vec![at(100, 0), at(101, 100), at(103, 300)]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn as_fast_as_possible_does_not_wait() {
+        let config = recording(
+            "as_fast_as_possible",
+            &[0, 1, 3],
+            ReplayMode::AsFastAsPossible,
+        );
+
+        assert_eq!(
+            replay(&config).await,
+            vec![at(100, 0), at(101, 0), at(103, 0)]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn malformed_lines_are_skipped() {
+        let config = recording("malformed", &[0, 2], ReplayMode::RealTime);
+
+        assert_eq!(replay(&config).await, vec![at(100, 0), at(102, 2000)]);
+    }
+}
@@ -0,0 +1,189 @@
+use super::messages::l1_data::L1Data;
+use super::messages::violation::Violation;
+use super::order_book::OrderBook;
+use super::shared_map::SharedMap;
+use crate::config::{SanityAction, SanityConfig};
+use arc_swap::ArcSwapOption;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+type References = SharedMap<&'static str, SharedMap<String, ArcSwapOption<Reference>>>;
+
+struct Reference {
+    price: Decimal,
+    consecutive_outliers: u32,
+}
+
+/// Validates updates before they are published. Jumps are measured against the last accepted
+/// price of the same stream; after `max_consecutive_outliers` jumps in a row the new level is
+/// accepted, so a genuine move does not block the feed.
+#[derive(Clone)]
+pub(super) struct SanityFilter {
+    config: SanityConfig,
+    references: Arc<References>,
+}
+
+impl SanityFilter {
+    pub fn new(config: SanityConfig) -> Self {
+        Self {
+            config,
+            references: Arc::default(),
+        }
+    }
+
+    pub fn drops(&self) -> bool {
+        self.config.action == SanityAction::Drop
+    }
+
+    pub fn check_l1(&self, l1_data: &L1Data) -> Option<Violation> {
+        self.check_quote(
+            "L1",
+            &l1_data.symbol,
+            l1_data.best_bid_level.price,
+            l1_data.best_ask_level.price,
+        )
+    }
+
+    /// Checks the top of the local book; a side with no levels is not an error.
+    pub fn check_l2(&self, instrument: &str, order_book: &OrderBook) -> Option<Violation> {
+        let (Some(bid), Some(ask)) = (
+            order_book.bids(1).first().map(|level| level.price),
+            order_book.asks(1).first().map(|level| level.price),
+        ) else {
+            return None;
+        };
+
+        self.check_quote("L2", instrument, bid, ask)
+    }
+
+    pub fn check_trade(&self, symbol: &str, price: Decimal) -> Option<Violation> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        if self.config.reject_zero_prices && price.is_zero() {
+            return Some(Violation::ZeroPrice);
+        }
+
+        self.check_jump("Trades", symbol, price)
+    }
+
+    fn check_quote(
+        &self,
+        stream: &'static str,
+        symbol: &str,
+        bid: Decimal,
+        ask: Decimal,
+    ) -> Option<Violation> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        if self.config.reject_zero_prices && (bid.is_zero() || ask.is_zero()) {
+            return Some(Violation::ZeroPrice);
+        }
+
+        if self.config.reject_crossed_books && bid >= ask {
+            return Some(Violation::CrossedBook { bid, ask });
+        }
+
+        self.check_jump(stream, symbol, (bid + ask) / Decimal::TWO)
+    }
+
+    fn check_jump(&self, stream: &'static str, symbol: &str, price: Decimal) -> Option<Violation> {
+        if self.config.max_price_change_pct.is_zero() || price.is_zero() {
+            return None;
+        }
+
+        let reference = self
+            .references
+            .get_or_insert_with(&stream, SharedMap::default)
+            .get_or_insert_with(symbol, ArcSwapOption::empty);
+
+        let mut violation = None;
+        reference.rcu(|reference| {
+            let (next, jump) = self.next_reference(reference.as_deref(), price);
+            violation = jump;
+            Some(Arc::new(next))
+        });
+        violation
+    }
+
+    fn next_reference(
+        &self,
+        reference: Option<&Reference>,
+        price: Decimal,
+    ) -> (Reference, Option<Violation>) {
+        let accepted = Reference {
+            price,
+            consecutive_outliers: 0,
+        };
+        let Some(reference) = reference else {
+            return (accepted, None);
+        };
+
+        let change_pct = ((price - reference.price) / reference.price).abs() * Decimal::ONE_HUNDRED;
+        if change_pct > self.config.max_price_change_pct
+            && reference.consecutive_outliers < self.config.max_consecutive_outliers
+        {
+            return (
+                Reference {
+                    price: reference.price,
+                    consecutive_outliers: reference.consecutive_outliers + 1,
+                },
+                Some(Violation::PriceJump {
+                    previous: reference.price,
+                    current: price,
+                }),
+            );
+        }
+
+        (accepted, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jumps_are_accepted_after_the_outlier_cap() {
+        let filter = SanityFilter::new(SanityConfig {
+            max_price_change_pct: Decimal::from(10),
+            max_consecutive_outliers: 2,
+            ..SanityConfig::default()
+        });
+        let check = |price: i64| filter.check_trade("btcusdt", Decimal::from(price));
+
+        assert!(check(100).is_none());
+        assert_eq!(
+            check(150),
+            Some(Violation::PriceJump {
+                previous: Decimal::from(100),
+                current: Decimal::from(150),
+            })
+        );
+        assert!(check(150).is_some());
+        // The third jump in a row becomes the new reference.
+        assert!(check(150).is_none());
+        assert!(check(155).is_none());
+        assert!(check(100).is_some());
+    }
+
+    #[test]
+    fn an_accepted_price_resets_the_outlier_count() {
+        let filter = SanityFilter::new(SanityConfig {
+            max_price_change_pct: Decimal::from(10),
+            max_consecutive_outliers: 2,
+            ..SanityConfig::default()
+        });
+        let check = |price: i64| filter.check_trade("btcusdt", Decimal::from(price));
+
+        assert!(check(100).is_none());
+        assert!(check(150).is_some());
+        assert!(check(101).is_none());
+        assert!(check(150).is_some());
+        assert!(check(150).is_some());
+        assert!(check(150).is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use std::time::{Duration, Instant};
+
+/// Outcome of checking a diff update against the last applied exchange update id.
+pub(super) enum SequenceCheck {
+    /// Already contained in the book (e.g. buffered before the snapshot was taken).
+    Outdated,
+    InSequence,
+    Gap {
+        expected: u64,
+        received: u64,
+    },
+}
+
+/// Follows Binance's diff depth update ids: each update covers `first..=last`, and the
+/// first one applied after a snapshot must contain `snapshot + 1`.
+#[derive(Default)]
+pub(super) struct SequenceTracker {
+    last_update_id: Option<u64>,
+}
+
+impl SequenceTracker {
+    pub fn is_synced(&self) -> bool {
+        self.last_update_id.is_some()
+    }
+
+    pub fn reset(&mut self, snapshot_update_id: u64) {
+        self.last_update_id = Some(snapshot_update_id);
+    }
+
+    pub fn invalidate(&mut self) {
+        self.last_update_id = None;
+    }
+
+    pub fn check(&mut self, first_update_id: u64, last_update_id: u64) -> SequenceCheck {
+        let Some(applied) = self.last_update_id else {
+            return SequenceCheck::Outdated;
+        };
+
+        if last_update_id <= applied {
+            return SequenceCheck::Outdated;
+        }
+
+        if first_update_id > applied + 1 {
+            return SequenceCheck::Gap {
+                expected: applied + 1,
+                received: first_update_id,
+            };
+        }
+
+        self.last_update_id = Some(last_update_id);
+        SequenceCheck::InSequence
+    }
+}
+
+/// Spaces out the REST snapshots taken to resync the book: the wait doubles after each
+/// snapshot, up to `max`, and drops back to `min` once the book is in sequence again.
+pub(super) struct SnapshotBackoff {
+    min: Duration,
+    max: Duration,
+    delay: Duration,
+    next_snapshot: Option<Instant>,
+}
+
+impl SnapshotBackoff {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max,
+            delay: min,
+            next_snapshot: None,
+        }
+    }
+
+    pub fn next_snapshot(&self) -> Option<Instant> {
+        self.next_snapshot
+    }
+
+    pub fn on_snapshot(&mut self, now: Instant) {
+        self.next_snapshot = Some(now + self.delay);
+        self.delay = (self.delay * 2).min(self.max);
+    }
+
+    pub fn on_synced(&mut self) {
+        self.delay = self.min;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_are_checked_against_the_snapshot() {
+        let mut tracker = SequenceTracker::default();
+        assert!(matches!(tracker.check(1, 2), SequenceCheck::Outdated));
+
+        tracker.reset(100);
+        // Buffered before the snapshot, entirely contained in it.
+        assert!(matches!(tracker.check(90, 100), SequenceCheck::Outdated));
+        // The first update after the snapshot must contain 101.
+        assert!(matches!(
+            tracker.check(102, 105),
+            SequenceCheck::Gap {
+                expected: 101,
+                received: 102
+            }
+        ));
+        assert!(matches!(tracker.check(95, 101), SequenceCheck::InSequence));
+        assert!(matches!(tracker.check(102, 110), SequenceCheck::InSequence));
+        assert!(matches!(tracker.check(112, 115), SequenceCheck::Gap { .. }));
+
+        tracker.invalidate();
+        assert!(!tracker.is_synced());
+        assert!(matches!(tracker.check(111, 115), SequenceCheck::Outdated));
+    }
+
+    #[test]
+    fn snapshots_back_off_until_the_book_is_in_sequence() {
+        let second = Duration::from_secs(1);
+        let mut backoff = SnapshotBackoff::new(second, 4 * second);
+        let start = Instant::now();
+        assert_eq!(backoff.next_snapshot(), None);
+
+        backoff.on_snapshot(start);
+        assert_eq!(backoff.next_snapshot(), Some(start + second));
+
+        backoff.on_snapshot(start + second);
+        assert_eq!(backoff.next_snapshot(), Some(start + 3 * second));
+
+        backoff.on_snapshot(start + 3 * second);
+        backoff.on_snapshot(start + 7 * second);
+        // Capped at the maximum.
+        assert_eq!(backoff.next_snapshot(), Some(start + 11 * second));
+
+        backoff.on_synced();
+        backoff.on_snapshot(start + 11 * second);
+        assert_eq!(backoff.next_snapshot(), Some(start + 12 * second));
+    }
+}
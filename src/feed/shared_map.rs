@@ -0,0 +1,76 @@
+use arc_swap::ArcSwap;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Per-key state looked up without locking on the publishing path. Values change through
+/// atomics or `ArcSwap` of their own, so only adding or removing a key copies the map.
+pub(super) struct SharedMap<K, V>(ArcSwap<HashMap<K, Arc<V>>>);
+
+impl<K, V> Default for SharedMap<K, V> {
+    fn default() -> Self {
+        Self(ArcSwap::from_pointee(HashMap::new()))
+    }
+}
+
+impl<K: Eq + Hash + Clone, V> SharedMap<K, V> {
+    pub fn get<Q>(&self, key: &Q) -> Option<Arc<V>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.0.load().get(key).cloned()
+    }
+
+    pub fn get_or_insert_with<Q>(&self, key: &Q, value: impl FnOnce() -> V) -> Arc<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = K> + ?Sized,
+    {
+        match self.get(key) {
+            Some(existing) => existing,
+            None => self.insert_if_absent(key.to_owned(), Arc::new(value())),
+        }
+    }
+
+    /// Returns the value that ends up in the map, which is not `value` when another thread
+    /// inserted the key first.
+    pub fn insert_if_absent(&self, key: K, value: Arc<V>) -> Arc<V> {
+        self.0.rcu(|map| {
+            let mut map = HashMap::clone(map);
+            map.entry(key.clone()).or_insert_with(|| value.clone());
+            map
+        });
+        self.get(&key).unwrap_or(value)
+    }
+
+    pub fn insert(&self, key: K, value: Arc<V>) {
+        self.0.rcu(|map| {
+            let mut map = HashMap::clone(map);
+            map.insert(key.clone(), value.clone());
+            map
+        });
+    }
+
+    pub fn remove<Q>(&self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.retain(|existing, _| existing.borrow() != key);
+    }
+
+    pub fn retain(&self, keep: impl Fn(&K, &V) -> bool) {
+        self.0.rcu(|map| {
+            map.iter()
+                .filter(|(key, value)| keep(key, value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect::<HashMap<_, _>>()
+        });
+    }
+
+    pub fn snapshot(&self) -> Arc<HashMap<K, Arc<V>>> {
+        self.0.load_full()
+    }
+}
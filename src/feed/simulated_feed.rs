@@ -0,0 +1,115 @@
+use super::messages::{l1_data::L1Data, l2_data::L2Data, level::Level, trade_data::TradeData};
+use crate::common_types::instrument_id::InstrumentId;
+use crate::common_types::side::Side;
+use rust_decimal::Decimal;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SIMULATED_DEPTH: i32 = 5;
+
+/// Random walk order book used by the simulator environment in place of an exchange feed.
+pub(super) struct SimulatedBook {
+    instrument: InstrumentId,
+    mid_price: Decimal,
+    seed: u64,
+}
+
+impl SimulatedBook {
+    pub fn new<Instrument>(instrument: Instrument, reference_price: Decimal) -> Self
+    where
+        Instrument: Into<InstrumentId>,
+    {
+        let instrument = instrument.into();
+
+        let mut hasher = DefaultHasher::new();
+        instrument.hash(&mut hasher);
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+            .hash(&mut hasher);
+
+        Self {
+            instrument,
+            mid_price: reference_price,
+            seed: hasher.finish() | 1,
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+
+    fn random_in_range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next_random() % (high - low + 1) as u64) as i64
+    }
+
+    fn random_quantity(&mut self) -> Decimal {
+        Decimal::new(self.random_in_range(1, 2000), 3)
+    }
+
+    pub fn step(&mut self) -> (L1Data, L2Data) {
+        let price_change = Decimal::new(self.random_in_range(-20, 20), 5);
+        self.mid_price += (self.mid_price * price_change).round_dp(8);
+
+        let half_spread = (self.mid_price * Decimal::new(1, 4)).round_dp(8);
+        let best_bid_price = (self.mid_price - half_spread).round_dp(8);
+        let best_ask_price = (self.mid_price + half_spread).round_dp(8);
+
+        let bid_side_levels: Vec<Level> = (0..SIMULATED_DEPTH)
+            .map(|i| {
+                Level::new(
+                    i + 1,
+                    self.random_quantity(),
+                    best_bid_price - half_spread * Decimal::from(i),
+                )
+            })
+            .collect();
+
+        let ask_side_levels: Vec<Level> = (0..SIMULATED_DEPTH)
+            .map(|i| {
+                Level::new(
+                    i + 1,
+                    self.random_quantity(),
+                    best_ask_price + half_spread * Decimal::from(i),
+                )
+            })
+            .collect();
+
+        let l1_data = L1Data::new(
+            self.instrument.clone(),
+            bid_side_levels[0].quantity,
+            bid_side_levels[0].price,
+            ask_side_levels[0].quantity,
+            ask_side_levels[0].price,
+        );
+
+        (
+            l1_data,
+            L2Data::new(self.instrument.clone(), bid_side_levels, ask_side_levels),
+        )
+    }
+
+    /// Prints a trade against the current touch with a random aggressor side.
+    pub fn trade(&mut self) -> TradeData {
+        let half_spread = (self.mid_price * Decimal::new(1, 4)).round_dp(8);
+
+        let (aggressor_side, price) = if self.next_random().is_multiple_of(2) {
+            (Side::Buy, (self.mid_price + half_spread).round_dp(8))
+        } else {
+            (Side::Sell, (self.mid_price - half_spread).round_dp(8))
+        };
+
+        TradeData::new(
+            self.instrument.clone(),
+            price,
+            self.random_quantity(),
+            aggressor_side,
+            chrono::Utc::now(),
+        )
+    }
+}
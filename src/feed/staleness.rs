@@ -0,0 +1,44 @@
+use super::messages::feed_status::FeedState;
+use std::time::Duration;
+
+/// Tracks whether an exchange stream is delivering updates and reports each state change
+/// exactly once.
+pub(super) struct FeedHealth {
+    threshold: Duration,
+    degraded: Option<FeedState>,
+}
+
+impl FeedHealth {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            degraded: None,
+        }
+    }
+
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    pub fn on_update(&mut self) -> Option<FeedState> {
+        self.degraded.take().map(|_| FeedState::Recovered)
+    }
+
+    pub fn on_timeout(&mut self) -> Option<FeedState> {
+        if self.degraded.is_some() {
+            return None;
+        }
+
+        self.degraded = Some(FeedState::Stale);
+        self.degraded
+    }
+
+    pub fn on_reconnecting(&mut self) -> Option<FeedState> {
+        if self.degraded == Some(FeedState::Reconnecting) {
+            return None;
+        }
+
+        self.degraded = Some(FeedState::Reconnecting);
+        self.degraded
+    }
+}
@@ -0,0 +1,236 @@
+use super::messages::feed_status::FeedState;
+use super::messages::l1_data::L1Data;
+use crate::common_types::instrument_id::{self, InstrumentId};
+use crate::config::SyntheticConfig;
+use rust_decimal::Decimal;
+
+const SYNTHETIC_DECIMAL_PLACES: u32 = 8;
+
+/// Derives the top of book of a cross rate such as SOL/BTC from its legs quoted in a common
+/// currency (SOL/USDT and BTC/USDT).
+pub(super) struct SyntheticBook {
+    symbol: InstrumentId,
+    base_leg_symbol: String,
+    quote_leg_symbol: String,
+    base_leg: Option<L1Data>,
+    quote_leg: Option<L1Data>,
+    base_leg_state: FeedState,
+    quote_leg_state: FeedState,
+    state: FeedState,
+}
+
+impl SyntheticBook {
+    pub fn new(synthetic: &SyntheticConfig) -> Self {
+        Self {
+            symbol: instrument_id::intern(&synthetic.symbol()),
+            base_leg_symbol: synthetic.base_leg(),
+            quote_leg_symbol: synthetic.quote_leg(),
+            base_leg: None,
+            quote_leg: None,
+            base_leg_state: FeedState::Recovered,
+            quote_leg_state: FeedState::Recovered,
+            state: FeedState::Recovered,
+        }
+    }
+
+    /// Applies a leg's feed status and returns the synthetic's new status when it changes.
+    /// The synthetic is as healthy as its worst leg, and a degraded leg's quote is dropped
+    /// so nothing is derived from it until the leg delivers fresh data.
+    pub fn on_status(&mut self, leg_symbol: &str, leg_state: FeedState) -> Option<FeedState> {
+        // Missed leg updates are superseded by the next quote of the leg.
+        if leg_state == FeedState::Gap {
+            return None;
+        }
+
+        let degraded = leg_state != FeedState::Recovered;
+
+        if leg_symbol == self.base_leg_symbol {
+            self.base_leg_state = leg_state;
+            if degraded {
+                self.base_leg = None;
+            }
+        } else if leg_symbol == self.quote_leg_symbol {
+            self.quote_leg_state = leg_state;
+            if degraded {
+                self.quote_leg = None;
+            }
+        } else {
+            return None;
+        }
+
+        let state = worst(self.base_leg_state, self.quote_leg_state);
+        if state == self.state {
+            return None;
+        }
+
+        self.state = state;
+        Some(state)
+    }
+
+    /// Applies a leg update and returns the synthetic quote once both legs have a usable one.
+    pub fn update(&mut self, l1_data: &L1Data) -> Option<L1Data> {
+        if *l1_data.symbol == self.base_leg_symbol {
+            self.base_leg = Some(l1_data.clone());
+        } else if *l1_data.symbol == self.quote_leg_symbol {
+            self.quote_leg = Some(l1_data.clone());
+        } else {
+            return None;
+        }
+
+        self.compose()
+    }
+
+    /// Selling the synthetic sells the base leg at its bid and buys the quote leg at its ask;
+    /// buying it does the opposite. Quantities are in the synthetic's base currency and limited
+    /// by whichever leg runs out first.
+    fn compose(&self) -> Option<L1Data> {
+        let base_leg = self.base_leg.as_ref()?;
+        let quote_leg = self.quote_leg.as_ref()?;
+
+        let base_bid = &base_leg.best_bid_level;
+        let base_ask = &base_leg.best_ask_level;
+        let quote_bid = &quote_leg.best_bid_level;
+        let quote_ask = &quote_leg.best_ask_level;
+
+        if [
+            base_bid.price,
+            base_ask.price,
+            quote_bid.price,
+            quote_ask.price,
+        ]
+        .iter()
+        .any(|price| price.is_zero())
+        {
+            return None;
+        }
+
+        let bid_price = base_bid.price / quote_ask.price;
+        let ask_price = base_ask.price / quote_bid.price;
+        let bid_quantity = base_bid
+            .quantity
+            .min(quote_ask.quantity * quote_ask.price / base_bid.price);
+        let ask_quantity = base_ask
+            .quantity
+            .min(quote_bid.quantity * quote_bid.price / base_ask.price);
+
+        let mut l1_data = L1Data::new(
+            self.symbol.clone(),
+            round(bid_quantity),
+            round(bid_price),
+            round(ask_quantity),
+            round(ask_price),
+        );
+        // A quote derived from a flagged leg is as suspect as the leg.
+        l1_data.violation = base_leg
+            .violation
+            .clone()
+            .or_else(|| quote_leg.violation.clone());
+
+        Some(l1_data)
+    }
+}
+
+fn worst(first: FeedState, second: FeedState) -> FeedState {
+    let severity = |state| match state {
+        FeedState::Recovered | FeedState::Gap => 0,
+        FeedState::Stale => 1,
+        FeedState::Reconnecting => 2,
+        FeedState::Invalid => 3,
+    };
+
+    if severity(second) > severity(first) {
+        second
+    } else {
+        first
+    }
+}
+
+fn round(value: Decimal) -> Decimal {
+    value.round_dp(SYNTHETIC_DECIMAL_PLACES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::messages::violation::Violation;
+
+    fn book() -> SyntheticBook {
+        SyntheticBook::new(&SyntheticConfig {
+            base: "SOL".into(),
+            quote: "BTC".into(),
+            via: "USDT".into(),
+        })
+    }
+
+    fn quote(symbol: &str, bid: (i64, i64), ask: (i64, i64)) -> L1Data {
+        L1Data::new(
+            symbol,
+            Decimal::from(bid.0),
+            Decimal::from(bid.1),
+            Decimal::from(ask.0),
+            Decimal::from(ask.1),
+        )
+    }
+
+    #[test]
+    fn cross_rate_comes_from_the_opposite_sides_of_the_legs() {
+        let mut book = book();
+
+        assert!(book
+            .update(&quote("SOLUSDT", (10, 100), (10, 101)))
+            .is_none());
+        let synthetic = book
+            .update(&quote("BTCUSDT", (1, 50_000), (1, 50_100)))
+            .unwrap();
+
+        assert_eq!(&*synthetic.symbol, "SOLBTC");
+        // Selling SOL/BTC sells SOL at its bid and buys BTC at its ask.
+        assert_eq!(
+            synthetic.best_bid_level.price,
+            round(Decimal::from(100) / Decimal::from(50_100))
+        );
+        assert_eq!(
+            synthetic.best_ask_level.price,
+            round(Decimal::from(101) / Decimal::from(50_000))
+        );
+        // The SOL legs are the smaller side here.
+        assert_eq!(synthetic.best_bid_level.quantity, Decimal::from(10));
+        assert_eq!(synthetic.best_ask_level.quantity, Decimal::from(10));
+        assert!(synthetic.violation.is_none());
+    }
+
+    #[test]
+    fn quantities_are_capped_by_the_thinner_leg() {
+        let mut book = book();
+
+        book.update(&quote("SOLUSDT", (1_000, 100), (1_000, 125)));
+        let synthetic = book
+            .update(&quote("BTCUSDT", (2, 50_000), (1, 50_000)))
+            .unwrap();
+
+        // 1 BTC at 50000 buys 500 SOL at 100, 2 BTC at 50000 sell for 800 SOL at 125.
+        assert_eq!(synthetic.best_bid_level.quantity, Decimal::from(500));
+        assert_eq!(synthetic.best_ask_level.quantity, Decimal::from(800));
+    }
+
+    #[test]
+    fn flagged_legs_flag_the_synthetic_quote() {
+        let mut book = book();
+        let violation = Violation::PriceJump {
+            previous: Decimal::from(50_000),
+            current: Decimal::from(60_000),
+        };
+
+        book.update(&quote("SOLUSDT", (10, 100), (10, 101)));
+        let mut flagged = quote("BTCUSDT", (1, 60_000), (1, 60_100));
+        flagged.violation = Some(violation.clone());
+
+        let synthetic = book.update(&flagged).unwrap();
+        assert_eq!(synthetic.violation, Some(violation));
+
+        let synthetic = book
+            .update(&quote("BTCUSDT", (1, 50_000), (1, 50_100)))
+            .unwrap();
+        assert!(synthetic.violation.is_none());
+    }
+}
@@ -0,0 +1,125 @@
+use super::messages::{ticker::Ticker, trade_data::TradeData};
+use crate::common_types::instrument_id::InstrumentId;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, VecDeque};
+
+const TICKER_DECIMAL_PLACES: u32 = 8;
+
+/// Builds 24 hour statistics from public trades, for feeds without an exchange ticker
+/// (simulator and replay).
+pub(super) struct TickerBuilder {
+    symbol: InstrumentId,
+    trades: VecDeque<(DateTime<Utc>, Decimal, Decimal)>,
+    /// How many trades in the window printed at each price, for the high and low.
+    prices: BTreeMap<Decimal, usize>,
+    volume: Decimal,
+}
+
+impl TickerBuilder {
+    pub fn new<Symbol>(symbol: Symbol) -> Self
+    where
+        Symbol: Into<InstrumentId>,
+    {
+        Self {
+            symbol: symbol.into(),
+            trades: VecDeque::new(),
+            prices: BTreeMap::new(),
+            volume: Decimal::ZERO,
+        }
+    }
+
+    pub fn on_trade(&mut self, trade: &TradeData) -> Ticker {
+        self.trades
+            .push_back((trade.trade_time, trade.price, trade.quantity));
+        *self.prices.entry(trade.price).or_default() += 1;
+        self.volume += trade.quantity;
+
+        let window_start = trade.trade_time - Duration::hours(24);
+        while let Some((trade_time, price, quantity)) = self.trades.front() {
+            if *trade_time >= window_start {
+                break;
+            }
+            self.volume -= *quantity;
+            if let Some(count) = self.prices.get_mut(price) {
+                *count -= 1;
+                if *count == 0 {
+                    self.prices.remove(price);
+                }
+            }
+            self.trades.pop_front();
+        }
+
+        let open_price = self
+            .trades
+            .front()
+            .map_or(trade.price, |(_, price, _)| *price);
+        let high_price = self
+            .prices
+            .last_key_value()
+            .map_or(trade.price, |(price, _)| *price);
+        let low_price = self
+            .prices
+            .first_key_value()
+            .map_or(trade.price, |(price, _)| *price);
+
+        let price_change_pct = (trade.price - open_price)
+            .checked_div(open_price)
+            .map_or(Decimal::ZERO, |change| {
+                (change * Decimal::ONE_HUNDRED).round_dp(TICKER_DECIMAL_PLACES)
+            });
+
+        Ticker {
+            symbol: self.symbol.clone(),
+            last_price: trade.price,
+            open_price,
+            high_price,
+            low_price,
+            volume: self.volume,
+            price_change_pct,
+            close_time: trade.trade_time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::side::Side;
+    use chrono::TimeZone;
+
+    fn trade(hour: i64, price: i64) -> TradeData {
+        let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        TradeData::new(
+            "BTCUSDT",
+            Decimal::from(price),
+            Decimal::ONE,
+            Side::Buy,
+            start + Duration::hours(hour),
+        )
+    }
+
+    #[test]
+    fn high_and_low_follow_the_trades_in_the_window() {
+        let mut builder = TickerBuilder::new("BTCUSDT");
+
+        builder.on_trade(&trade(0, 120));
+        builder.on_trade(&trade(1, 90));
+        builder.on_trade(&trade(2, 90));
+        let ticker = builder.on_trade(&trade(3, 100));
+        assert_eq!(ticker.high_price, Decimal::from(120));
+        assert_eq!(ticker.low_price, Decimal::from(90));
+        assert_eq!(ticker.volume, Decimal::from(4));
+
+        // The 120 print leaves the window, both 90 prints are still in it.
+        let ticker = builder.on_trade(&trade(25, 110));
+        assert_eq!(ticker.high_price, Decimal::from(110));
+        assert_eq!(ticker.low_price, Decimal::from(90));
+        assert_eq!(ticker.open_price, Decimal::from(90));
+
+        let ticker = builder.on_trade(&trade(27, 105));
+        assert_eq!(ticker.high_price, Decimal::from(110));
+        assert_eq!(ticker.low_price, Decimal::from(100));
+        assert_eq!(ticker.volume, Decimal::from(3));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod algo_context;
+pub mod algorithams;
+pub mod common_types;
+pub mod config;
+pub mod feed;
+pub mod logging;
+pub mod market;
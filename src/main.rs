@@ -1,18 +1,11 @@
+use sniper_algorithm::algo_context::algo_service::AlgoService;
+use sniper_algorithm::config::{AlgorithmConfig, Environment, MarketConfig};
+use sniper_algorithm::feed::feed_handle::FeedHandle;
+use sniper_algorithm::logging::algo_logger::AlgoLogger;
+use sniper_algorithm::market::market_handle::MarketHandle;
+use std::io::{self, BufRead};
 use std::process;
-mod algo_context;
-mod algorithams;
-mod common_types;
-mod config;
-mod logging;
-mod market;
-use crate::market::market_handle::MarketHandle;
-use algo_context::algo_service::AlgoService;
-use feed::feed_handle::FeedHandle;
-use logging::algo_logger::AlgoLogger;
-
-use config::{AlgorithmConfig, MarketConfig};
-
-mod feed;
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
 async fn main() {
     let config = match AlgorithmConfig::load_from_file("config/config.toml") {
@@ -26,7 +19,12 @@ async fn main() {
         }
     };
 
-    let market_config = match MarketConfig::from_env() {
+    if config.environment.is_production() && !confirm_production() {
+        eprintln!("Production environment was not confirmed, exiting.");
+        process::exit(1);
+    }
+
+    let market_config = match MarketConfig::from_env(config.environment.clone()) {
         Ok(config) => config,
         Err(e) => {
             eprintln!("Failed to env config: {}", e);
@@ -36,10 +34,15 @@ async fn main() {
 
     AlgoLogger::init_once().expect("Failed to initialize logger");
 
-    println!("Successfully started");
+    println!("Successfully started on {}", config.environment);
 
-    let (feed_service, feed_handle) =
-        FeedHandle::new(config::extract_trading_pairs(&config.algorithms)).await;
+    let (feed_service, feed_handle) = FeedHandle::new(
+        config.environment.clone(),
+        config.simulator.clone(),
+        config.feed.clone(),
+    );
+
+    check_limit_prices(&config, &feed_service).await;
 
     let (market_service, market_handle) = MarketHandle::new(market_config).await;
 
@@ -66,3 +69,65 @@ async fn main() {
     }
     println!("Algorithms done");
 }
+
+/// Warns about sniper limits far from the market; they either never fill or fill at once at
+/// a price nobody intended.
+async fn check_limit_prices(config: &AlgorithmConfig, feed_handle: &FeedHandle) {
+    let max_distance_pct = config.validation.max_limit_distance_pct;
+    if max_distance_pct.is_zero() {
+        return;
+    }
+
+    for params in &config.algorithms {
+        let ticker = match feed_handle
+            .get_ticker(params.base.as_str(), params.quote.as_str())
+            .await
+        {
+            Ok(ticker) => ticker,
+            Err(error) => {
+                eprintln!(
+                    "No ticker for {} ({}), limit price of algo {} was not checked.",
+                    params.make_symbol(),
+                    error,
+                    params.algo_id
+                );
+                continue;
+            }
+        };
+
+        let Some(distance_pct) = ticker.distance_pct(params.price) else {
+            continue;
+        };
+
+        if distance_pct > max_distance_pct {
+            eprintln!(
+                "WARNING: {} limit {} of algo {} is {:.2}% away from the last {} price {} (24h low {}, high {}).",
+                params.side,
+                params.price,
+                params.algo_id,
+                distance_pct,
+                params.make_symbol(),
+                ticker.last_price,
+                ticker.low_price,
+                ticker.high_price
+            );
+        }
+    }
+}
+
+fn confirm_production() -> bool {
+    eprintln!("==================================================================");
+    eprintln!("  WARNING: {} selected.", Environment::Mainnet);
+    eprintln!("  Orders will be sent to the live exchange and use REAL funds.");
+    eprintln!("==================================================================");
+
+    if std::env::var("CONFIRM_PRODUCTION").is_ok_and(|value| value == "yes") {
+        eprintln!("Production confirmed through CONFIRM_PRODUCTION.");
+        return true;
+    }
+
+    eprintln!("Type 'yes' to continue:");
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).is_ok() && answer.trim() == "yes"
+}
@@ -1,28 +1,68 @@
 use super::messages::{
-    execution_type::ExecutionType, market_messages::MarketMessages,
+    execution_type::ExecutionType,
+    market_messages::MarketMessages,
     market_responses::MarketResponses,
+    order_request::{OcoOrderRequest, OrderRequest},
 };
+use super::oco_tracker::OcoTracker;
+use super::order_slicer::{OrderLimits, OrderSlicer, SlicedOrder};
 use crate::{
-    common_types::{order_types::OrderType, side::Side, time_in_force::TIF},
+    common_types::{
+        order_types::OrderType, side::Side, symbol_status::SymbolStatus, time_in_force::TIF,
+    },
     config::MarketConfig,
 };
 use binance::{
     account::{Account, OrderSide, OrderType as BinanceOrderType, TimeInForce},
     general::General,
-    model::Filters,
+    model::{ExchangeInformation, Filters},
     websockets::*,
 };
-use binance::{api::*, config::Config};
+use binance::{api::*, util::build_signed_request};
 use binance::{model::OrderTradeEvent, userstream::*};
 use probe::probe_lazy;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::{prelude::Zero, Decimal};
+use serde::Deserialize;
 use std::sync::atomic::AtomicBool;
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::time::Duration;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    sync::Arc,
+};
 use tokio::{
     sync::mpsc,
     task::{self},
 };
+use uuid::Uuid;
+
+const SYMBOL_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Builds the `exchangeInfo` query that asks only for the given symbols, so polling
+/// doesn't download every listed symbol.
+fn symbols_query(symbols: &[String]) -> String {
+    let symbols: Vec<String> = symbols
+        .iter()
+        .map(|symbol| format!("%22{}%22", symbol.to_uppercase()))
+        .collect();
+    format!("symbols=%5B{}%5D", symbols.join(","))
+}
+
+type AlgoContexts = Arc<std::sync::Mutex<HashMap<String, (String, mpsc::Sender<MarketResponses>)>>>;
+
+type SymbolWatchers = Arc<
+    std::sync::Mutex<HashMap<String, (SymbolStatus, Vec<(String, mpsc::Sender<MarketResponses>)>)>>,
+>;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OcoOrderResponse {
+    order_list_id: i64,
+    list_client_order_id: String,
+    list_status_type: String,
+    list_order_status: String,
+}
 
 macro_rules! probe {
     ($name:ident) => {
@@ -35,7 +75,11 @@ pub(super) struct MarketActor {
     account: Account,
     general: General,
     market_config: MarketConfig,
-    algo_contexts: Arc<std::sync::Mutex<HashMap<String, (String, mpsc::Sender<MarketResponses>)>>>,
+    algo_contexts: AlgoContexts,
+    symbol_watchers: SymbolWatchers,
+    symbol_limits: Arc<std::sync::Mutex<HashMap<String, OrderLimits>>>,
+    order_slicer: Arc<std::sync::Mutex<OrderSlicer>>,
+    oco_tracker: Arc<std::sync::Mutex<OcoTracker>>,
 }
 
 impl MarketActor {
@@ -45,9 +89,9 @@ impl MarketActor {
     ) -> Self {
         let api_key = Some(market_config.api_key.clone());
         let api_secret = Some(market_config.api_secret.clone());
+        let config = market_config.environment.binance_config();
 
         let result = task::spawn_blocking(move || {
-            let config = Config::testnet();
             let user_stream: Account =
                 Binance::new_with_config(api_key.clone(), api_secret.clone(), &config);
 
@@ -61,10 +105,14 @@ impl MarketActor {
 
         Self {
             receiver,
-            account: account,
-            general: general,
+            account,
+            general,
             market_config,
             algo_contexts: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            symbol_watchers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            symbol_limits: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            order_slicer: Arc::new(std::sync::Mutex::new(OrderSlicer::default())),
+            oco_tracker: Arc::new(std::sync::Mutex::new(OcoTracker::default())),
         }
     }
 
@@ -76,12 +124,15 @@ impl MarketActor {
                 sender,
             } => {
                 let general = self.general.clone();
+                let symbol_watchers = self.symbol_watchers.clone();
+                let symbol_limits = self.symbol_limits.clone();
 
-                task::spawn_blocking(move || match general.get_symbol_info(symbol) {
+                task::spawn_blocking(move || match general.get_symbol_info(symbol.clone()) {
                     Ok(answer) => {
                         let (mut min_qty, mut max_qty, mut lot_size) = (None, None, None);
                         let (mut min_price, mut max_price, mut tick_size) = (None, None, None);
                         let mut min_amount = None;
+                        let mut market_max_qty = None;
 
                         for filter in &answer.filters {
                             match filter {
@@ -94,6 +145,13 @@ impl MarketActor {
                                     max_qty = mxq.parse::<Decimal>().ok();
                                     lot_size = step_size.parse::<Decimal>().ok();
                                 }
+                                Filters::MarketLotSize {
+                                    min_qty: _,
+                                    max_qty: mxq,
+                                    step_size: _,
+                                } => {
+                                    market_max_qty = mxq.parse::<Decimal>().ok();
+                                }
                                 Filters::PriceFilter {
                                     min_price: mp,
                                     max_price: mxp,
@@ -117,6 +175,28 @@ impl MarketActor {
                             }
                         }
 
+                        let status =
+                            SymbolStatus::from_str(&answer.status).unwrap_or(SymbolStatus::Unknown);
+
+                        symbol_limits.lock().unwrap().insert(
+                            symbol.clone(),
+                            OrderLimits {
+                                max_quantity: max_qty,
+                                market_max_quantity: market_max_qty,
+                                min_quantity: min_qty,
+                                step_size: lot_size,
+                                min_notional: min_amount,
+                            },
+                        );
+
+                        symbol_watchers
+                            .lock()
+                            .unwrap()
+                            .entry(symbol)
+                            .or_insert_with(|| (status.clone(), Vec::new()))
+                            .1
+                            .push((algo_id.clone(), sender.clone()));
+
                         sender
                             .try_send(MarketResponses::SymbolInformation {
                                 algo_id,
@@ -127,6 +207,7 @@ impl MarketActor {
                                 max_price,
                                 tick_size,
                                 min_amount,
+                                status: Some(status),
                             })
                             .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
                     }
@@ -142,12 +223,24 @@ impl MarketActor {
                                 max_price: None,
                                 tick_size: None,
                                 min_amount: None,
+                                status: None,
                             })
                             .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
                     }
                 });
             }
 
+            MarketMessages::StopWatchingSymbol { symbol, algo_id } => {
+                let mut symbol_watchers = self.symbol_watchers.lock().unwrap();
+
+                if let Some((_, algos)) = symbol_watchers.get_mut(&symbol) {
+                    algos.retain(|(watcher_id, _)| watcher_id != &algo_id);
+                    if algos.is_empty() {
+                        symbol_watchers.remove(&symbol);
+                    }
+                }
+            }
+
             MarketMessages::CreateOrder {
                 symbol,
                 price,
@@ -159,87 +252,258 @@ impl MarketActor {
                 order_id,
                 algo_id,
             } => {
-                let account_clone = self.account.clone();
-                let order_side = OrderSide::from_int(side.to_int()).unwrap_or(OrderSide::Buy);
-                let time_in_force_binance =
-                    TimeInForce::from_int(time_in_force.to_int()).unwrap_or(TimeInForce::GTC);
-                let order_type_binance = BinanceOrderType::from_int(order_type.to_int())
-                    .unwrap_or(BinanceOrderType::Limit);
-                let mut algo_contexts = self.algo_contexts.lock().unwrap();
+                probe!(order_sent_to_market);
+
+                self.create_order(
+                    OrderRequest {
+                        order_id,
+                        algo_id,
+                        symbol,
+                        price,
+                        quantity,
+                        side,
+                        order_type,
+                        time_in_force,
+                    },
+                    sender,
+                );
+            }
 
+            MarketMessages::CreateOrders { orders, sender } => {
                 probe!(order_sent_to_market);
 
-                algo_contexts.insert(order_id.clone(), (algo_id.clone(), sender.clone()));
-                task::spawn_blocking(move || {
-                    match account_clone.custom_order(
-                        symbol.clone(),
-                        quantity.clone().to_f64().unwrap_or(0.),
-                        price.clone().to_f64().unwrap_or(0.),
-                        None,
-                        order_side,
-                        order_type_binance,
-                        time_in_force_binance,
-                        Some(order_id.clone()),
-                    ) {
-                        Ok(_) => {}
-                        Err(e) => {
-                            println!("Error: {}", e);
-                            sender
-                                .try_send(MarketResponses::OrderRejected {
-                                    order_id,
-                                    algo_id,
-                                    symbol,
-                                    execution_status: ExecutionType::Rejected,
-                                    order_quantity: quantity,
-                                    side,
-                                    order_type,
-                                    price,
-                                    time_in_force,
-                                    rejection_reason: e.to_string(),
-                                })
-                                .unwrap_or_else(|err| {
-                                    eprintln!("Failed to send message: {:?}", err)
-                                });
-                        }
-                    }
-                });
+                for order in orders {
+                    self.create_order(order, sender.clone());
+                }
+            }
+
+            MarketMessages::CreateOcoOrder { order, sender } => {
+                probe!(order_sent_to_market);
+
+                self.submit_oco_order(order, sender);
             }
         }
     }
+
+    fn create_order(&self, order: OrderRequest, sender: mpsc::Sender<MarketResponses>) {
+        let limits = self
+            .symbol_limits
+            .lock()
+            .unwrap()
+            .get(&order.symbol)
+            .cloned()
+            .unwrap_or_default();
+        let max_quantity = limits.max_quantity(&order.order_type);
+
+        let child_quantities = match limits.split(&order) {
+            Ok(child_quantities) => child_quantities,
+            Err(e) => {
+                eprintln!("Can't split order {}: {}", order.order_id, e);
+                sender
+                    .try_send(MarketResponses::OrderRejected {
+                        order_id: order.order_id,
+                        algo_id: order.algo_id,
+                        symbol: order.symbol,
+                        execution_status: ExecutionType::Rejected,
+                        order_quantity: order.quantity,
+                        side: order.side,
+                        order_type: order.order_type,
+                        price: order.price,
+                        time_in_force: order.time_in_force,
+                        rejection_reason: e,
+                    })
+                    .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                return;
+            }
+        };
+
+        if child_quantities.len() > 1 {
+            let children: Vec<OrderRequest> = child_quantities
+                .into_iter()
+                .map(|quantity| order.child(Uuid::new_v4().to_string(), quantity))
+                .collect();
+
+            println!(
+                "Order {} exceeds the maximum quantity {} for {}, splitting it into {} orders.",
+                order.order_id,
+                max_quantity.unwrap_or_default(),
+                order.symbol,
+                children.len()
+            );
+
+            self.order_slicer
+                .lock()
+                .unwrap()
+                .register(SlicedOrder::new(order, &children));
+
+            for child in children {
+                self.submit_order(child, sender.clone());
+            }
+        } else {
+            self.submit_order(order, sender);
+        }
+    }
+
+    fn submit_order(&self, order: OrderRequest, sender: mpsc::Sender<MarketResponses>) {
+        let account_clone = self.account.clone();
+        let order_slicer = self.order_slicer.clone();
+        let order_side = OrderSide::from_int(order.side.to_int()).unwrap_or(OrderSide::Buy);
+        let time_in_force_binance =
+            TimeInForce::from_int(order.time_in_force.to_int()).unwrap_or(TimeInForce::GTC);
+        let order_type_binance = BinanceOrderType::from_int(order.order_type.to_int())
+            .unwrap_or(BinanceOrderType::Limit);
+
+        self.algo_contexts.lock().unwrap().insert(
+            order.order_id.clone(),
+            (order.algo_id.clone(), sender.clone()),
+        );
+
+        task::spawn_blocking(move || {
+            match account_clone.custom_order(
+                order.symbol.clone(),
+                order.quantity.to_f64().unwrap_or(0.),
+                order.price.to_f64().unwrap_or(0.),
+                None,
+                order_side,
+                order_type_binance,
+                time_in_force_binance,
+                Some(order.order_id.clone()),
+            ) {
+                Ok(_) => {}
+                Err(e) => {
+                    println!("Error: {}", e);
+                    dispatch_response(
+                        &sender,
+                        &order_slicer,
+                        MarketResponses::OrderRejected {
+                            order_id: order.order_id,
+                            algo_id: order.algo_id,
+                            symbol: order.symbol,
+                            execution_status: ExecutionType::Rejected,
+                            order_quantity: order.quantity,
+                            side: order.side,
+                            order_type: order.order_type,
+                            price: order.price,
+                            time_in_force: order.time_in_force,
+                            rejection_reason: e.to_string(),
+                        },
+                    );
+                }
+            }
+        });
+    }
+
+    fn submit_oco_order(&self, order: OcoOrderRequest, sender: mpsc::Sender<MarketResponses>) {
+        let account_clone = self.account.clone();
+        let oco_tracker = self.oco_tracker.clone();
+
+        // Leg execution reports are routed through the tracker, the algorithm never created
+        // the legs as orders of its own.
+        oco_tracker.lock().unwrap().register(&order, sender.clone());
+
+        task::spawn_blocking(move || {
+            let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+            parameters.insert("symbol".into(), order.symbol.clone());
+            parameters.insert("side".into(), order.side.to_string());
+            parameters.insert("quantity".into(), order.quantity.normalize().to_string());
+            parameters.insert("price".into(), order.price.normalize().to_string());
+            parameters.insert("stopPrice".into(), order.stop_price.normalize().to_string());
+            parameters.insert(
+                "stopLimitPrice".into(),
+                order.stop_limit_price.normalize().to_string(),
+            );
+            parameters.insert("stopLimitTimeInForce".into(), TIF::GTC.to_string());
+            parameters.insert("listClientOrderId".into(), order.list_id.clone());
+            parameters.insert("limitClientOrderId".into(), order.limit_order_id.clone());
+            parameters.insert("stopClientOrderId".into(), order.stop_order_id.clone());
+
+            let result = match build_signed_request(parameters, account_clone.recv_window) {
+                Ok(request) => account_clone
+                    .client
+                    .post_signed::<OcoOrderResponse>(API::Spot(Spot::Oco), request),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(response) => {
+                    println!(
+                        "OCO order list {} accepted as order list {}: {} {}.",
+                        response.list_client_order_id,
+                        response.order_list_id,
+                        response.list_status_type,
+                        response.list_order_status
+                    );
+                }
+                Err(e) => {
+                    println!("Error: {}", e);
+                    oco_tracker.lock().unwrap().remove(&order.list_id);
+
+                    sender
+                        .try_send(MarketResponses::OcoOrderRejected {
+                            list_id: order.list_id,
+                            algo_id: order.algo_id,
+                            symbol: order.symbol,
+                            rejection_reason: e.to_string(),
+                        })
+                        .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                }
+            }
+        });
+    }
 }
 
 pub(super) async fn run_my_actor(mut actor: MarketActor) {
     let algo_contexts = actor.algo_contexts.clone();
+    let order_slicer = actor.order_slicer.clone();
+    let oco_tracker = actor.oco_tracker.clone();
 
     let api_key = Some(actor.market_config.api_key.clone());
     let api_secret = Some(actor.market_config.api_secret.clone());
+    let config = actor.market_config.environment.binance_config();
 
     task::spawn_blocking(move || {
         let keep_running = AtomicBool::new(true);
-        let config = Config::testnet();
         let user_stream: UserStream = Binance::new_with_config(api_key, api_secret, &config);
 
         if let Ok(answer) = user_stream.start() {
             let listen_key = answer.listen_key;
 
+            // binance-rs takes its own error type back from the callback.
+            #[allow(clippy::result_large_err)]
             let mut web_socket: WebSockets<'_> = WebSockets::new(|event: WebsocketEvent| {
-                match event {
-                    WebsocketEvent::OrderTrade(trade) => {
-                        let algo_contexts = algo_contexts.lock().unwrap();
-                        match algo_contexts.get(&trade.new_client_order_id) {
-                            Some((algo_id, algo_context)) => {
-                                handle_order_trade_event(algo_context, algo_id, &trade);
-                            }
-                            None => {
-                                eprintln!(
-                                    "No algorithm context found for new_client_order_id: {}",
-                                    trade.new_client_order_id
-                                );
-                            }
+                if let WebsocketEvent::OrderTrade(trade) = event {
+                    let oco_leg_event = oco_tracker.lock().unwrap().on_order_trade_event(&trade);
+                    if let Some(oco_leg_event) = oco_leg_event {
+                        handle_order_trade_event(
+                            &oco_leg_event.sender,
+                            &order_slicer,
+                            &oco_leg_event.algo_id,
+                            &trade,
+                        );
+                        if let Some(list_status) = oco_leg_event.list_status {
+                            oco_leg_event
+                                .sender
+                                .try_send(list_status)
+                                .unwrap_or_else(|err| {
+                                    eprintln!("Failed to send message: {:?}", err)
+                                });
                         }
+                        return Ok(());
                     }
-                    _ => (),
-                };
+
+                    let algo_contexts = algo_contexts.lock().unwrap();
+                    match algo_contexts.get(&trade.new_client_order_id) {
+                        Some((algo_id, algo_context)) => {
+                            handle_order_trade_event(algo_context, &order_slicer, algo_id, &trade);
+                        }
+                        None => {
+                            eprintln!(
+                                "No algorithm context found for new_client_order_id: {}",
+                                trade.new_client_order_id
+                            );
+                        }
+                    }
+                }
 
                 Ok(())
             });
@@ -256,150 +520,360 @@ pub(super) async fn run_my_actor(mut actor: MarketActor) {
         }
     });
 
+    let general = actor.general.clone();
+    let symbol_watchers = actor.symbol_watchers.clone();
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SYMBOL_STATUS_POLL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let symbols: Vec<String> = {
+                let mut symbol_watchers = symbol_watchers.lock().unwrap();
+                symbol_watchers.retain(|_, (_, algos)| {
+                    algos.retain(|(_, sender)| !sender.is_closed());
+                    !algos.is_empty()
+                });
+                symbol_watchers.keys().cloned().collect()
+            };
+            if symbols.is_empty() {
+                continue;
+            }
+
+            let general = general.clone();
+            let exchange_info = match task::spawn_blocking(move || {
+                general
+                    .client
+                    .get::<ExchangeInformation>(
+                        API::Spot(Spot::ExchangeInfo),
+                        Some(symbols_query(&symbols)),
+                    )
+                    .map_err(|e| e.to_string())
+            })
+            .await
+            {
+                Ok(Ok(exchange_info)) => exchange_info,
+                Ok(Err(e)) => {
+                    eprintln!("Failed to poll symbol statuses: Error: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("Symbol status polling task failed: {:?}", e);
+                    continue;
+                }
+            };
+
+            let mut symbol_watchers = symbol_watchers.lock().unwrap();
+
+            for symbol_info in exchange_info.symbols {
+                if let Some((last_status, algos)) = symbol_watchers.get_mut(&symbol_info.symbol) {
+                    let status = SymbolStatus::from_str(&symbol_info.status)
+                        .unwrap_or(SymbolStatus::Unknown);
+
+                    if status == *last_status {
+                        continue;
+                    }
+
+                    eprintln!(
+                        "Symbol {} changed trading status from {} to {}.",
+                        symbol_info.symbol, last_status, status
+                    );
+
+                    *last_status = status.clone();
+
+                    for (algo_id, sender) in algos.iter() {
+                        sender
+                            .try_send(MarketResponses::SymbolStatusChanged {
+                                algo_id: algo_id.clone(),
+                                symbol: symbol_info.symbol.clone(),
+                                status: status.clone(),
+                            })
+                            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+                    }
+                }
+            }
+        }
+    });
+
     while let Some(msg) = actor.receiver.recv().await {
         actor.handle(msg).await;
     }
 }
 
+fn dispatch_response(
+    algo: &mpsc::Sender<MarketResponses>,
+    order_slicer: &std::sync::Mutex<OrderSlicer>,
+    response: MarketResponses,
+) {
+    let responses = order_slicer.lock().unwrap().route(response);
+
+    for response in responses {
+        algo.try_send(response)
+            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+    }
+}
+
 fn handle_order_trade_event(
     algo: &mpsc::Sender<MarketResponses>,
-    algo_id: &String,
+    order_slicer: &std::sync::Mutex<OrderSlicer>,
+    algo_id: &str,
     event: &OrderTradeEvent,
 ) {
     let execution_type = ExecutionType::from_str(&event.execution_type);
 
     match execution_type {
         ExecutionType::New => {
-            algo.try_send(MarketResponses::CreateOrderAck {
-                order_id: event.new_client_order_id.clone(),
-                algo_id: algo_id.clone(),
-                symbol: event.symbol.clone(),
-                execution_status: execution_type,
-                order_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero()),
-                side: Side::from_str(&event.side).unwrap_or(Side::Buy),
-                order_type: OrderType::from_str(&event.order_type).unwrap_or(OrderType::Limit),
-                price: event.price.parse::<Decimal>().unwrap_or(Decimal::ZERO),
-                time_in_force: TIF::from_str(&event.time_in_force).unwrap_or(TIF::GTC),
-            })
-            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+            dispatch_response(
+                algo,
+                order_slicer,
+                MarketResponses::CreateOrderAck {
+                    order_id: event.new_client_order_id.clone(),
+                    algo_id: algo_id.to_owned(),
+                    symbol: event.symbol.clone(),
+                    execution_status: execution_type,
+                    order_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero()),
+                    side: Side::from_str(&event.side).unwrap_or(Side::Buy),
+                    order_type: OrderType::from_str(&event.order_type).unwrap_or(OrderType::Limit),
+                    price: event.price.parse::<Decimal>().unwrap_or(Decimal::ZERO),
+                    time_in_force: TIF::from_str(&event.time_in_force).unwrap_or(TIF::GTC),
+                },
+            );
         }
         ExecutionType::Trade => {
-            if event.order_status == String::from("FILLED") {
-                algo.try_send(MarketResponses::OrderFullyFilled {
+            if event.order_status == "FILLED" {
+                dispatch_response(
+                    algo,
+                    order_slicer,
+                    MarketResponses::OrderFullyFilled {
+                        order_id: event.new_client_order_id.clone(),
+                        algo_id: algo_id.to_owned(),
+                        symbol: event.symbol.clone(),
+                        execution_status: execution_type,
+                        quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero()),
+                        fill_price: event
+                            .price_last_filled_trade
+                            .parse::<Decimal>()
+                            .unwrap_or(Decimal::zero()),
+                        side: Side::from_str(&event.side).unwrap_or(Side::Buy),
+                        executed_quantity: event
+                            .qty_last_filled_trade
+                            .parse::<Decimal>()
+                            .unwrap_or(Decimal::zero()),
+                        cumulative_quantity: event
+                            .accumulated_qty_filled_trades
+                            .parse::<Decimal>()
+                            .unwrap_or(Decimal::zero()),
+                        leaves_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero())
+                            - event
+                                .qty_last_filled_trade
+                                .parse::<Decimal>()
+                                .unwrap_or(Decimal::zero()),
+                    },
+                );
+            } else {
+                dispatch_response(
+                    algo,
+                    order_slicer,
+                    MarketResponses::OrderPartiallyFilled {
+                        order_id: event.new_client_order_id.clone(),
+                        algo_id: algo_id.to_owned(),
+                        symbol: event.symbol.clone(),
+                        execution_status: execution_type,
+                        quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero()),
+                        fill_price: event
+                            .price_last_filled_trade
+                            .parse::<Decimal>()
+                            .unwrap_or(Decimal::zero()),
+                        side: Side::from_str(&event.side).unwrap_or(Side::Buy),
+                        executed_quantity: event
+                            .qty_last_filled_trade
+                            .parse::<Decimal>()
+                            .unwrap_or(Decimal::zero()),
+                        cumulative_quantity: event
+                            .accumulated_qty_filled_trades
+                            .parse::<Decimal>()
+                            .unwrap_or(Decimal::zero()),
+                        leaves_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero())
+                            - event
+                                .qty_last_filled_trade
+                                .parse::<Decimal>()
+                                .unwrap_or(Decimal::zero()),
+                    },
+                );
+            }
+        }
+        ExecutionType::Expired => {
+            dispatch_response(
+                algo,
+                order_slicer,
+                MarketResponses::OrderExpired {
                     order_id: event.new_client_order_id.clone(),
-                    algo_id: algo_id.clone(),
+                    algo_id: algo_id.to_owned(),
                     symbol: event.symbol.clone(),
                     execution_status: execution_type,
                     quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero()),
-                    fill_price: event
-                        .price_last_filled_trade
-                        .parse::<Decimal>()
-                        .unwrap_or(Decimal::zero()),
                     side: Side::from_str(&event.side).unwrap_or(Side::Buy),
                     executed_quantity: event
                         .qty_last_filled_trade
                         .parse::<Decimal>()
-                        .unwrap_or(Decimal::zero()),
+                        .unwrap_or(Decimal::ZERO),
                     cumulative_quantity: event
                         .accumulated_qty_filled_trades
                         .parse::<Decimal>()
                         .unwrap_or(Decimal::zero()),
-                    leaves_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero())
+                    leaves_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::ZERO)
                         - event
-                            .qty_last_filled_trade
+                            .accumulated_qty_filled_trades
                             .parse::<Decimal>()
-                            .unwrap_or(Decimal::zero()),
-                })
-                .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
-            } else {
-                algo.try_send(MarketResponses::OrderPartiallyFilled {
+                            .unwrap_or(Decimal::ZERO),
+                },
+            );
+        }
+        ExecutionType::Rejected => {
+            dispatch_response(
+                algo,
+                order_slicer,
+                MarketResponses::OrderRejected {
                     order_id: event.new_client_order_id.clone(),
-                    algo_id: algo_id.clone(),
+                    algo_id: algo_id.to_owned(),
                     symbol: event.symbol.clone(),
                     execution_status: execution_type,
-                    quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero()),
-                    fill_price: event
-                        .price_last_filled_trade
-                        .parse::<Decimal>()
-                        .unwrap_or(Decimal::zero()),
+                    order_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::ZERO),
                     side: Side::from_str(&event.side).unwrap_or(Side::Buy),
-                    executed_quantity: event
-                        .qty_last_filled_trade
-                        .parse::<Decimal>()
-                        .unwrap_or(Decimal::zero()),
+                    order_type: OrderType::from_str(&event.order_type).unwrap_or(OrderType::Limit),
+                    price: event.price.parse::<Decimal>().unwrap_or(Decimal::ZERO),
+                    rejection_reason: event.order_reject_reason.clone(),
+                    time_in_force: TIF::from_str(&event.time_in_force).unwrap_or(TIF::GTC),
+                },
+            );
+        }
+        ExecutionType::Canceled => {
+            dispatch_response(
+                algo,
+                order_slicer,
+                MarketResponses::OrderCanceled {
+                    order_id: event.new_client_order_id.clone(),
+                    algo_id: algo_id.to_owned(),
+                    symbol: event.symbol.clone(),
+                    execution_status: execution_type,
+                    quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::ZERO),
+                    side: Side::from_str(&event.side).unwrap(),
+                    executed_quantity: event.qty_last_filled_trade.parse::<Decimal>().unwrap(),
                     cumulative_quantity: event
                         .accumulated_qty_filled_trades
                         .parse::<Decimal>()
-                        .unwrap_or(Decimal::zero()),
-                    leaves_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero())
+                        .unwrap_or(Decimal::ZERO),
+                    leaves_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::ZERO)
                         - event
                             .qty_last_filled_trade
                             .parse::<Decimal>()
-                            .unwrap_or(Decimal::zero()),
-                })
-                .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
-            }
+                            .unwrap_or(Decimal::ZERO),
+                },
+            );
         }
-        ExecutionType::Expired => {
-            algo.try_send(MarketResponses::OrderExpired {
-                order_id: event.new_client_order_id.clone(),
-                algo_id: algo_id.clone(),
-                symbol: event.symbol.clone(),
-                execution_status: execution_type,
-                quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::zero()),
-                side: Side::from_str(&event.side).unwrap_or(Side::Buy),
-                executed_quantity: event
-                    .qty_last_filled_trade
-                    .parse::<Decimal>()
-                    .unwrap_or(Decimal::ZERO),
-                cumulative_quantity: event
-                    .accumulated_qty_filled_trades
-                    .parse::<Decimal>()
-                    .unwrap_or(Decimal::zero()),
-                leaves_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::ZERO)
-                    - event
-                        .accumulated_qty_filled_trades
-                        .parse::<Decimal>()
-                        .unwrap_or(Decimal::ZERO),
-            })
-            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Environment;
+
+    /// An actor whose REST requests are refused, so every order that reaches the exchange
+    /// is rejected.
+    async fn unreachable_market(receiver: mpsc::Receiver<MarketMessages>) -> MarketActor {
+        let config = binance::config::Config::default().set_rest_api_endpoint("http://127.0.0.1:1");
+        let (account, general) = task::spawn_blocking(move || {
+            let account: Account = Binance::new_with_config(None, None, &config);
+            let general: General = Binance::new_with_config(None, None, &config);
+            (account, general)
+        })
+        .await
+        .unwrap();
+
+        MarketActor {
+            receiver,
+            account,
+            general,
+            market_config: MarketConfig {
+                api_key: String::new(),
+                api_secret: String::new(),
+                environment: Environment::Testnet,
+            },
+            algo_contexts: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            symbol_watchers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            symbol_limits: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            order_slicer: Arc::new(std::sync::Mutex::new(OrderSlicer::default())),
+            oco_tracker: Arc::new(std::sync::Mutex::new(OcoTracker::default())),
         }
-        ExecutionType::Rejected => {
-            algo.try_send(MarketResponses::OrderRejected {
-                order_id: event.new_client_order_id.clone(),
-                algo_id: algo_id.clone(),
-                symbol: event.symbol.clone(),
-                execution_status: execution_type,
-                order_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::ZERO),
-                side: Side::from_str(&event.side).unwrap_or(Side::Buy),
-                order_type: OrderType::from_str(&event.order_type).unwrap_or(OrderType::Limit),
-                price: event.price.parse::<Decimal>().unwrap_or(Decimal::ZERO),
-                rejection_reason: event.order_reject_reason.clone(),
-                time_in_force: TIF::from_str(&event.time_in_force).unwrap_or(TIF::GTC),
-            })
-            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+    }
+
+    fn order(order_id: &str, quantity: Decimal) -> OrderRequest {
+        OrderRequest {
+            order_id: order_id.into(),
+            algo_id: "algo".into(),
+            symbol: "BTCUSDT".into(),
+            price: Decimal::new(100, 0),
+            quantity,
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            time_in_force: TIF::GTC,
         }
-        ExecutionType::Canceled => {
-            algo.try_send(MarketResponses::OrderCanceled {
-                order_id: event.new_client_order_id.clone(),
-                algo_id: algo_id.clone(),
-                symbol: event.symbol.clone(),
-                execution_status: execution_type,
-                quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::ZERO),
-                side: Side::from_str(&event.side).unwrap(),
-                executed_quantity: event.qty_last_filled_trade.parse::<Decimal>().unwrap(),
-                cumulative_quantity: event
-                    .accumulated_qty_filled_trades
-                    .parse::<Decimal>()
-                    .unwrap_or(Decimal::ZERO),
-                leaves_quantity: event.qty.parse::<Decimal>().unwrap_or(Decimal::ZERO)
-                    - event
-                        .qty_last_filled_trade
-                        .parse::<Decimal>()
-                        .unwrap_or(Decimal::ZERO),
+    }
+
+    #[tokio::test]
+    async fn a_failing_order_does_not_stop_the_others() {
+        let (_market_sender, market_receiver) = mpsc::channel(1);
+        let mut market = unreachable_market(market_receiver).await;
+        market.symbol_limits.lock().unwrap().insert(
+            "BTCUSDT".into(),
+            OrderLimits {
+                max_quantity: Some(Decimal::new(1, 0)),
+                step_size: Some(Decimal::new(1, 1)),
+                ..Default::default()
+            },
+        );
+
+        let (sender, mut receiver) = mpsc::channel(10);
+        market
+            .handle(MarketMessages::CreateOrders {
+                orders: vec![
+                    order("first", Decimal::new(5, 1)),
+                    // Too large for one order and can't be split into whole steps.
+                    order("second", Decimal::new(155, 2)),
+                    order("third", Decimal::new(5, 1)),
+                ],
+                sender,
             })
-            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+            .await;
+
+        let mut rejections = HashMap::new();
+        for _ in 0..3 {
+            match tokio::time::timeout(Duration::from_secs(10), receiver.recv()).await {
+                Ok(Some(MarketResponses::OrderRejected {
+                    order_id,
+                    rejection_reason,
+                    ..
+                })) => {
+                    rejections.insert(order_id, rejection_reason);
+                }
+                Ok(Some(response)) => panic!("unexpected response {}", response),
+                Ok(None) | Err(_) => panic!("expected a rejection for each order"),
+            }
         }
-        _ => (),
+
+        assert_eq!(
+            rejections["second"],
+            "quantity 1.55 is not a multiple of the step size 0.1"
+        );
+        // The others were still sent, and refused by the exchange.
+        assert!(rejections.contains_key("first"));
+        assert!(rejections.contains_key("third"));
+        let algo_contexts = market.algo_contexts.lock().unwrap();
+        assert!(algo_contexts.contains_key("first"));
+        assert!(!algo_contexts.contains_key("second"));
+        assert!(algo_contexts.contains_key("third"));
     }
 }
@@ -1,10 +1,15 @@
 use super::{
     market::MarketActor,
-    messages::{market_messages::MarketMessages, market_responses::MarketResponses},
+    messages::{
+        market_messages::MarketMessages,
+        market_responses::MarketResponses,
+        order_request::{OcoOrderRequest, OrderRequest},
+    },
+    simulated_market::SimulatedMarketActor,
 };
 use crate::{
     common_types::{order_types::OrderType, side::Side, time_in_force::TIF},
-    config::MarketConfig,
+    config::{Environment, MarketConfig},
 };
 use rust_decimal::Decimal;
 use tokio::{sync::mpsc, task::JoinHandle};
@@ -19,13 +24,27 @@ impl MarketHandle {
     pub async fn new(market_config: MarketConfig) -> (Self, JoinHandle<()>) {
         let (sender, receiver) = mpsc::channel(100);
 
-        let actor = MarketActor::new(market_config, receiver).await;
-
-        let handle = tokio::spawn(super::market::run_my_actor(actor));
+        let handle = match market_config.environment {
+            Environment::Simulator => {
+                let actor = SimulatedMarketActor::new(receiver);
+                tokio::spawn(super::simulated_market::run_simulated_actor(actor))
+            }
+            Environment::Testnet | Environment::Mainnet => {
+                let actor = MarketActor::new(market_config, receiver).await;
+                tokio::spawn(super::market::run_my_actor(actor))
+            }
+        };
 
         (Self { sender }, handle)
     }
 
+    /// A handle whose messages go to `sender`, for tests of what algorithms ask of the market.
+    #[cfg(test)]
+    pub(crate) fn with_sender(sender: mpsc::Sender<MarketMessages>) -> Self {
+        Self { sender }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_order(
         &self,
         symbol: String,
@@ -53,6 +72,18 @@ impl MarketHandle {
             .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
     }
 
+    pub fn create_orders(&self, orders: Vec<OrderRequest>, sender: mpsc::Sender<MarketResponses>) {
+        self.sender
+            .try_send(MarketMessages::CreateOrders { orders, sender })
+            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+    }
+
+    pub fn create_oco_order(&self, order: OcoOrderRequest, sender: mpsc::Sender<MarketResponses>) {
+        self.sender
+            .try_send(MarketMessages::CreateOcoOrder { order, sender })
+            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+    }
+
     pub fn get_symbol_info(
         &self,
         symbol: String,
@@ -67,4 +98,10 @@ impl MarketHandle {
             })
             .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
     }
+
+    pub fn stop_watching_symbol(&self, symbol: String, algo_id: String) {
+        self.sender
+            .try_send(MarketMessages::StopWatchingSymbol { symbol, algo_id })
+            .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+    }
 }
@@ -1,4 +1,10 @@
-use super::{market_handle::MarketHandle, messages::market_responses::MarketResponses};
+use super::{
+    market_handle::MarketHandle,
+    messages::{
+        market_responses::MarketResponses,
+        order_request::{OcoOrderRequest, OrderRequest},
+    },
+};
 use crate::common_types::{order_types::OrderType, side::Side, time_in_force::TIF};
 use rust_decimal::Decimal;
 use tokio::sync::mpsc;
@@ -38,8 +44,8 @@ impl MarketService {
     {
         self.create_order(
             symbol.into(),
-            price.into(),
-            quantity.into(),
+            price,
+            quantity,
             side.clone(),
             OrderType::Limit,
             TIF::IOC,
@@ -61,8 +67,8 @@ impl MarketService {
 
         self.market_handle.create_order(
             symbol.into(),
-            price.into(),
-            quantity.into(),
+            price,
+            quantity,
             side,
             order_type,
             time_inforce,
@@ -72,6 +78,83 @@ impl MarketService {
         );
     }
 
+    /// Places one order per `(price, quantity)` pair and returns the generated order ids in
+    /// the same order. The market places each order with its own request, so one can be
+    /// rejected while the others are placed.
+    pub fn create_orders<Symbol>(
+        &self,
+        symbol: Symbol,
+        side: &Side,
+        order_type: OrderType,
+        time_in_force: TIF,
+        orders: &[(Decimal, Decimal)],
+    ) -> Vec<String>
+    where
+        Symbol: Into<String>,
+    {
+        let symbol = symbol.into();
+
+        let orders: Vec<OrderRequest> = orders
+            .iter()
+            .map(|(price, quantity)| OrderRequest {
+                order_id: Uuid::new_v4().to_string(),
+                algo_id: self.algo_id.clone(),
+                symbol: symbol.clone(),
+                price: *price,
+                quantity: *quantity,
+                side: side.clone(),
+                order_type: order_type.clone(),
+                time_in_force: time_in_force.clone(),
+            })
+            .collect();
+
+        let order_ids = orders.iter().map(|order| order.order_id.clone()).collect();
+
+        self.market_handle
+            .create_orders(orders, self.meesage_sender.clone());
+
+        order_ids
+    }
+
+    /// Places a limit order at `price` linked with a stop-loss-limit order triggered at
+    /// `stop_price`; when one of them executes the exchange cancels the other.
+    /// Returns the list id reported back in `MarketResponses::OcoListStatus`, followed by the
+    /// order ids of the limit and stop legs, whose execution reports are delivered as well.
+    pub fn create_oco_order<Symbol>(
+        &self,
+        symbol: Symbol,
+        side: &Side,
+        quantity: Decimal,
+        price: Decimal,
+        stop_price: Decimal,
+        stop_limit_price: Decimal,
+    ) -> (String, String, String)
+    where
+        Symbol: Into<String>,
+    {
+        let list_id = Uuid::new_v4().to_string();
+        let limit_order_id = Uuid::new_v4().to_string();
+        let stop_order_id = Uuid::new_v4().to_string();
+
+        self.market_handle.create_oco_order(
+            OcoOrderRequest {
+                list_id: list_id.clone(),
+                limit_order_id: limit_order_id.clone(),
+                stop_order_id: stop_order_id.clone(),
+                algo_id: self.algo_id.clone(),
+                symbol: symbol.into(),
+                side: side.clone(),
+                quantity,
+                price,
+                stop_price,
+                stop_limit_price,
+            },
+            self.meesage_sender.clone(),
+        );
+
+        (list_id, limit_order_id, stop_order_id)
+    }
+
     pub fn get_symbol_info<Symbol>(&self, symbol: Symbol)
     where
         Symbol: Into<String>,
@@ -82,4 +165,65 @@ impl MarketService {
             self.meesage_sender.clone(),
         );
     }
+
+    /// Stops the trading status updates `get_symbol_info` started, for algorithms that
+    /// have finished.
+    pub fn stop_watching_symbol<Symbol>(&self, symbol: Symbol)
+    where
+        Symbol: Into<String>,
+    {
+        self.market_handle
+            .stop_watching_symbol(symbol.into(), self.algo_id.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::messages::market_messages::MarketMessages;
+
+    #[test]
+    fn create_orders_sends_one_order_per_price_and_quantity() {
+        let (market_sender, mut market_receiver) = mpsc::channel(1);
+        let (response_sender, _response_receiver) = mpsc::channel(1);
+        let service = MarketService::new(
+            &MarketHandle::with_sender(market_sender),
+            &response_sender,
+            "algo",
+        );
+
+        let prices_and_quantities = [
+            (Decimal::new(100, 0), Decimal::new(1, 0)),
+            (Decimal::new(101, 0), Decimal::new(2, 0)),
+            (Decimal::new(102, 0), Decimal::new(3, 0)),
+        ];
+        let order_ids = service.create_orders(
+            "BTCUSDT",
+            &Side::Buy,
+            OrderType::Limit,
+            TIF::GTC,
+            &prices_and_quantities,
+        );
+
+        let Ok(MarketMessages::CreateOrders { orders, .. }) = market_receiver.try_recv() else {
+            panic!("expected a single CreateOrders message");
+        };
+        assert!(market_receiver.try_recv().is_err());
+
+        assert_eq!(orders.len(), prices_and_quantities.len());
+        for ((order, (price, quantity)), order_id) in
+            orders.iter().zip(prices_and_quantities).zip(&order_ids)
+        {
+            assert_eq!(&order.order_id, order_id);
+            assert_eq!(order.algo_id, "algo");
+            assert_eq!(order.symbol, "BTCUSDT");
+            assert_eq!((order.price, order.quantity), (price, quantity));
+            assert!(matches!(order.side, Side::Buy));
+        }
+
+        let mut unique_ids = order_ids.clone();
+        unique_ids.sort();
+        unique_ids.dedup();
+        assert_eq!(unique_ids.len(), order_ids.len());
+    }
 }
@@ -13,6 +13,7 @@ pub enum ExecutionType {
 }
 
 impl ExecutionType {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &str) -> Self {
         match input {
             "NEW" => ExecutionType::New,
@@ -0,0 +1,19 @@
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListStatus {
+    Executing,
+    AllDone,
+    Reject,
+}
+
+impl fmt::Display for ListStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let variant_str = match self {
+            ListStatus::Executing => "EXECUTING",
+            ListStatus::AllDone => "ALL_DONE",
+            ListStatus::Reject => "REJECT",
+        };
+        write!(f, "{}", variant_str)
+    }
+}
@@ -1,4 +1,7 @@
-use super::market_responses::MarketResponses;
+use super::{
+    market_responses::MarketResponses,
+    order_request::{OcoOrderRequest, OrderRequest},
+};
 use crate::common_types::{order_types::OrderType, side::Side, time_in_force::TIF};
 use rust_decimal::Decimal;
 use tokio::sync::mpsc;
@@ -9,6 +12,10 @@ pub enum MarketMessages {
         algo_id: String,
         sender: mpsc::Sender<MarketResponses>,
     },
+    StopWatchingSymbol {
+        symbol: String,
+        algo_id: String,
+    },
     CreateOrder {
         symbol: String,
         price: Decimal,
@@ -20,4 +27,12 @@ pub enum MarketMessages {
         order_id: String,
         algo_id: String,
     },
+    CreateOrders {
+        orders: Vec<OrderRequest>,
+        sender: mpsc::Sender<MarketResponses>,
+    },
+    CreateOcoOrder {
+        order: OcoOrderRequest,
+        sender: mpsc::Sender<MarketResponses>,
+    },
 }
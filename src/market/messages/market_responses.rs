@@ -1,5 +1,7 @@
-use super::execution_type::ExecutionType;
-use crate::common_types::{order_types::OrderType, side::Side, time_in_force::TIF};
+use super::{execution_type::ExecutionType, list_status::ListStatus};
+use crate::common_types::{
+    order_types::OrderType, side::Side, symbol_status::SymbolStatus, time_in_force::TIF,
+};
 use core::fmt;
 use rust_decimal::Decimal;
 
@@ -13,6 +15,12 @@ pub enum MarketResponses {
         max_price: Option<Decimal>,
         tick_size: Option<Decimal>,
         min_amount: Option<Decimal>,
+        status: Option<SymbolStatus>,
+    },
+    SymbolStatusChanged {
+        algo_id: String,
+        symbol: String,
+        status: SymbolStatus,
     },
     CreateOrderAck {
         order_id: String,
@@ -72,6 +80,20 @@ pub enum MarketResponses {
         rejection_reason: String,
         time_in_force: TIF,
     },
+    /// Part of an order placed as several child orders was rejected while the rest works
+    /// on: `rejected_quantity` will never fill and `leaves_quantity` is still working.
+    OrderPartiallyRejected {
+        order_id: String,
+        algo_id: String,
+        symbol: String,
+        execution_status: ExecutionType,
+        quantity: Decimal,
+        side: Side,
+        rejected_quantity: Decimal,
+        cumulative_quantity: Decimal,
+        leaves_quantity: Decimal,
+        rejection_reason: String,
+    },
     OrderCanceled {
         order_id: String,
         algo_id: String,
@@ -83,6 +105,20 @@ pub enum MarketResponses {
         cumulative_quantity: Decimal,
         leaves_quantity: Decimal,
     },
+    OcoListStatus {
+        list_id: String,
+        algo_id: String,
+        symbol: String,
+        list_status: ListStatus,
+        limit_order_id: String,
+        stop_order_id: String,
+    },
+    OcoOrderRejected {
+        list_id: String,
+        algo_id: String,
+        symbol: String,
+        rejection_reason: String,
+    },
 }
 
 macro_rules! format_optional {
@@ -106,10 +142,11 @@ impl fmt::Display for MarketResponses {
                 max_price,
                 tick_size,
                 min_amount,
+                status,
             } => {
                 write!(
                     f,
-                    "SymbolInformation {{ algo_id: {}, min_quantity: {}, max_quantity: {}, lot_size: {}, min_price: {}, max_price: {}, tick_size: {}, min_amount{} }}",
+                    "SymbolInformation {{ algo_id: {}, min_quantity: {}, max_quantity: {}, lot_size: {}, min_price: {}, max_price: {}, tick_size: {}, min_amount{}, status: {} }}",
                     algo_id,
                     format_optional!(min_quantity),
                     format_optional!(max_quantity),
@@ -118,6 +155,18 @@ impl fmt::Display for MarketResponses {
                     format_optional!(max_price),
                     format_optional!(tick_size),
                     format_optional!(min_amount),
+                    format_optional!(status),
+                )
+            }
+            MarketResponses::SymbolStatusChanged {
+                algo_id,
+                symbol,
+                status,
+            } => {
+                write!(
+                    f,
+                    "SymbolStatusChanged {{ algo_id: {}, symbol: {}, status: {} }}",
+                    algo_id, symbol, status
                 )
             }
             MarketResponses::CreateOrderAck {
@@ -208,6 +257,24 @@ impl fmt::Display for MarketResponses {
                     order_id, algo_id, symbol, execution_status, order_quantity, price, side,time_in_force,order_type, rejection_reason
                 )
             }
+            MarketResponses::OrderPartiallyRejected {
+                order_id,
+                algo_id,
+                symbol,
+                execution_status,
+                quantity,
+                side,
+                rejected_quantity,
+                cumulative_quantity,
+                leaves_quantity,
+                rejection_reason,
+            } => {
+                write!(
+                    f,
+                    "OrderPartiallyRejected {{ order_id: {}, algo_id: {}, symbol: {}, execution_status: {}, quantity: {}, side: {}, rejected_quantity: {}, cumulative_quantity: {}, leaves_quantity: {}, rejection_reason: {} }}",
+                    order_id, algo_id, symbol, execution_status, quantity, side, rejected_quantity, cumulative_quantity, leaves_quantity, rejection_reason
+                )
+            }
             MarketResponses::OrderCanceled {
                 order_id,
                 algo_id,
@@ -225,6 +292,32 @@ impl fmt::Display for MarketResponses {
                     order_id, algo_id, symbol, execution_status, quantity, side, executed_quantity, cumulative_quantity, leaves_quantity
                 )
             }
+            MarketResponses::OcoListStatus {
+                list_id,
+                algo_id,
+                symbol,
+                list_status,
+                limit_order_id,
+                stop_order_id,
+            } => {
+                write!(
+                    f,
+                    "OcoListStatus {{ list_id: {}, algo_id: {}, symbol: {}, list_status: {}, limit_order_id: {}, stop_order_id: {} }}",
+                    list_id, algo_id, symbol, list_status, limit_order_id, stop_order_id
+                )
+            }
+            MarketResponses::OcoOrderRejected {
+                list_id,
+                algo_id,
+                symbol,
+                rejection_reason,
+            } => {
+                write!(
+                    f,
+                    "OcoOrderRejected {{ list_id: {}, algo_id: {}, symbol: {}, rejection_reason: {} }}",
+                    list_id, algo_id, symbol, rejection_reason
+                )
+            }
         }
     }
 }
@@ -233,12 +326,32 @@ impl MarketResponses {
     pub fn algo_id(&self) -> &str {
         match self {
             MarketResponses::SymbolInformation { algo_id, .. }
+            | MarketResponses::SymbolStatusChanged { algo_id, .. }
             | MarketResponses::CreateOrderAck { algo_id, .. }
             | MarketResponses::OrderPartiallyFilled { algo_id, .. }
             | MarketResponses::OrderFullyFilled { algo_id, .. }
             | MarketResponses::OrderExpired { algo_id, .. }
             | MarketResponses::OrderRejected { algo_id, .. }
-            | MarketResponses::OrderCanceled { algo_id, .. } => algo_id,
+            | MarketResponses::OrderPartiallyRejected { algo_id, .. }
+            | MarketResponses::OrderCanceled { algo_id, .. }
+            | MarketResponses::OcoListStatus { algo_id, .. }
+            | MarketResponses::OcoOrderRejected { algo_id, .. } => algo_id,
+        }
+    }
+
+    pub fn order_id(&self) -> Option<&str> {
+        match self {
+            MarketResponses::SymbolInformation { .. }
+            | MarketResponses::SymbolStatusChanged { .. }
+            | MarketResponses::OcoListStatus { .. }
+            | MarketResponses::OcoOrderRejected { .. } => None,
+            MarketResponses::CreateOrderAck { order_id, .. }
+            | MarketResponses::OrderPartiallyFilled { order_id, .. }
+            | MarketResponses::OrderFullyFilled { order_id, .. }
+            | MarketResponses::OrderExpired { order_id, .. }
+            | MarketResponses::OrderRejected { order_id, .. }
+            | MarketResponses::OrderPartiallyRejected { order_id, .. }
+            | MarketResponses::OrderCanceled { order_id, .. } => Some(order_id),
         }
     }
 }
@@ -1,3 +1,5 @@
 pub mod execution_type;
+pub mod list_status;
 pub mod market_messages;
 pub mod market_responses;
+pub mod order_request;
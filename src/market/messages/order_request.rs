@@ -0,0 +1,78 @@
+use crate::common_types::{order_types::OrderType, side::Side, time_in_force::TIF};
+use core::fmt;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone)]
+pub struct OrderRequest {
+    pub order_id: String,
+    pub algo_id: String,
+    pub symbol: String,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub side: Side,
+    pub order_type: OrderType,
+    pub time_in_force: TIF,
+}
+
+impl OrderRequest {
+    pub fn child<OrderId>(&self, order_id: OrderId, quantity: Decimal) -> Self
+    where
+        OrderId: Into<String>,
+    {
+        Self {
+            order_id: order_id.into(),
+            quantity,
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for OrderRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "OrderRequest {{ order_id: {}, algo_id: {}, symbol: {}, price: {}, quantity: {}, side: {}, order_type: {}, time_in_force: {} }}",
+            self.order_id,
+            self.algo_id,
+            self.symbol,
+            self.price,
+            self.quantity,
+            self.side,
+            self.order_type,
+            self.time_in_force
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OcoOrderRequest {
+    pub list_id: String,
+    pub limit_order_id: String,
+    pub stop_order_id: String,
+    pub algo_id: String,
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub stop_price: Decimal,
+    pub stop_limit_price: Decimal,
+}
+
+impl fmt::Display for OcoOrderRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "OcoOrderRequest {{ list_id: {}, limit_order_id: {}, stop_order_id: {}, algo_id: {}, symbol: {}, side: {}, quantity: {}, price: {}, stop_price: {}, stop_limit_price: {} }}",
+            self.list_id,
+            self.limit_order_id,
+            self.stop_order_id,
+            self.algo_id,
+            self.symbol,
+            self.side,
+            self.quantity,
+            self.price,
+            self.stop_price,
+            self.stop_limit_price
+        )
+    }
+}
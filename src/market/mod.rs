@@ -1,4 +1,8 @@
+#[allow(clippy::module_inception)]
 pub mod market;
 pub mod market_handle;
 pub mod market_service;
 pub mod messages;
+mod oco_tracker;
+mod order_slicer;
+mod simulated_market;
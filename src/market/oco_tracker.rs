@@ -0,0 +1,258 @@
+use super::messages::{
+    execution_type::ExecutionType, list_status::ListStatus, market_responses::MarketResponses,
+    order_request::OcoOrderRequest,
+};
+use binance::model::OrderTradeEvent;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+#[derive(Debug, PartialEq)]
+enum LegState {
+    PendingAck,
+    Working,
+    Done,
+    Rejected,
+}
+
+struct OcoOrder {
+    list_id: String,
+    algo_id: String,
+    symbol: String,
+    limit_order_id: String,
+    stop_order_id: String,
+    sender: mpsc::Sender<MarketResponses>,
+    legs: HashMap<String, LegState>,
+    list_status: Option<ListStatus>,
+}
+
+impl OcoOrder {
+    fn list_status_response(&self, list_status: ListStatus) -> MarketResponses {
+        MarketResponses::OcoListStatus {
+            list_id: self.list_id.clone(),
+            algo_id: self.algo_id.clone(),
+            symbol: self.symbol.clone(),
+            list_status,
+            limit_order_id: self.limit_order_id.clone(),
+            stop_order_id: self.stop_order_id.clone(),
+        }
+    }
+}
+
+/// An execution report of an OCO leg: the report itself goes to the owning algorithm, followed
+/// by the list status when the report changed it.
+pub(super) struct OcoLegEvent {
+    pub algo_id: String,
+    pub sender: mpsc::Sender<MarketResponses>,
+    pub list_status: Option<MarketResponses>,
+}
+
+#[derive(Default)]
+pub(super) struct OcoTracker {
+    oco_orders: HashMap<String, OcoOrder>,
+    lists: HashMap<String, String>,
+}
+
+impl OcoTracker {
+    pub fn register(&mut self, order: &OcoOrderRequest, sender: mpsc::Sender<MarketResponses>) {
+        for leg_id in [&order.limit_order_id, &order.stop_order_id] {
+            self.lists.insert(leg_id.clone(), order.list_id.clone());
+        }
+
+        self.oco_orders.insert(
+            order.list_id.clone(),
+            OcoOrder {
+                list_id: order.list_id.clone(),
+                algo_id: order.algo_id.clone(),
+                symbol: order.symbol.clone(),
+                limit_order_id: order.limit_order_id.clone(),
+                stop_order_id: order.stop_order_id.clone(),
+                sender,
+                legs: HashMap::from([
+                    (order.limit_order_id.clone(), LegState::PendingAck),
+                    (order.stop_order_id.clone(), LegState::PendingAck),
+                ]),
+                list_status: None,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, list_id: &str) {
+        if let Some(oco_order) = self.oco_orders.remove(list_id) {
+            for leg_id in oco_order.legs.keys() {
+                self.lists.remove(leg_id);
+            }
+        }
+    }
+
+    /// None when the order is not a leg of a tracked OCO order. Derives the list status from
+    /// the execution reports of the legs, as binance-rs does not deliver the user stream's
+    /// listStatus events. The exchange rejects both legs of a rejected list.
+    pub fn on_order_trade_event(&mut self, event: &OrderTradeEvent) -> Option<OcoLegEvent> {
+        let list_id = self.lists.get(&event.new_client_order_id)?.clone();
+        let oco_order = self.oco_orders.get_mut(&list_id)?;
+
+        let mut leg_event = OcoLegEvent {
+            algo_id: oco_order.algo_id.clone(),
+            sender: oco_order.sender.clone(),
+            list_status: None,
+        };
+
+        let leg_state = match ExecutionType::from_str(&event.execution_type) {
+            ExecutionType::New => LegState::Working,
+            ExecutionType::Trade if event.order_status == "FILLED" => LegState::Done,
+            ExecutionType::Trade => LegState::Working,
+            ExecutionType::Canceled | ExecutionType::Expired => LegState::Done,
+            ExecutionType::Rejected => LegState::Rejected,
+            _ => return Some(leg_event),
+        };
+        oco_order
+            .legs
+            .insert(event.new_client_order_id.clone(), leg_state);
+
+        let finished = oco_order
+            .legs
+            .values()
+            .all(|leg_state| matches!(leg_state, LegState::Done | LegState::Rejected));
+
+        let list_status = if oco_order
+            .legs
+            .values()
+            .any(|leg_state| *leg_state == LegState::Rejected)
+        {
+            Some(ListStatus::Reject)
+        } else if finished {
+            Some(ListStatus::AllDone)
+        } else if oco_order
+            .legs
+            .values()
+            .all(|leg_state| *leg_state != LegState::PendingAck)
+        {
+            Some(ListStatus::Executing)
+        } else {
+            None
+        };
+
+        if let Some(list_status) = list_status {
+            if oco_order.list_status.as_ref() != Some(&list_status) {
+                leg_event.list_status = Some(oco_order.list_status_response(list_status.clone()));
+                oco_order.list_status = Some(list_status);
+            }
+        }
+
+        if finished {
+            self.remove(&list_id);
+        }
+
+        Some(leg_event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::side::Side;
+    use rust_decimal::Decimal;
+
+    fn oco_order() -> OcoOrderRequest {
+        OcoOrderRequest {
+            list_id: "list".to_owned(),
+            limit_order_id: "limit".to_owned(),
+            stop_order_id: "stop".to_owned(),
+            algo_id: "algo".to_owned(),
+            symbol: "BTCUSDT".to_owned(),
+            side: Side::Sell,
+            quantity: Decimal::ONE,
+            price: Decimal::from(110),
+            stop_price: Decimal::from(90),
+            stop_limit_price: Decimal::from(89),
+        }
+    }
+
+    fn tracker() -> (OcoTracker, mpsc::Receiver<MarketResponses>) {
+        let (sender, receiver) = mpsc::channel(10);
+        let mut tracker = OcoTracker::default();
+        tracker.register(&oco_order(), sender);
+        (tracker, receiver)
+    }
+
+    fn event(order_id: &str, execution_type: &str, order_status: &str) -> OrderTradeEvent {
+        serde_json::from_value(serde_json::json!({
+            "e": "executionReport", "E": 0, "s": "BTCUSDT", "c": order_id, "S": "SELL",
+            "o": "LIMIT_MAKER", "f": "GTC", "q": "1", "p": "110", "x": execution_type,
+            "X": order_status, "r": "NONE", "i": 1, "l": "0.4", "z": "0.4", "L": "110",
+            "n": "0", "T": 0, "t": 1, "m": false
+        }))
+        .unwrap()
+    }
+
+    /// The list status the event produced, or None when it left the list status unchanged.
+    fn list_status(tracker: &mut OcoTracker, event: OrderTradeEvent) -> Option<ListStatus> {
+        let leg_event = tracker.on_order_trade_event(&event).unwrap();
+        assert_eq!(leg_event.algo_id, "algo");
+
+        match leg_event.list_status? {
+            MarketResponses::OcoListStatus { list_status, .. } => Some(list_status),
+            response => panic!("unexpected response {}", response),
+        }
+    }
+
+    #[test]
+    fn one_leg_filling_and_the_other_expiring_finishes_the_list() {
+        let (mut tracker, _receiver) = tracker();
+
+        assert_eq!(
+            list_status(&mut tracker, event("limit", "NEW", "NEW")),
+            None
+        );
+        assert_eq!(
+            list_status(&mut tracker, event("stop", "NEW", "NEW")),
+            Some(ListStatus::Executing)
+        );
+        assert_eq!(
+            list_status(&mut tracker, event("limit", "TRADE", "FILLED")),
+            None
+        );
+        assert_eq!(
+            list_status(&mut tracker, event("stop", "EXPIRED", "EXPIRED")),
+            Some(ListStatus::AllDone)
+        );
+
+        assert!(tracker
+            .on_order_trade_event(&event("stop", "EXPIRED", "EXPIRED"))
+            .is_none());
+    }
+
+    #[test]
+    fn partial_fills_are_forwarded_without_changing_the_list_status() {
+        let (mut tracker, _receiver) = tracker();
+
+        list_status(&mut tracker, event("limit", "NEW", "NEW"));
+        list_status(&mut tracker, event("stop", "NEW", "NEW"));
+
+        assert_eq!(
+            list_status(&mut tracker, event("limit", "TRADE", "PARTIALLY_FILLED")),
+            None
+        );
+        assert!(tracker
+            .on_order_trade_event(&event("limit", "TRADE", "PARTIALLY_FILLED"))
+            .is_some());
+    }
+
+    #[test]
+    fn rejected_legs_reject_the_list() {
+        let (mut tracker, _receiver) = tracker();
+
+        assert_eq!(
+            list_status(&mut tracker, event("limit", "REJECTED", "REJECTED")),
+            Some(ListStatus::Reject)
+        );
+        assert_eq!(
+            list_status(&mut tracker, event("stop", "REJECTED", "REJECTED")),
+            None
+        );
+
+        assert!(tracker
+            .on_order_trade_event(&event("limit", "REJECTED", "REJECTED"))
+            .is_none());
+    }
+}
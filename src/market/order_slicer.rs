@@ -0,0 +1,774 @@
+use super::messages::{
+    execution_type::ExecutionType, market_responses::MarketResponses, order_request::OrderRequest,
+};
+use crate::common_types::order_types::OrderType;
+use rust_decimal::{
+    prelude::{ToPrimitive, Zero},
+    Decimal,
+};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct OrderLimits {
+    pub max_quantity: Option<Decimal>,
+    pub market_max_quantity: Option<Decimal>,
+    pub min_quantity: Option<Decimal>,
+    pub step_size: Option<Decimal>,
+    pub min_notional: Option<Decimal>,
+}
+
+impl OrderLimits {
+    pub fn max_quantity(&self, order_type: &OrderType) -> Option<Decimal> {
+        let max_quantity = match order_type {
+            OrderType::Market => self.market_max_quantity.or(self.max_quantity),
+            OrderType::Limit | OrderType::StopLossLimit => self.max_quantity,
+        };
+
+        max_quantity.filter(|max_quantity| !max_quantity.is_zero())
+    }
+
+    /// The quantities of the child orders an order is placed as, a single one when it fits
+    /// the maximum quantity. Fails when a child would break the symbol's filters, so the
+    /// parent is rejected up front instead of quietly filling less than asked.
+    pub fn split(&self, order: &OrderRequest) -> Result<Vec<Decimal>, String> {
+        let Some(max_quantity) = self.max_quantity(&order.order_type) else {
+            return Ok(vec![order.quantity]);
+        };
+        if order.quantity <= max_quantity {
+            return Ok(vec![order.quantity]);
+        }
+
+        let step_size = self.step_size.filter(|step_size| !step_size.is_zero());
+        if let Some(step_size) = step_size {
+            if !(order.quantity % step_size).is_zero() {
+                return Err(format!(
+                    "quantity {} is not a multiple of the step size {}",
+                    order.quantity, step_size
+                ));
+            }
+        }
+
+        let slices = split_quantity(order.quantity, max_quantity, step_size);
+
+        for slice in &slices {
+            if let Some(min_quantity) = self.min_quantity {
+                if *slice < min_quantity {
+                    return Err(format!(
+                        "child quantity {} is below the minimum quantity {}",
+                        slice, min_quantity
+                    ));
+                }
+            }
+            if let Some(min_notional) = self.min_notional {
+                if !order.price.is_zero() && *slice * order.price < min_notional {
+                    return Err(format!(
+                        "child notional {} is below the minimum notional {}",
+                        *slice * order.price,
+                        min_notional
+                    ));
+                }
+            }
+        }
+
+        Ok(slices)
+    }
+}
+
+/// Splits `quantity` into the fewest slices of at most `max_quantity`, as equal as the step
+/// size allows: slices differ by at most one step, so none is left as a small remainder.
+pub(super) fn split_quantity(
+    quantity: Decimal,
+    max_quantity: Decimal,
+    step_size: Option<Decimal>,
+) -> Vec<Decimal> {
+    if max_quantity <= Decimal::zero() || quantity <= max_quantity {
+        return vec![quantity];
+    }
+
+    let count = (quantity / max_quantity).ceil();
+    let step_size = step_size.unwrap_or(Decimal::zero());
+
+    let slice = if step_size.is_zero() {
+        quantity / count
+    } else {
+        (quantity / count / step_size).floor() * step_size
+    };
+    let mut slices = vec![slice; count.to_usize().unwrap_or(1)];
+
+    // Hands the steps the rounding left over to the first slices, and any rounding below a
+    // step to the last one.
+    let mut remaining = quantity - slice * count;
+    if !step_size.is_zero() {
+        for slice in slices.iter_mut() {
+            if remaining < step_size {
+                break;
+            }
+            *slice += step_size;
+            remaining -= step_size;
+        }
+    }
+    if let Some(last) = slices.last_mut() {
+        *last += remaining;
+    }
+
+    slices
+}
+
+#[derive(Debug, PartialEq)]
+enum ChildState {
+    PendingAck,
+    Working,
+    Rejected,
+    Done,
+}
+
+struct ChildOrder {
+    quantity: Decimal,
+    cumulative_quantity: Decimal,
+    state: ChildState,
+}
+
+pub(super) struct SlicedOrder {
+    parent: OrderRequest,
+    children: HashMap<String, ChildOrder>,
+    acked_quantity: Decimal,
+    rejected_quantity: Decimal,
+    cumulative_quantity: Decimal,
+    rejection_reason: String,
+    ack_sent: bool,
+    finished: bool,
+    deferred: Vec<MarketResponses>,
+}
+
+impl SlicedOrder {
+    pub fn new(parent: OrderRequest, children: &[OrderRequest]) -> Self {
+        Self {
+            parent,
+            children: children
+                .iter()
+                .map(|child| {
+                    (
+                        child.order_id.clone(),
+                        ChildOrder {
+                            quantity: child.quantity,
+                            cumulative_quantity: Decimal::zero(),
+                            state: ChildState::PendingAck,
+                        },
+                    )
+                })
+                .collect(),
+            acked_quantity: Decimal::zero(),
+            rejected_quantity: Decimal::zero(),
+            cumulative_quantity: Decimal::zero(),
+            rejection_reason: String::new(),
+            ack_sent: false,
+            finished: false,
+            deferred: Vec::new(),
+        }
+    }
+
+    fn all_children_done(&self) -> bool {
+        self.children
+            .values()
+            .all(|child| matches!(child.state, ChildState::Done | ChildState::Rejected))
+    }
+
+    fn leaves_quantity(&self) -> Decimal {
+        self.acked_quantity - self.cumulative_quantity
+    }
+
+    fn on_response(&mut self, response: MarketResponses) -> Vec<MarketResponses> {
+        match response {
+            MarketResponses::CreateOrderAck { order_id, .. } => {
+                if let Some(child) = self.children.get_mut(&order_id) {
+                    child.state = ChildState::Working;
+                    self.acked_quantity += child.quantity;
+                }
+                self.on_child_acknowledged()
+            }
+            MarketResponses::OrderRejected {
+                order_id,
+                rejection_reason,
+                ..
+            } => {
+                if let Some(child) = self.children.get_mut(&order_id) {
+                    child.state = ChildState::Rejected;
+                    self.rejected_quantity += child.quantity;
+                }
+                self.rejection_reason = rejection_reason;
+                self.on_child_acknowledged()
+            }
+            response if !self.ack_sent => {
+                self.deferred.push(response);
+                Vec::new()
+            }
+            response => self.on_execution(response),
+        }
+    }
+
+    fn on_child_acknowledged(&mut self) -> Vec<MarketResponses> {
+        if self.ack_sent
+            || self
+                .children
+                .values()
+                .any(|child| child.state == ChildState::PendingAck)
+        {
+            return Vec::new();
+        }
+
+        let parent = &self.parent;
+
+        if self.acked_quantity.is_zero() {
+            self.finished = true;
+
+            return vec![MarketResponses::OrderRejected {
+                order_id: parent.order_id.clone(),
+                algo_id: parent.algo_id.clone(),
+                symbol: parent.symbol.clone(),
+                execution_status: ExecutionType::Rejected,
+                order_quantity: parent.quantity,
+                price: parent.price,
+                side: parent.side.clone(),
+                order_type: parent.order_type.clone(),
+                rejection_reason: self.rejection_reason.clone(),
+                time_in_force: parent.time_in_force.clone(),
+            }];
+        }
+
+        self.ack_sent = true;
+
+        // The parent keeps its requested quantity; rejected children are reported as a
+        // partial reject right after the ack, so their reason is not lost.
+        let mut responses = vec![MarketResponses::CreateOrderAck {
+            order_id: parent.order_id.clone(),
+            algo_id: parent.algo_id.clone(),
+            symbol: parent.symbol.clone(),
+            execution_status: ExecutionType::New,
+            order_quantity: parent.quantity,
+            price: parent.price,
+            side: parent.side.clone(),
+            order_type: parent.order_type.clone(),
+            time_in_force: parent.time_in_force.clone(),
+        }];
+
+        if !self.rejected_quantity.is_zero() {
+            responses.push(MarketResponses::OrderPartiallyRejected {
+                order_id: parent.order_id.clone(),
+                algo_id: parent.algo_id.clone(),
+                symbol: parent.symbol.clone(),
+                execution_status: ExecutionType::Rejected,
+                quantity: parent.quantity,
+                side: parent.side.clone(),
+                rejected_quantity: self.rejected_quantity,
+                cumulative_quantity: self.cumulative_quantity,
+                leaves_quantity: self.leaves_quantity(),
+                rejection_reason: self.rejection_reason.clone(),
+            });
+        }
+
+        for response in std::mem::take(&mut self.deferred) {
+            responses.extend(self.on_execution(response));
+        }
+
+        responses
+    }
+
+    fn on_execution(&mut self, response: MarketResponses) -> Vec<MarketResponses> {
+        match response {
+            MarketResponses::OrderPartiallyFilled {
+                order_id,
+                fill_price,
+                executed_quantity,
+                ..
+            } => self.on_fill(&order_id, fill_price, executed_quantity, false),
+            MarketResponses::OrderFullyFilled {
+                order_id,
+                fill_price,
+                executed_quantity,
+                ..
+            } => self.on_fill(&order_id, fill_price, executed_quantity, true),
+            MarketResponses::OrderExpired { order_id, .. } => {
+                self.on_child_done(&order_id);
+                self.finish_if_done(ExecutionType::Expired)
+            }
+            MarketResponses::OrderCanceled { order_id, .. } => {
+                self.on_child_done(&order_id);
+                self.finish_if_done(ExecutionType::Canceled)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn on_child_done(&mut self, order_id: &str) {
+        if let Some(child) = self.children.get_mut(order_id) {
+            child.state = ChildState::Done;
+        }
+    }
+
+    fn on_fill(
+        &mut self,
+        order_id: &str,
+        fill_price: Decimal,
+        executed_quantity: Decimal,
+        child_filled: bool,
+    ) -> Vec<MarketResponses> {
+        if let Some(child) = self.children.get_mut(order_id) {
+            child.cumulative_quantity += executed_quantity;
+            if child_filled || child.cumulative_quantity >= child.quantity {
+                child.state = ChildState::Done;
+            }
+        }
+        self.cumulative_quantity += executed_quantity;
+
+        let parent = &self.parent;
+
+        // Only an order whose children all filled is filled; one with rejected children
+        // expires once the rest has filled.
+        if self.all_children_done() && self.cumulative_quantity >= parent.quantity {
+            self.finished = true;
+
+            return vec![MarketResponses::OrderFullyFilled {
+                order_id: parent.order_id.clone(),
+                algo_id: parent.algo_id.clone(),
+                symbol: parent.symbol.clone(),
+                execution_status: ExecutionType::Trade,
+                quantity: parent.quantity,
+                fill_price,
+                side: parent.side.clone(),
+                executed_quantity,
+                cumulative_quantity: self.cumulative_quantity,
+                leaves_quantity: Decimal::zero(),
+            }];
+        }
+
+        let mut responses = vec![MarketResponses::OrderPartiallyFilled {
+            order_id: parent.order_id.clone(),
+            algo_id: parent.algo_id.clone(),
+            symbol: parent.symbol.clone(),
+            execution_status: ExecutionType::Trade,
+            quantity: parent.quantity,
+            fill_price,
+            side: parent.side.clone(),
+            executed_quantity,
+            cumulative_quantity: self.cumulative_quantity,
+            leaves_quantity: self.leaves_quantity(),
+        }];
+        responses.extend(self.finish_if_done(ExecutionType::Expired));
+
+        responses
+    }
+
+    fn finish_if_done(&mut self, execution_type: ExecutionType) -> Vec<MarketResponses> {
+        if self.finished || !self.all_children_done() {
+            return Vec::new();
+        }
+        self.finished = true;
+
+        let parent = &self.parent;
+
+        let response = match execution_type {
+            ExecutionType::Canceled => MarketResponses::OrderCanceled {
+                order_id: parent.order_id.clone(),
+                algo_id: parent.algo_id.clone(),
+                symbol: parent.symbol.clone(),
+                execution_status: execution_type,
+                quantity: parent.quantity,
+                side: parent.side.clone(),
+                executed_quantity: Decimal::zero(),
+                cumulative_quantity: self.cumulative_quantity,
+                leaves_quantity: self.leaves_quantity(),
+            },
+            _ => MarketResponses::OrderExpired {
+                order_id: parent.order_id.clone(),
+                algo_id: parent.algo_id.clone(),
+                symbol: parent.symbol.clone(),
+                execution_status: ExecutionType::Expired,
+                quantity: parent.quantity,
+                side: parent.side.clone(),
+                executed_quantity: Decimal::zero(),
+                cumulative_quantity: self.cumulative_quantity,
+                leaves_quantity: self.leaves_quantity(),
+            },
+        };
+
+        vec![response]
+    }
+}
+
+#[derive(Default)]
+pub(super) struct OrderSlicer {
+    sliced_orders: HashMap<String, SlicedOrder>,
+    parents: HashMap<String, String>,
+}
+
+impl OrderSlicer {
+    pub fn register(&mut self, sliced_order: SlicedOrder) {
+        let parent_id = sliced_order.parent.order_id.clone();
+
+        for child_id in sliced_order.children.keys() {
+            self.parents.insert(child_id.clone(), parent_id.clone());
+        }
+        self.sliced_orders.insert(parent_id, sliced_order);
+    }
+
+    /// Translates a response for a child order into the responses of its logical
+    /// parent order. Responses for orders that were not sliced are passed through.
+    pub fn route(&mut self, response: MarketResponses) -> Vec<MarketResponses> {
+        let parent_id = match response
+            .order_id()
+            .and_then(|order_id| self.parents.get(order_id))
+        {
+            Some(parent_id) => parent_id.clone(),
+            None => return vec![response],
+        };
+
+        let Some(sliced_order) = self.sliced_orders.get_mut(&parent_id) else {
+            return vec![response];
+        };
+
+        let responses = sliced_order.on_response(response);
+
+        if sliced_order.finished {
+            if let Some(sliced_order) = self.sliced_orders.remove(&parent_id) {
+                for child_id in sliced_order.children.keys() {
+                    self.parents.remove(child_id);
+                }
+            }
+        }
+
+        responses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_types::{side::Side, time_in_force::TIF};
+
+    fn decimal(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn decimals(values: &[&str]) -> Vec<Decimal> {
+        values.iter().map(|value| decimal(value)).collect()
+    }
+
+    fn order(order_id: &str, quantity: &str) -> OrderRequest {
+        OrderRequest {
+            order_id: order_id.to_owned(),
+            algo_id: "algo".to_owned(),
+            symbol: "BTCUSDT".to_owned(),
+            price: Decimal::from(100),
+            quantity: decimal(quantity),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            time_in_force: TIF::GTC,
+        }
+    }
+
+    fn ack(order_id: &str, quantity: &str) -> MarketResponses {
+        MarketResponses::CreateOrderAck {
+            order_id: order_id.to_owned(),
+            algo_id: "algo".to_owned(),
+            symbol: "BTCUSDT".to_owned(),
+            execution_status: ExecutionType::New,
+            order_quantity: decimal(quantity),
+            price: Decimal::from(100),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            time_in_force: TIF::GTC,
+        }
+    }
+
+    fn partial_fill(order_id: &str, executed_quantity: &str) -> MarketResponses {
+        MarketResponses::OrderPartiallyFilled {
+            order_id: order_id.to_owned(),
+            algo_id: "algo".to_owned(),
+            symbol: "BTCUSDT".to_owned(),
+            execution_status: ExecutionType::Trade,
+            quantity: Decimal::ZERO,
+            fill_price: Decimal::from(100),
+            side: Side::Buy,
+            executed_quantity: decimal(executed_quantity),
+            cumulative_quantity: Decimal::ZERO,
+            leaves_quantity: Decimal::ZERO,
+        }
+    }
+
+    fn full_fill(order_id: &str, executed_quantity: &str) -> MarketResponses {
+        MarketResponses::OrderFullyFilled {
+            order_id: order_id.to_owned(),
+            algo_id: "algo".to_owned(),
+            symbol: "BTCUSDT".to_owned(),
+            execution_status: ExecutionType::Trade,
+            quantity: Decimal::ZERO,
+            fill_price: Decimal::from(100),
+            side: Side::Buy,
+            executed_quantity: decimal(executed_quantity),
+            cumulative_quantity: Decimal::ZERO,
+            leaves_quantity: Decimal::ZERO,
+        }
+    }
+
+    fn rejected(order_id: &str, rejection_reason: &str) -> MarketResponses {
+        MarketResponses::OrderRejected {
+            order_id: order_id.to_owned(),
+            algo_id: "algo".to_owned(),
+            symbol: "BTCUSDT".to_owned(),
+            execution_status: ExecutionType::Rejected,
+            order_quantity: Decimal::ZERO,
+            price: Decimal::from(100),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            rejection_reason: rejection_reason.to_owned(),
+            time_in_force: TIF::GTC,
+        }
+    }
+
+    fn expired(order_id: &str) -> MarketResponses {
+        MarketResponses::OrderExpired {
+            order_id: order_id.to_owned(),
+            algo_id: "algo".to_owned(),
+            symbol: "BTCUSDT".to_owned(),
+            execution_status: ExecutionType::Expired,
+            quantity: Decimal::ZERO,
+            side: Side::Buy,
+            executed_quantity: Decimal::ZERO,
+            cumulative_quantity: Decimal::ZERO,
+            leaves_quantity: Decimal::ZERO,
+        }
+    }
+
+    fn canceled(order_id: &str) -> MarketResponses {
+        MarketResponses::OrderCanceled {
+            order_id: order_id.to_owned(),
+            algo_id: "algo".to_owned(),
+            symbol: "BTCUSDT".to_owned(),
+            execution_status: ExecutionType::Canceled,
+            quantity: Decimal::ZERO,
+            side: Side::Buy,
+            executed_quantity: Decimal::ZERO,
+            cumulative_quantity: Decimal::ZERO,
+            leaves_quantity: Decimal::ZERO,
+        }
+    }
+
+    /// The kind of a parent response with its order id and quantities.
+    fn describe(response: &MarketResponses) -> String {
+        match response {
+            MarketResponses::CreateOrderAck {
+                order_id,
+                order_quantity,
+                ..
+            } => format!("ack {} {}", order_id, order_quantity),
+            MarketResponses::OrderPartiallyFilled {
+                order_id,
+                cumulative_quantity,
+                leaves_quantity,
+                ..
+            } => format!(
+                "partial {} {} {}",
+                order_id, cumulative_quantity, leaves_quantity
+            ),
+            MarketResponses::OrderFullyFilled {
+                order_id,
+                cumulative_quantity,
+                ..
+            } => format!("filled {} {}", order_id, cumulative_quantity),
+            MarketResponses::OrderExpired {
+                order_id,
+                cumulative_quantity,
+                leaves_quantity,
+                ..
+            } => format!(
+                "expired {} {} {}",
+                order_id, cumulative_quantity, leaves_quantity
+            ),
+            MarketResponses::OrderCanceled {
+                order_id,
+                cumulative_quantity,
+                leaves_quantity,
+                ..
+            } => format!(
+                "canceled {} {} {}",
+                order_id, cumulative_quantity, leaves_quantity
+            ),
+            MarketResponses::OrderRejected {
+                order_id,
+                order_quantity,
+                rejection_reason,
+                ..
+            } => format!(
+                "rejected {} {} {}",
+                order_id, order_quantity, rejection_reason
+            ),
+            MarketResponses::OrderPartiallyRejected {
+                order_id,
+                rejected_quantity,
+                leaves_quantity,
+                rejection_reason,
+                ..
+            } => format!(
+                "partially rejected {} {} {} {}",
+                order_id, rejected_quantity, leaves_quantity, rejection_reason
+            ),
+            response => format!("{}", response),
+        }
+    }
+
+    fn route(order_slicer: &mut OrderSlicer, response: MarketResponses) -> Vec<String> {
+        order_slicer.route(response).iter().map(describe).collect()
+    }
+
+    fn sliced(children: &[(&str, &str)]) -> OrderSlicer {
+        let parent = order("parent", "10");
+        let children: Vec<OrderRequest> = children
+            .iter()
+            .map(|(order_id, quantity)| parent.child(*order_id, decimal(quantity)))
+            .collect();
+
+        let mut order_slicer = OrderSlicer::default();
+        order_slicer.register(SlicedOrder::new(parent, &children));
+        order_slicer
+    }
+
+    #[test]
+    fn exact_multiples_split_into_max_quantity_slices() {
+        assert_eq!(
+            split_quantity(decimal("15"), decimal("5"), Some(decimal("0.1"))),
+            decimals(&["5", "5", "5"])
+        );
+    }
+
+    #[test]
+    fn remainders_are_spread_over_step_aligned_slices() {
+        assert_eq!(
+            split_quantity(decimal("10.3"), decimal("5"), Some(decimal("0.1"))),
+            decimals(&["3.5", "3.4", "3.4"])
+        );
+    }
+
+    #[test]
+    fn children_below_the_minimum_quantity_reject_the_order() {
+        let limits = |min_quantity| OrderLimits {
+            max_quantity: Some(decimal("5")),
+            min_quantity: Some(decimal(min_quantity)),
+            step_size: Some(decimal("0.1")),
+            ..OrderLimits::default()
+        };
+
+        // Greedily this would leave a 0.5 remainder below the minimum; equal slices do not.
+        assert_eq!(
+            limits("1").split(&order("order", "10.5")),
+            Ok(decimals(&["3.5", "3.5", "3.5"]))
+        );
+        assert!(limits("4").split(&order("order", "10.5")).is_err());
+        assert!(limits("1").split(&order("order", "10.55")).is_err());
+    }
+
+    #[test]
+    fn children_below_the_minimum_notional_reject_the_order() {
+        let limits = OrderLimits {
+            max_quantity: Some(decimal("5")),
+            min_notional: Some(decimal("400")),
+            ..OrderLimits::default()
+        };
+
+        assert!(limits.split(&order("order", "10")).is_ok());
+        assert!(limits.split(&order("order", "5.2")).is_err());
+    }
+
+    #[test]
+    fn child_fills_and_expiry_combine_into_the_parent() {
+        let mut order_slicer = sliced(&[("first", "5"), ("second", "5")]);
+
+        assert!(route(&mut order_slicer, ack("first", "5")).is_empty());
+        assert_eq!(
+            route(&mut order_slicer, ack("second", "5")),
+            ["ack parent 10"]
+        );
+        assert_eq!(
+            route(&mut order_slicer, partial_fill("first", "2")),
+            ["partial parent 2 8"]
+        );
+        assert!(route(&mut order_slicer, expired("first")).is_empty());
+        assert_eq!(
+            route(&mut order_slicer, expired("second")),
+            ["expired parent 2 8"]
+        );
+    }
+
+    #[test]
+    fn executions_before_the_last_ack_are_deferred_and_cancels_combine() {
+        let mut order_slicer = sliced(&[("first", "5"), ("second", "5")]);
+
+        assert!(route(&mut order_slicer, ack("first", "5")).is_empty());
+        assert!(route(&mut order_slicer, partial_fill("first", "1")).is_empty());
+        assert_eq!(
+            route(&mut order_slicer, ack("second", "5")),
+            ["ack parent 10", "partial parent 1 9"]
+        );
+        assert!(route(&mut order_slicer, canceled("first")).is_empty());
+        assert_eq!(
+            route(&mut order_slicer, canceled("second")),
+            ["canceled parent 1 9"]
+        );
+
+        // The parent is finished, so later reports pass through untouched.
+        assert_eq!(
+            route(&mut order_slicer, canceled("second")),
+            ["canceled second 0 0"]
+        );
+    }
+
+    #[test]
+    fn a_rejected_child_partially_rejects_the_parent_which_never_fills() {
+        let mut order_slicer = sliced(&[("first", "5"), ("second", "5")]);
+
+        assert!(route(&mut order_slicer, ack("first", "5")).is_empty());
+        assert_eq!(
+            route(
+                &mut order_slicer,
+                rejected("second", "insufficient balance")
+            ),
+            [
+                "ack parent 10",
+                "partially rejected parent 5 5 insufficient balance"
+            ]
+        );
+        assert_eq!(
+            route(&mut order_slicer, full_fill("first", "5")),
+            ["partial parent 5 0", "expired parent 5 0"]
+        );
+    }
+
+    #[test]
+    fn an_order_whose_children_are_all_rejected_is_rejected_with_their_reason() {
+        let mut order_slicer = sliced(&[("first", "5"), ("second", "5")]);
+
+        assert!(route(&mut order_slicer, rejected("first", "insufficient balance")).is_empty());
+        assert_eq!(
+            route(
+                &mut order_slicer,
+                rejected("second", "insufficient balance")
+            ),
+            ["rejected parent 10 insufficient balance"]
+        );
+    }
+
+    #[test]
+    fn children_that_all_fill_fill_the_parent() {
+        let mut order_slicer = sliced(&[("first", "5"), ("second", "5")]);
+
+        route(&mut order_slicer, ack("first", "5"));
+        route(&mut order_slicer, ack("second", "5"));
+        assert_eq!(
+            route(&mut order_slicer, full_fill("first", "5")),
+            ["partial parent 5 5"]
+        );
+        assert_eq!(
+            route(&mut order_slicer, full_fill("second", "5")),
+            ["filled parent 10"]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use super::messages::{
+    execution_type::ExecutionType, list_status::ListStatus, market_messages::MarketMessages,
+    market_responses::MarketResponses, order_request::OrderRequest,
+};
+use crate::common_types::symbol_status::SymbolStatus;
+use rust_decimal::prelude::Zero;
+use rust_decimal::Decimal;
+use tokio::sync::mpsc;
+
+/// Local stand-in for the exchange: every order is acknowledged and fully filled at
+/// its limit price, so algorithms can be exercised end to end without an account.
+pub(super) struct SimulatedMarketActor {
+    receiver: mpsc::Receiver<MarketMessages>,
+}
+
+impl SimulatedMarketActor {
+    pub fn new(receiver: mpsc::Receiver<MarketMessages>) -> Self {
+        Self { receiver }
+    }
+
+    fn handle(&mut self, market_message: MarketMessages) {
+        match market_message {
+            MarketMessages::GetSymbolInformation {
+                symbol: _,
+                algo_id,
+                sender,
+            } => {
+                sender
+                    .try_send(MarketResponses::SymbolInformation {
+                        algo_id,
+                        min_quantity: None,
+                        max_quantity: None,
+                        lot_size: None,
+                        min_price: None,
+                        max_price: None,
+                        tick_size: None,
+                        min_amount: None,
+                        status: Some(SymbolStatus::Trading),
+                    })
+                    .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+            }
+
+            MarketMessages::StopWatchingSymbol { .. } => {}
+
+            MarketMessages::CreateOrder {
+                symbol,
+                price,
+                quantity,
+                side,
+                order_type,
+                time_in_force,
+                sender,
+                order_id,
+                algo_id,
+            } => {
+                Self::execute_order(
+                    OrderRequest {
+                        order_id,
+                        algo_id,
+                        symbol,
+                        price,
+                        quantity,
+                        side,
+                        order_type,
+                        time_in_force,
+                    },
+                    &sender,
+                );
+            }
+
+            MarketMessages::CreateOrders { orders, sender } => {
+                for order in orders {
+                    Self::execute_order(order, &sender);
+                }
+            }
+
+            MarketMessages::CreateOcoOrder { order, sender } => {
+                sender
+                    .try_send(MarketResponses::OcoListStatus {
+                        list_id: order.list_id,
+                        algo_id: order.algo_id,
+                        symbol: order.symbol,
+                        list_status: ListStatus::Executing,
+                        limit_order_id: order.limit_order_id,
+                        stop_order_id: order.stop_order_id,
+                    })
+                    .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+            }
+        }
+    }
+
+    fn execute_order(order: OrderRequest, sender: &mpsc::Sender<MarketResponses>) {
+        println!("Simulated market executing {}", order);
+
+        let responses = [
+            MarketResponses::CreateOrderAck {
+                order_id: order.order_id.clone(),
+                algo_id: order.algo_id.clone(),
+                symbol: order.symbol.clone(),
+                execution_status: ExecutionType::New,
+                order_quantity: order.quantity,
+                price: order.price,
+                side: order.side.clone(),
+                order_type: order.order_type.clone(),
+                time_in_force: order.time_in_force.clone(),
+            },
+            MarketResponses::OrderFullyFilled {
+                order_id: order.order_id,
+                algo_id: order.algo_id,
+                symbol: order.symbol,
+                execution_status: ExecutionType::Trade,
+                quantity: order.quantity,
+                fill_price: order.price,
+                side: order.side,
+                executed_quantity: order.quantity,
+                cumulative_quantity: order.quantity,
+                leaves_quantity: Decimal::zero(),
+            },
+        ];
+
+        for response in responses {
+            sender
+                .try_send(response)
+                .unwrap_or_else(|err| eprintln!("Failed to send message: {:?}", err));
+        }
+    }
+}
+
+pub(super) async fn run_simulated_actor(mut actor: SimulatedMarketActor) {
+    while let Some(msg) = actor.receiver.recv().await {
+        actor.handle(msg);
+    }
+}
@@ -0,0 +1,261 @@
+//! Runs the feed against the mock exchange playing config/mock_scenarios/l2_gap_and_reconnect.toml
+//! and checks what the subscribers of a `FeedHandle` receive.
+
+use rust_decimal::Decimal;
+use sniper_algorithm::common_types::tracked_sender::TrackedSender;
+use sniper_algorithm::config::{EndpointsConfig, Environment, FeedConfig, SimulatorConfig};
+use sniper_algorithm::feed::feed_handle::FeedHandle;
+use sniper_algorithm::feed::messages::feed_status::FeedState;
+use sniper_algorithm::feed::messages::messages::{FeedUpdate, L2UpdateMode};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+const SCENARIO: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/config/mock_scenarios/l2_gap_and_reconnect.toml"
+);
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+struct MockExchange {
+    process: Child,
+    port: u16,
+}
+
+impl MockExchange {
+    fn start() -> Self {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("no free port")
+            .port();
+
+        let process = Command::new(env!("CARGO_BIN_EXE_mock_exchange"))
+            .arg(SCENARIO)
+            .arg(port.to_string())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("failed to start the mock exchange");
+        let mock_exchange = MockExchange { process, port };
+
+        let started = std::time::Instant::now();
+        while TcpStream::connect(("127.0.0.1", port)).is_err() {
+            assert!(
+                started.elapsed() < TIMEOUT,
+                "the mock exchange is not listening"
+            );
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        mock_exchange
+    }
+
+    fn feed_config(&self) -> FeedConfig {
+        FeedConfig {
+            endpoints: Some(EndpointsConfig {
+                rest_api: format!("http://127.0.0.1:{}", self.port),
+                websocket: format!("ws://127.0.0.1:{}/ws", self.port),
+            }),
+            ..FeedConfig::default()
+        }
+    }
+}
+
+impl Drop for MockExchange {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+fn subscriber(name: &str) -> (TrackedSender<FeedUpdate>, mpsc::Receiver<FeedUpdate>) {
+    let (sender, receiver) = mpsc::channel(1000);
+    (TrackedSender::new(sender, name.to_owned()), receiver)
+}
+
+/// Skips updates until `expected` matches one, failing once the deadline has passed.
+async fn receive_until<T>(
+    receiver: &mut mpsc::Receiver<FeedUpdate>,
+    deadline: Instant,
+    description: &str,
+    mut expected: impl FnMut(&FeedUpdate) -> Option<T>,
+) -> T {
+    loop {
+        match tokio::time::timeout_at(deadline, receiver.recv()).await {
+            Ok(Some(update)) => {
+                if let Some(found) = expected(&update) {
+                    return found;
+                }
+            }
+            Ok(None) => panic!("feed closed while waiting for {}", description),
+            Err(_) => panic!("timed out waiting for {}", description),
+        }
+    }
+}
+
+fn price(price: &str) -> Decimal {
+    price.parse().unwrap()
+}
+
+fn is_status(update: &FeedUpdate, state: FeedState) -> Option<()> {
+    match update {
+        FeedUpdate::Status(_, status) if status.state == state => Some(()),
+        _ => None,
+    }
+}
+
+async fn check_l1(mut receiver: mpsc::Receiver<FeedUpdate>, deadline: Instant) {
+    let first = receive_until(
+        &mut receiver,
+        deadline,
+        "the first L1 update",
+        |update| match update {
+            FeedUpdate::L1Update(_, l1_data) => Some(l1_data.clone()),
+            _ => None,
+        },
+    )
+    .await;
+    assert_eq!(&*first.symbol, "btcusdt");
+    assert_eq!(first.best_bid_level.price, price("105000.00"));
+    assert_eq!(first.best_bid_level.quantity, price("1.5"));
+    assert_eq!(first.best_ask_level.price, price("105001.00"));
+    assert_eq!(first.best_ask_level.quantity, price("1.0"));
+
+    receive_until(&mut receiver, deadline, "L1 reconnecting", |update| {
+        is_status(update, FeedState::Reconnecting)
+    })
+    .await;
+
+    receive_until(
+        &mut receiver,
+        deadline,
+        "L1 after reconnecting",
+        |update| match update {
+            FeedUpdate::L1Update(_, l1_data)
+                if l1_data.best_ask_level.price == price("105002.00") =>
+            {
+                Some(())
+            }
+            _ => None,
+        },
+    )
+    .await;
+}
+
+async fn check_l2(mut receiver: mpsc::Receiver<FeedUpdate>, deadline: Instant) {
+    receive_until(
+        &mut receiver,
+        deadline,
+        "the updated book",
+        |update| match update {
+            FeedUpdate::L2Update(_, l2_data)
+                if l2_data.bid_side_levels[0].price == price("105000.00")
+                    && l2_data.bid_side_levels[0].quantity == price("2.5") =>
+            {
+                Some(())
+            }
+            _ => None,
+        },
+    )
+    .await;
+
+    receive_until(&mut receiver, deadline, "the gap", |update| {
+        is_status(update, FeedState::Invalid)
+    })
+    .await;
+    receive_until(&mut receiver, deadline, "the resync", |update| {
+        is_status(update, FeedState::Recovered)
+    })
+    .await;
+
+    receive_until(
+        &mut receiver,
+        deadline,
+        "the resynced book",
+        |update| match update {
+            FeedUpdate::L2Update(_, l2_data)
+                if l2_data.ask_side_levels[0].price == price("105002.00")
+                    && l2_data.ask_side_levels[0].quantity == price("2.0") =>
+            {
+                Some(())
+            }
+            _ => None,
+        },
+    )
+    .await;
+
+    receive_until(
+        &mut receiver,
+        deadline,
+        "the book invalidated by the reconnect",
+        |update| is_status(update, FeedState::Invalid),
+    )
+    .await;
+}
+
+async fn check_trades(mut receiver: mpsc::Receiver<FeedUpdate>, deadline: Instant) {
+    let trade = receive_until(
+        &mut receiver,
+        deadline,
+        "the trade",
+        |update| match update {
+            FeedUpdate::Trade(_, trade) => Some(trade.clone()),
+            _ => None,
+        },
+    )
+    .await;
+
+    assert_eq!(&*trade.symbol, "btcusdt");
+    assert_eq!(trade.price, price("105001.00"));
+    assert_eq!(trade.quantity, price("0.25"));
+}
+
+/// The mock serves no ticker stream, so the ticker is built from its trades.
+async fn check_ticker(mut receiver: mpsc::Receiver<FeedUpdate>, deadline: Instant) {
+    let ticker = receive_until(
+        &mut receiver,
+        deadline,
+        "the ticker",
+        |update| match update {
+            FeedUpdate::Ticker(_, ticker) => Some(ticker.clone()),
+            _ => None,
+        },
+    )
+    .await;
+
+    assert_eq!(&*ticker.symbol, "btcusdt");
+    assert_eq!(ticker.last_price, price("105001.00"));
+    assert_eq!(ticker.volume, price("0.25"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn feed_follows_the_mock_exchange_scenario() {
+    let mock_exchange = MockExchange::start();
+    let (feed, _feed_task) = FeedHandle::new(
+        Environment::Testnet,
+        SimulatorConfig::default(),
+        mock_exchange.feed_config(),
+    );
+
+    let (l1_a, l1_a_updates) = subscriber("l1-a");
+    let (l1_b, l1_b_updates) = subscriber("l1-b");
+    let (l2, l2_updates) = subscriber("l2");
+    let (trades, trade_updates) = subscriber("trades");
+    let (ticker, ticker_updates) = subscriber("ticker");
+
+    feed.subscribe_to_l1("algo-a", "btc", "usdt", l1_a);
+    feed.subscribe_to_l1("algo-b", "btc", "usdt", l1_b);
+    feed.subscribe_to_l2("algo-l2", "btc", "usdt", 5, L2UpdateMode::Snapshot, l2);
+    feed.subscribe_to_trades("algo-trades", "btc", "usdt", trades);
+    feed.subscribe_to_ticker("algo-ticker", "btc", "usdt", ticker);
+
+    let deadline = Instant::now() + TIMEOUT;
+    tokio::join!(
+        check_l1(l1_a_updates, deadline),
+        check_l1(l1_b_updates, deadline),
+        check_l2(l2_updates, deadline),
+        check_trades(trade_updates, deadline),
+        check_ticker(ticker_updates, deadline),
+    );
+}